@@ -2,6 +2,9 @@ import { ASSIGNED_ROOM_PATTERN, QUEUE_TICKET_PATTERN, QUEUE_TOKEN_PATTERN } from
 
 const encoder = new TextEncoder();
 const MAX_SECRET_BYTES = 256;
+/** PKCS#8 wrapper for a raw 32-byte Ed25519 private key seed. */
+const ED25519_PKCS8_PREFIX = "302e020100300506032b657004220420";
+const START_SIGNING_SEED_PATTERN = /^[0-9a-f]{64}$/;
 
 /** Stable, delimiter-safe signed bytes. Never include the token in logs/errors. */
 export function canonicalAssignment({ room, mode, capacity, ticket, expiresAt }) {
@@ -49,6 +52,31 @@ export async function consumeAssignment(secret, fields, token, now, storage, cry
   return { ok: true };
 }
 
+/**
+ * Canonical bytes of an immutable v3 start. The Rust client rebuilds exactly
+ * this text from its lobby getters before installing a session, so it covers
+ * every field the client reads, including each entry's self-reported
+ * simulation fingerprint, cosmetics and ratings.
+ */
+export function canonicalLobbyStart({ mode, capacity, matchGeneration = 0, epoch, round, seed, inputDelay, roster, resume = "" }) {
+  const entries = [...roster]
+    .sort((a, b) => a.index - b.index)
+    .map((entry) => [entry.playerId, entry.score, entry.simulation ?? "", entry.cosmetics ?? "", entry.ratings ?? ""].join(":"))
+    .join(",");
+  return `v3-start\n${mode}\n${capacity}\n${matchGeneration}\n${epoch}\n${round}\n${seed}\n${inputDelay}\n${entries}\n${resume}`;
+}
+
+/** Resolves null when no valid hex Ed25519 seed is configured. */
+export async function importStartSigningKey(seedHex, cryptoImpl = globalThis.crypto) {
+  if (typeof seedHex !== "string" || !START_SIGNING_SEED_PATTERN.test(seedHex)) return null;
+  return cryptoImpl.subtle.importKey("pkcs8", fromHex(`${ED25519_PKCS8_PREFIX}${seedHex}`), { name: "Ed25519" }, false, ["sign"]);
+}
+
+export async function signLobbyStart(key, start, cryptoImpl = globalThis.crypto) {
+  const bytes = await cryptoImpl.subtle.sign({ name: "Ed25519" }, key, encoder.encode(canonicalLobbyStart(start)));
+  return hex(new Uint8Array(bytes));
+}
+
 function validSecret(secret) {
   if (typeof secret !== "string") return false;
   const length = encoder.encode(secret).byteLength;
@@ -7,10 +7,10 @@ import {
 import { rotateReconnectIdentity } from "../vendor/cloudflare-game-common/lifecycle.js";
 import {
   MAX_LOBBY_SOCKETS, MAX_MESSAGE_BYTES, RECONNECT_GRACE_MS,
-  applyMessageRateLimit, parseEpochClientMessage, parseEpochLobbyQuery, randomHex,
+  applyMessageRateLimit, lobbyInputDelay, parseEpochClientMessage, parseEpochLobbyQuery, randomHex,
 } from "./protocol.js";
 import { generateIceServers } from "./turn.js";
-import { consumeAssignment } from "./assignment.js";
+import { consumeAssignment, importStartSigningKey, signLobbyStart } from "./assignment.js";
 
 const KEY = "lobby-v3";
 
@@ -58,6 +58,7 @@ export class EpochLobby extends DurableObject {
     this.state.reconnectBatchEpoch ??= null;
     this.state.reconnectBatchRound ??= null;
     this.state.boundaryDepartures ??= {};
+    this.state.resumeOffer ??= null;
     if (this.state.mode !== parsed.value.mode || this.state.capacity !== parsed.value.capacity) return text("Lobby configuration mismatch", 409);
     if (!assignmentAdmitted) {
       const admission = await this.admitAssignment(parsed.value, room, now);
@@ -110,7 +111,17 @@ export class EpochLobby extends DurableObject {
     // persisted short batch lets simultaneous reloads converge on one changed
     // epoch that every old client can stage and every empty client can install.
     if (parsed.value.playerId && this.isActive(player.playerId)) {
+      const active = this.state.active;
+      const resume = parsed.value.resume;
+      if (resume && resume.epoch === active.epoch && resume.round === active.round && this.state.reconnectBatchDeadline == null) {
+        // The control socket dropped but this client still holds the round's
+        // GGRS session; its peers renegotiate through ordinary signals.
+        this.sendStatus(server, player);
+        await this.persist();
+        return new Response(null, { status: 101, webSocket: client });
+      }
       markActiveReconnect(this.state, player.playerId, now);
+      this.requestResumeSnapshot(player.playerId);
       this.sendStatus(server, player, "reconnecting");
       const rollover = await this.processReconnectRollover(now);
       if (!rollover) await this.persist();
@@ -127,7 +138,7 @@ export class EpochLobby extends DurableObject {
       } else if (player.ready) {
         const start = startNextEpoch(this.state, randomHex(), "reconnect_ready");
         await this.persist();
-        if (start) this.broadcastStart(start); else this.sendStatus(server, player);
+        if (start) await this.broadcastStart(start); else this.sendStatus(server, player);
       } else {
         this.sendStatus(server, player);
         if (!rollover) await this.persist();
@@ -208,7 +219,7 @@ export class EpochLobby extends DurableObject {
         // opponents are sent to menu and are never silently queued.
         this.send(socket, { type: "requeue", status: "waiting" });
         const start = startNextEpoch(this.state, randomHex(), "explicit_requeue");
-        if (start) this.broadcastStart(start); else this.sendStatus(socket, player);
+        if (start) await this.broadcastStart(start); else this.sendStatus(socket, player);
       }
       return;
     }
@@ -222,24 +233,48 @@ export class EpochLobby extends DurableObject {
       if (result.type === "pending") this.broadcast({ type: "rematch_pending", generation: result.generation, nonce: result.nonce, requestedBy: result.requestedBy, deadline: result.deadline, accepted: result.accepted, required: result.required });
       else if (result.type === "accepted") {
         this.broadcast({ type: "rematch_accepted", generation: result.generation, nonce: result.nonce });
-        if (!result.duplicate && result.next) this.broadcastStart(result.next);
+        if (!result.duplicate && result.next) await this.broadcastStart(result.next);
       } else if (result.type === "denied") this.broadcast({ type: "rematch_denied", generation: result.generation, nonce: result.nonce, reason: result.reason, destination: "main_menu" });
       else this.sendError(socket, result.code);
       return;
     }
     if (message.type === "profile") {
       player.profile = { name: message.name, paletteId: message.paletteId, cosmeticId: message.cosmeticId };
+      // Kept beside the profile so the vendored lifecycle clones stay unchanged.
+      player.simulation = message.simulation ?? null;
+      player.cosmetics = message.cosmetics ?? null;
+      player.ratings = message.ratings ?? null;
       await this.persist();
       this.send(socket, { type: "profile_accepted" });
       return;
     }
+    if (message.type === "latency") {
+      // Peer RTT samples only shape the next immutable start. Stale rounds and
+      // non-roster senders are ignored; measurement must never fail a lobby.
+      const active = this.state.active;
+      if (!active || active.epoch !== message.epoch || active.round !== message.round || !this.isActive(player.playerId)) return;
+      player.rttMs = message.rttMs;
+      await this.persist();
+      return;
+    }
+    if (message.type === "resume_snapshot") {
+      // The first roster snapshot for the round under a pending reload batch
+      // wins. Late, stale or unsolicited snapshots are ignored.
+      const active = this.state.active;
+      if (!active || this.state.reconnectBatchDeadline == null || active.epoch !== message.epoch || active.round !== message.round ||
+          this.state.reconnectBatchEpoch !== active.epoch || this.state.reconnectBatchRound !== active.round ||
+          !this.isActive(player.playerId) || this.state.resumeOffer?.epoch === active.epoch && this.state.resumeOffer?.round === active.round) return;
+      this.state.resumeOffer = { epoch: active.epoch, round: active.round, seed: active.seed, snapshot: message.snapshot };
+      await this.persist();
+      return;
+    }
     if (message.type === "ready") {
       player.ready = true;
       // startNextEpoch explicitly refuses to replace state.active. Ready from an
       // active member or a mid-round waiter applies only after commit/abort.
       const start = startNextEpoch(this.state, randomHex(), "roster_ready");
       await this.persist();
-      if (start) this.broadcastStart(start); else this.sendStatus(socket, player);
+      if (start) await this.broadcastStart(start); else this.sendStatus(socket, player);
       return;
     }
     if (message.type === "signal") {
@@ -264,11 +299,11 @@ export class EpochLobby extends DurableObject {
         this.broadcast(commitMessage(result));
         this.finishBoundary(result);
         if (result.matchOver) this.broadcast({ type: "match_over", generation: result.matchGeneration, rematchGeneration: result.matchGeneration + 1 });
-        if (result.next) this.broadcastStart(result.next);
+        if (result.next) await this.broadcastStart(result.next);
       } else if (result.type === "abort") {
         this.broadcast(abortMessage(result));
         this.finishBoundary(result);
-        if (result.next) this.broadcastStart(result.next);
+        if (result.next) await this.broadcastStart(result.next);
       } else this.sendError(socket, result.code);
     }
   }
@@ -333,12 +368,20 @@ export class EpochLobby extends DurableObject {
         }
       }
     }
+    const offer = this.state.resumeOffer;
+    this.state.resumeOffer = null;
     const result = rolloverActiveReconnect(this.state, now, randomHex());
     if (!result) return null;
+    if (result.type === "rollover" && offer?.epoch === result.previousEpoch && offer.round === result.previousRound) {
+      // A survivor's confirmed-frame snapshot continues the interrupted round:
+      // the changed epoch keeps its seed so the same map is regenerated.
+      result.next.seed = offer.seed;
+      result.next.resume = offer.snapshot;
+    }
     // State is durable before any peer observes the changed epoch. Repeated
     // alarms/messages see a cleared deadline and cannot increment it again.
     await this.persist();
-    if (result.type === "rollover") this.broadcastStart(result.next);
+    if (result.type === "rollover") await this.broadcastStart(result.next);
     else if (result.type === "waiting") {
       for (const entry of this.state.active?.roster ?? []) {
         const socket = this.socket(entry.playerId);
@@ -377,10 +420,18 @@ export class EpochLobby extends DurableObject {
   }
 
   startMessage(active) {
-    return { type: "start", protocol: 3, epoch: active.epoch, round: active.round, matchGeneration: this.state.matchGeneration, mode: this.state.mode, capacity: this.state.capacity, seed: active.seed, roster: active.roster };
+    return { type: "start", protocol: 3, epoch: active.epoch, round: active.round, matchGeneration: this.state.matchGeneration, mode: this.state.mode, capacity: this.state.capacity, seed: active.seed, roster: active.roster.map((entry) => this.withSimulation(entry)), inputDelay: lobbyInputDelay(this.state.players, active.roster), ...(active.resume ? { resume: active.resume } : {}) };
   }
-  broadcastStart(active) {
+  withSimulation(entry) {
+    const { simulation, cosmetics, ratings } = this.state.players[entry.playerId] ?? {};
+    return { ...entry, ...(simulation ? { simulation } : {}), ...(cosmetics ? { cosmetics } : {}), ...(ratings ? { ratings } : {}) };
+  }
+  async broadcastStart(active) {
     const message = this.startMessage(active);
+    // Clients built with the matching public key refuse unsigned starts.
+    this.startSigningKey ??= importStartSigningKey(this.env.LOBBY_START_SIGNING_KEY);
+    const key = await this.startSigningKey;
+    if (key) message.signature = await signLobbyStart(key, message);
     // Starts are recipient-safe: waiters and boundary departures must not be
     // handed a bootstrap whose immutable roster excludes their identity.
     for (const entry of active.roster) {
@@ -388,6 +439,14 @@ export class EpochLobby extends DurableObject {
       if (socket) this.send(socket, message);
     }
   }
+  requestResumeSnapshot(playerId) {
+    const active = this.state.active;
+    for (const entry of active.roster) {
+      if (entry.playerId === playerId) continue;
+      const socket = this.socket(entry.playerId);
+      if (socket) this.send(socket, { type: "resume_request", epoch: active.epoch, round: active.round });
+    }
+  }
   sendStatus(socket, player, status = this.state.active ? "active" : "waiting") {
     const reconnectDeadline = status === "reconnecting"
       ? (this.state.reconnectBatchDeadline ?? this.activeReconnectGraceDeadline())
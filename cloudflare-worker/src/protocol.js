@@ -9,6 +9,17 @@ export const MAX_MESSAGE_BYTES = 16 * 1024;
 export const MAX_MESSAGES_PER_SECOND = 60;
 export const RECONNECT_GRACE_MS = 30_000;
 export const MAX_LOBBY_SOCKETS = 32;
+export const DEFAULT_INPUT_DELAY = 2;
+export const MAX_INPUT_DELAY = 4;
+export const MAX_REPORTED_RTT_MS = 5_000;
+export const RESUME_SNAPSHOT_PATTERN = /^[0-9a-z:;.,]{1,2048}$/;
+/** Client simulation fingerprint: version, gameplay constants, rollback registrations. */
+export const SIMULATION_PATTERN = /^[0-9a-f]{16}$/;
+/** Equipped cosmetic item ids, one per slot: `hat.trail.bullet`. */
+export const COSMETICS_PATTERN = /^\d{1,4}\.\d{1,4}\.\d{1,4}$/;
+/** Self-reported client skill ratings: `duel.deathmatch`. */
+export const RATINGS_PATTERN = /^\d{1,4}\.\d{1,4}$/;
+const ROLLBACK_FRAME_MS = 1000 / 60;
 
 const ROOM_PATTERN = /^[A-Za-z0-9_-]{1,64}$/;
 
@@ -55,8 +66,26 @@ export function parseEpochLobbyQuery(searchParams) {
     for (const key of assignmentKeys) copy.delete(key);
   }
 
+  // A resumed control socket names the round it still holds transport for, so
+  // the Worker can keep that round instead of batching a changed epoch.
+  const resumeKeys = ["resumeEpoch", "resumeRound"];
+  const resumePresent = resumeKeys.filter((key) => copy.has(key));
+  let resume = null;
+  if (resumePresent.length !== 0) {
+    if (resumePresent.length !== resumeKeys.length || resumeKeys.some((key) => copy.getAll(key).length !== 1)) {
+      return fail("resumeEpoch and resumeRound must be supplied exactly once together");
+    }
+    if (!copy.has("playerId")) return fail("resume requires a reconnect identity");
+    const [epoch, round] = resumeKeys.map((key) => copy.get(key));
+    if (![epoch, round].every((value) => /^[0-9]{1,10}$/.test(value) && Number(value) <= 0xffff_ffff)) {
+      return fail("invalid resume round");
+    }
+    resume = { epoch: Number(epoch), round: Number(round) };
+    for (const key of resumeKeys) copy.delete(key);
+  }
+
   const lobby = parseLobbyQuery(copy);
-  return lobby.ok ? { ok: true, value: { ...lobby.value, assignment } } : lobby;
+  return lobby.ok ? { ok: true, value: { ...lobby.value, assignment, resume } } : lobby;
 }
 
 export function parseQueueQuery(searchParams) {
@@ -137,8 +166,11 @@ export function parseEpochClientMessage(text) {
     if (!Number.isSafeInteger(message.generation) || message.generation < 1 || typeof message.nonce !== "string" || !PLAYER_ID_PATTERN.test(message.nonce) || typeof message.accept !== "boolean") return fail("invalid rematch response");
     return { ok: true, value: { ...message, nonce: message.nonce.toLowerCase() } };
   }
-  if (message.type === "profile" && onlyKeys(message, ["type", "name", "paletteId", "cosmeticId"])) {
+  if (message.type === "profile" && onlyKeys(message, ["type", "name", "paletteId", "cosmeticId", "cosmetics", "ratings", "simulation"])) {
     if (typeof message.name !== "string" || byteLength(message.name) === 0 || byteLength(message.name) > 24 || /[\u0000-\u001f\u007f]/.test(message.name) || message.name !== message.name.trim() || !Number.isInteger(message.paletteId) || message.paletteId < 0 || message.paletteId > 3 || !Number.isInteger(message.cosmeticId) || message.cosmeticId < 0 || message.cosmeticId > 3) return fail("invalid profile");
+    if (message.simulation !== undefined && (typeof message.simulation !== "string" || !SIMULATION_PATTERN.test(message.simulation))) return fail("invalid profile");
+    if (message.cosmetics !== undefined && (typeof message.cosmetics !== "string" || !COSMETICS_PATTERN.test(message.cosmetics))) return fail("invalid profile");
+    if (message.ratings !== undefined && (typeof message.ratings !== "string" || !RATINGS_PATTERN.test(message.ratings))) return fail("invalid profile");
     return { ok: true, value: message };
   }
   if (message.type === "report" && onlyKeys(message, ["type", "epoch", "round", "outcomes"])) {
@@ -151,6 +183,16 @@ export function parseEpochClientMessage(text) {
     }
     return { ok: true, value: { ...message, outcomes: message.outcomes.map((outcome) => ({ ...outcome, playerId: outcome.playerId.toLowerCase() })) } };
   }
+  if (message.type === "latency" && onlyKeys(message, ["type", "epoch", "round", "rttMs"])) {
+    if (!Number.isInteger(message.epoch) || message.epoch < 0 || !Number.isInteger(message.round) || message.round < 0 ||
+        !Number.isInteger(message.rttMs) || message.rttMs < 0 || message.rttMs > MAX_REPORTED_RTT_MS) return fail("invalid latency report");
+    return { ok: true, value: message };
+  }
+  if (message.type === "resume_snapshot" && onlyKeys(message, ["type", "epoch", "round", "snapshot"])) {
+    if (!Number.isInteger(message.epoch) || message.epoch < 0 || !Number.isInteger(message.round) || message.round < 0 ||
+        typeof message.snapshot !== "string" || !RESUME_SNAPSHOT_PATTERN.test(message.snapshot)) return fail("invalid resume snapshot");
+    return { ok: true, value: message };
+  }
   if (message.type === "signal") {
     // Protocol 3 signals are always epoch-scoped. A signal missing `epoch` is
     // not silently downgraded to the legacy v2 schema.
@@ -245,6 +287,22 @@ export function canonicalRoster(records) {
     .map((record, index) => ({ ...record, index }));
 }
 
+/** Whole rollback frames of one-way latency, capped so a bad link cannot stall input. */
+export function inputDelayForRtt(rttMs) {
+  return Math.min(MAX_INPUT_DELAY, Math.floor(rttMs / 2 / ROLLBACK_FRAME_MS));
+}
+
+/**
+ * One delay for the whole immutable roster, derived only from persisted
+ * samples so every recipient of a start receives the same value. An unmeasured
+ * identity keeps the historical fixed delay rather than guessing LAN.
+ */
+export function lobbyInputDelay(players, roster) {
+  const samples = roster.map((entry) => players[entry.playerId]?.rttMs);
+  if (samples.length === 0 || samples.some((sample) => !Number.isInteger(sample))) return DEFAULT_INPUT_DELAY;
+  return inputDelayForRtt(Math.max(...samples));
+}
+
 export function randomHex(bytes = 16, cryptoImpl = globalThis.crypto) {
   const value = cryptoImpl.getRandomValues(new Uint8Array(bytes));
   return Array.from(value, (byte) => byte.toString(16).padStart(2, "0")).join("");
@@ -258,7 +316,7 @@ function onlyKeys(value, allowed) {
   const keys = Object.keys(value);
   return keys.every((key) => allowed.includes(key)) && allowed.every((key) =>
     key === "nonce" || key === "sdpMid" || key === "sdpMLineIndex" ||
-    key === "usernameFragment" || Object.hasOwn(value, key));
+    key === "usernameFragment" || key === "simulation" || key === "cosmetics" || key === "ratings" || Object.hasOwn(value, key));
 }
 
 function optionalString(value, maxLength) {
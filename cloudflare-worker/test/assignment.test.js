@@ -1,6 +1,8 @@
 import test from "node:test";
 import assert from "node:assert/strict";
-import { consumeAssignment, signAssignment, verifyAssignment } from "../src/assignment.js";
+import {
+  canonicalLobbyStart, consumeAssignment, importStartSigningKey, signAssignment, signLobbyStart, verifyAssignment,
+} from "../src/assignment.js";
 
 const secret = "test-only-secret-that-is-at-least-thirty-two-bytes";
 const fields = {
@@ -40,3 +42,42 @@ test("expiry boundary and replay are rejected by one-use admission", async () =>
   assert.deepEqual(await consumeAssignment(secret, fields, token, fields.expiresAt - 1, storage), { ok: false, code: "replay" });
   assert.deepEqual(await consumeAssignment(secret, fields, token, fields.expiresAt, storage), { ok: false, code: "expired" });
 });
+
+// RFC 8032 test 1 key; the Rust client tests verify the same start vector.
+const startSeed = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const startPublicKey = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const start = {
+  mode: "deathmatch", capacity: 4, matchGeneration: 3, epoch: 4, round: 1, seed: "0123456789abcdef0123456789abcdef", inputDelay: 2,
+  roster: [
+    { playerId: "b".repeat(32), index: 1, score: 2, simulation: "0123456789abcdef", cosmetics: "1.0.2", ratings: "1510.1490" },
+    { playerId: "a".repeat(32), index: 0, score: 0 },
+    { playerId: "c".repeat(32), index: 2, score: 1, simulation: "fedcba9876543210" },
+  ],
+};
+
+test("lobby start canonical text is index-ordered and matches the client vector", () => {
+  assert.equal(
+    canonicalLobbyStart(start),
+    `v3-start\ndeathmatch\n4\n3\n4\n1\n0123456789abcdef0123456789abcdef\n2\n${"a".repeat(32)}:0:::,${"b".repeat(32)}:2:0123456789abcdef:1.0.2:1510.1490,${"c".repeat(32)}:1:fedcba9876543210::\n`,
+  );
+  assert.ok(canonicalLobbyStart({ ...start, resume: "v1;0" }).endsWith("\nv1;0"));
+});
+
+test("lobby starts are Ed25519-signed deterministically and unsigned without a key", async () => {
+  assert.equal(await importStartSigningKey(undefined), null);
+  assert.equal(await importStartSigningKey("not-hex"), null);
+  const key = await importStartSigningKey(startSeed);
+  const signature = await signLobbyStart(key, start);
+  assert.equal(signature, "fc7aeb385cec9f193bd0f7d3cd490e0c01911ce0e08005dda412bbceb31216abe6008dc5781dc36031ab2994f4a759a6c61482ceba80ae7d7ebf12047d765802");
+  const publicKey = await crypto.subtle.importKey("raw", Buffer.from(startPublicKey, "hex"), { name: "Ed25519" }, false, ["verify"]);
+  const bytes = (value) => new TextEncoder().encode(canonicalLobbyStart(value));
+  assert.equal(await crypto.subtle.verify({ name: "Ed25519" }, publicKey, Buffer.from(signature, "hex"), bytes(start)), true);
+  const verifies = async (value) => crypto.subtle.verify({ name: "Ed25519" }, publicKey, Buffer.from(signature, "hex"), bytes(value));
+  assert.equal(await verifies({ ...start, inputDelay: 0 }), false);
+  assert.equal(await verifies({ ...start, capacity: 8 }), false);
+  assert.equal(await verifies({ ...start, matchGeneration: 4 }), false);
+  const [b, a, c] = start.roster;
+  for (const edited of [{ ...b, simulation: "0000000000000000" }, { ...b, cosmetics: "0.0.0" }, { ...b, ratings: "9999.1490" }]) {
+    assert.equal(await verifies({ ...start, roster: [edited, a, c] }), false);
+  }
+});
@@ -36,8 +36,26 @@ test("boundary departure persists before acknowledgement and exits recipients on
 });
 
 test("changed starts are sent only to immutable roster sockets", () => {
-  const method = source.slice(source.indexOf("  broadcastStart(active) {"), source.indexOf("  sendStatus(socket", source.indexOf("  broadcastStart(active) {")));
+  const method = source.slice(source.indexOf("  async broadcastStart(active) {"), source.indexOf("  sendStatus(socket", source.indexOf("  async broadcastStart(active) {")));
   assert.match(method, /for \(const entry of active\.roster\)/);
   assert.match(method, /this\.socket\(entry\.playerId\)/);
   assert.doesNotMatch(method, /this\.broadcast\(/);
 });
+
+test("control resume keeps a matching round and reload batches request a snapshot", () => {
+  const reconnect = source.slice(source.indexOf("parsed.value.playerId && this.isActive"), source.indexOf("    } else {", source.indexOf("parsed.value.playerId && this.isActive")));
+  assert.match(reconnect, /resume\.epoch === active\.epoch && resume\.round === active\.round && this\.state\.reconnectBatchDeadline == null/);
+  assert.ok(reconnect.indexOf("this.sendStatus(server, player);") < reconnect.indexOf("markActiveReconnect"));
+  assert.match(reconnect, /this\.requestResumeSnapshot\(player\.playerId\)/);
+  const method = source.slice(source.indexOf("async processReconnectRollover"), source.indexOf("async expireRematch"));
+  assert.match(method, /result\.next\.seed = offer\.seed/);
+  assert.ok(method.indexOf("result.next.resume = offer.snapshot") < method.indexOf("await this.persist()"));
+});
+
+test("starts echo each roster member's simulation fingerprint from its profile", () => {
+  assert.match(source, /player\.simulation = message\.simulation \?\? null/);
+  assert.match(source, /player\.cosmetics = message\.cosmetics \?\? null/);
+  assert.match(source, /player\.ratings = message\.ratings \?\? null/);
+  const start = source.slice(source.indexOf("  startMessage(active) {"), source.indexOf("  withSimulation(entry) {"));
+  assert.match(start, /roster: active\.roster\.map\(\(entry\) => this\.withSimulation\(entry\)\)/);
+});
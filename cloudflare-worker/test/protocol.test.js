@@ -12,6 +12,10 @@ import {
   parseEpochClientMessage,
   parseEpochLobbyQuery,
   parseQueueQuery,
+  inputDelayForRtt,
+  lobbyInputDelay,
+  DEFAULT_INPUT_DELAY,
+  MAX_INPUT_DELAY,
 } from "../src/protocol.js";
 
 test("routePath preserves the legacy /match route and adds /lobby", () => {
@@ -105,6 +109,16 @@ test("epoch protocol validates profiles and reports", () => {
   ] })).ok, true);
   assert.equal(parseEpochClientMessage(JSON.stringify({ type:"report", epoch:0, round:0, outcomes:[] })).ok, false);
   assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"", paletteId:1, cosmeticId:2 })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:2, simulation:"0123456789abcdef" })).ok, true);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:2, simulation:"0123" })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:0, cosmetics:"2.64.128" })).ok, true);
+  for (const cosmetics of ["2.64", "2.64.128.1", "12345.0.0", "-1.0.0", 7]) {
+    assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:0, cosmetics })).ok, false);
+  }
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:0, ratings:"1012.987" })).ok, true);
+  for (const ratings of ["1012", "1012.987.1", "12345.0", "-1.0", 1012]) {
+    assert.equal(parseEpochClientMessage(JSON.stringify({ type:"profile", name:"Ghost", paletteId:1, cosmeticId:0, ratings })).ok, false);
+  }
   const nonce = "f".repeat(32);
   assert.equal(parseEpochClientMessage(JSON.stringify({ type:"rematch_request", generation:1, nonce })).ok, true);
   assert.equal(parseEpochClientMessage(JSON.stringify({ type:"rematch_response", generation:1, nonce, accept:false })).ok, true);
@@ -115,6 +129,47 @@ test("epoch protocol validates profiles and reports", () => {
   assert.equal(parseEpochClientMessage(JSON.stringify({ type:"rematch_request", generation:0, nonce })).ok, false);
 });
 
+test("latency reports are bounded and round-scoped", () => {
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"latency", epoch:1, round:2, rttMs:48 })).ok, true);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"latency", epoch:1, round:2, rttMs:5001 })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"latency", epoch:1, round:2, rttMs:4.5 })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"latency", epoch:1, rttMs:10 })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"latency", epoch:1, round:2, rttMs:10, extra:1 })).ok, false);
+});
+
+test("resume query requires a reconnect identity and a complete round pair", () => {
+  const identity = `playerId=${"a".repeat(32)}&reconnectToken=${"b".repeat(32)}`;
+  const parsed = parseEpochLobbyQuery(new URLSearchParams(`protocol=3&mode=duel&capacity=2&${identity}&resumeEpoch=4&resumeRound=2`));
+  assert.equal(parsed.ok, true);
+  assert.deepEqual(parsed.value.resume, { epoch: 4, round: 2 });
+  assert.equal(parseEpochLobbyQuery(new URLSearchParams(`protocol=3&mode=duel&capacity=2&${identity}`)).value.resume, null);
+  assert.equal(parseEpochLobbyQuery(new URLSearchParams("protocol=3&mode=duel&capacity=2&resumeEpoch=4&resumeRound=2")).ok, false);
+  assert.equal(parseEpochLobbyQuery(new URLSearchParams(`protocol=3&mode=duel&capacity=2&${identity}&resumeEpoch=4`)).ok, false);
+  assert.equal(parseEpochLobbyQuery(new URLSearchParams(`protocol=3&mode=duel&capacity=2&${identity}&resumeEpoch=-1&resumeRound=0`)).ok, false);
+  assert.equal(parseEpochLobbyQuery(new URLSearchParams(`protocol=3&mode=duel&capacity=2&${identity}&resumeEpoch=4294967296&resumeRound=0`)).ok, false);
+});
+
+test("resume snapshots are bounded opaque round-scoped text", () => {
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"resume_snapshot", epoch:1, round:2, snapshot:"v1;40;0:1,2,0,0,0,0" })).ok, true);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"resume_snapshot", epoch:1, round:2, snapshot:"" })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"resume_snapshot", epoch:1, round:2, snapshot:"<script>" })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"resume_snapshot", epoch:1, round:2, snapshot:"a".repeat(2049) })).ok, false);
+  assert.equal(parseEpochClientMessage(JSON.stringify({ type:"resume_snapshot", epoch:1, snapshot:"v1" })).ok, false);
+});
+
+test("lobby input delay follows the slowest measured roster member", () => {
+  assert.equal(inputDelayForRtt(0), 0);
+  assert.equal(inputDelayForRtt(20), 0);
+  assert.equal(inputDelayForRtt(40), 1);
+  assert.equal(inputDelayForRtt(70), 2);
+  assert.equal(inputDelayForRtt(5_000), MAX_INPUT_DELAY);
+  const roster = [{ playerId: "a".repeat(32) }, { playerId: "b".repeat(32) }];
+  assert.equal(lobbyInputDelay({}, roster), DEFAULT_INPUT_DELAY);
+  assert.equal(lobbyInputDelay({ ["a".repeat(32)]: { rttMs: 8 } }, roster), DEFAULT_INPUT_DELAY);
+  assert.equal(lobbyInputDelay({ ["a".repeat(32)]: { rttMs: 8 }, ["b".repeat(32)]: { rttMs: 12 } }, roster), 0);
+  assert.equal(lobbyInputDelay({ ["a".repeat(32)]: { rttMs: 8 }, ["b".repeat(32)]: { rttMs: 110 } }, roster), 3);
+});
+
 test("epoch signals are always epoch-scoped and never downgrade to v2", () => {
   const target = "1".repeat(32);
   const valid = parseEpochClientMessage(JSON.stringify({
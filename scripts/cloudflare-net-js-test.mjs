@@ -177,4 +177,147 @@ assert.equal(net.cloudflare_lobby_stalled(stallId), false);
   assert.equal(net.cloudflare_lobby_round(id), 3);
 }
 
+// RTT-derived input delay is carried by the immutable start, and latency
+// samples are reported once per round with only the slowest RTT.
+{
+  const net = await freshModule();
+  const { id, ws } = await readyLobby(net, { epoch: 4, round: 1 });
+  assert.equal(net.cloudflare_lobby_input_delay(id), 2, "absent inputDelay keeps the historical default");
+  const peer = MockPeer.instances.at(-1);
+  peer.getStats = async () => new Map([
+    ["t", { type: "transport", selectedCandidatePairId: "p" }],
+    ["p", { type: "candidate-pair", currentRoundTripTime: 0.0484 }],
+  ]);
+  assert.equal(net.cloudflare_lobby_sample_latency(id), true);
+  await tick();
+  assert.deepEqual(JSON.parse(ws.sent.at(-1)), { type: "latency", epoch: 4, round: 1, rttMs: 48 });
+  assert.equal(net.cloudflare_lobby_sample_latency(id), false, "one report per round");
+
+  const other = await freshModule();
+  const bad = other.cloudflare_connect_lobby("", "delay", 0, 2, "Ghost", 0, 0);
+  const badWs = MockWebSocket.instances.at(-1);
+  badWs.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  badWs.message({ type: "start", protocol: 3, epoch: 1, round: 0, seed: SEED, inputDelay: 9, roster: [{ index: 0, playerId: PLAYER_A, score: 0 }, { index: 1, playerId: PLAYER_B, score: 0 }] });
+  await tick();
+  assert.equal(other.cloudflare_status(bad), 2, "out-of-range inputDelay must fail validation");
+}
+
+// The worker's start signature is carried verbatim for Rust-side verification;
+// malformed signatures fail start validation in the transport.
+{
+  const net = await freshModule();
+  const { id } = await readyLobby(net, { epoch: 2, round: 0 });
+  assert.equal(net.cloudflare_lobby_signature(id), "", "unsigned starts expose an empty signature");
+
+  const signed = await freshModule();
+  const signedId = signed.cloudflare_connect_lobby("", "signed", 0, 2, "Ghost", 0, 0);
+  const signedWs = MockWebSocket.instances.at(-1);
+  signedWs.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  signedWs.message({ type: "start", protocol: 3, epoch: 1, round: 0, seed: SEED, signature: "ab".repeat(64), roster: [{ index: 0, playerId: PLAYER_A, score: 0 }, { index: 1, playerId: PLAYER_B, score: 0 }] });
+  await tick();
+  assert.equal(signed.cloudflare_lobby_signature(signedId), "ab".repeat(64));
+
+  const other = await freshModule();
+  const bad = other.cloudflare_connect_lobby("", "badsig", 0, 2, "Ghost", 0, 0);
+  const badWs = MockWebSocket.instances.at(-1);
+  badWs.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  badWs.message({ type: "start", protocol: 3, epoch: 1, round: 0, seed: SEED, signature: "XYZ", roster: [{ index: 0, playerId: PLAYER_A, score: 0 }, { index: 1, playerId: PLAYER_B, score: 0 }] });
+  await tick();
+  assert.equal(other.cloudflare_status(bad), 2, "malformed signatures must fail validation");
+}
+
+// A later round of the same epoch keeps its peers but must expose its own
+// signature, or the Rust side would verify the new start against the old one.
+{
+  const net = await freshModule();
+  const id = net.cloudflare_connect_lobby("", "rounds", 0, 2, "Ghost", 0, 0);
+  const ws = MockWebSocket.instances.at(-1);
+  ws.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  const roster = [{ index: 0, playerId: PLAYER_A, score: 0 }, { index: 1, playerId: PLAYER_B, score: 0 }];
+  ws.message({ type: "start", protocol: 3, epoch: 3, round: 0, seed: SEED, signature: "ab".repeat(64), roster });
+  await tick();
+  MockPeer.instances.at(-1).channel.onopen?.();
+  assert.equal(net.cloudflare_status(id), 1);
+  ws.message({ type: "start", protocol: 3, epoch: 3, round: 1, seed: SEED, signature: "cd".repeat(64), roster });
+  await tick();
+  assert.equal(net.cloudflare_lobby_pending_round(id), 1);
+  assert.equal(net.cloudflare_lobby_signature(id), "ab".repeat(64), "the active round keeps its signature until promotion");
+  assert.equal(net.cloudflare_lobby_promote_pending(id, 3, 0, 3, 1), true);
+  assert.equal(net.cloudflare_lobby_signature(id), "cd".repeat(64), "a promoted round carries its own signature");
+}
+
+// The simulation fingerprint rides on the profile and comes back per roster
+// entry so the game can refuse mismatched builds before installing GGRS.
+// Equipped cosmetics and skill ratings travel the same way but are
+// presentation-only.
+{
+  const net = await freshModule();
+  const id = net.cloudflare_connect_lobby("", "sim", 0, 2, "Ghost", 0, 0, "2.64.128", "1012.987", "0123456789abcdef");
+  const ws = MockWebSocket.instances.at(-1);
+  ws.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  assert.deepEqual(JSON.parse(ws.sent[0]), { type: "profile", name: "Ghost", paletteId: 0, cosmeticId: 0, cosmetics: "2.64.128", ratings: "1012.987", simulation: "0123456789abcdef" });
+  ws.message({ type: "start", protocol: 3, epoch: 1, round: 0, seed: SEED, roster: [{ index: 0, playerId: PLAYER_A, score: 0, simulation: "0123456789abcdef", cosmetics: "2.64.128", ratings: "1012.987" }, { index: 1, playerId: PLAYER_B, score: 0 }] });
+  await tick();
+  assert.equal(net.cloudflare_lobby_roster_simulation(id, 0), "0123456789abcdef");
+  assert.equal(net.cloudflare_lobby_roster_simulation(id, 1), "", "older clients report no fingerprint");
+  assert.equal(net.cloudflare_lobby_roster_cosmetics(id, 0), "2.64.128");
+  assert.equal(net.cloudflare_lobby_roster_cosmetics(id, 1), "", "older clients report no cosmetics");
+  assert.equal(net.cloudflare_lobby_roster_ratings(id, 0), "1012.987");
+  assert.equal(net.cloudflare_lobby_roster_ratings(id, 1), "", "older clients report no ratings");
+
+  const other = await freshModule();
+  const bad = other.cloudflare_connect_lobby("", "badsim", 0, 2, "Ghost", 0, 0, "", "", "not-a-fingerprint");
+  const badWs = MockWebSocket.instances.at(-1);
+  badWs.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "c".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  assert.equal(JSON.parse(badWs.sent[0]).simulation, undefined, "malformed local fingerprints are never sent");
+  badWs.message({ type: "start", protocol: 3, epoch: 1, round: 0, seed: SEED, roster: [{ index: 0, playerId: PLAYER_A, score: 0, simulation: "XYZ" }, { index: 1, playerId: PLAYER_B, score: 0 }] });
+  await tick();
+  assert.equal(other.cloudflare_status(bad), 2, "malformed roster fingerprints must fail validation");
+}
+
+// A peer or control socket lost mid-round renegotiates inside the reconnect
+// window, keeping the same epoch round instead of failing the session.
+{
+  const net = await freshModule();
+  const timers = [];
+  window.setTimeout = callback => timers.push(callback);
+  const { id, ws, channel } = await readyLobby(net, { epoch: 5, round: 2 });
+  const peers = MockPeer.instances.length;
+  channel.onclose();
+  assert.equal(net.cloudflare_status(id), 1, "a dropped channel opens a reconnect window");
+  assert.equal(net.cloudflare_telemetry(id, 4), 1n);
+  await tick();
+  assert.equal(MockPeer.instances.length, peers + 1, "the lower player ID re-offers at once");
+  assert.equal(JSON.parse(ws.sent.at(-1)).data.type, "offer");
+  MockPeer.instances.at(-1).channel.onopen();
+  assert.equal(net.cloudflare_lobby_epoch(id), 5);
+  assert.equal(net.cloudflare_lobby_round(id), 2);
+
+  ws.onclose({ code: 1006 });
+  assert.equal(net.cloudflare_status(id), 1, "an abnormal control close is resumable");
+  timers.at(-1)();
+  const resumed = MockWebSocket.instances.at(-1);
+  assert.notEqual(resumed, ws);
+  assert.match(resumed.url, /playerId=0{31}1&reconnectToken=a{32}&resumeEpoch=5&resumeRound=2$/);
+  resumed.message({ type: "welcome", protocol: 3, playerId: PLAYER_A, reconnectToken: "d".repeat(32), iceServers: [{ urls: "stun:stun.cloudflare.com:3478" }], turnExpiresAt: null });
+  await tick();
+  assert.equal(resumed.sent.length, 0, "a resumed control socket keeps the durable profile and readiness");
+  resumed.message({ type: "resume_request", epoch: 5, round: 2 });
+  await tick();
+  assert.equal(net.cloudflare_lobby_take_resume_request(id), true);
+  assert.equal(net.cloudflare_lobby_take_resume_request(id), false, "requests are taken once");
+  assert.equal(net.cloudflare_lobby_send_resume(id, 5, 2, "BAD"), false);
+  assert.equal(net.cloudflare_lobby_send_resume(id, 5, 2, "v1;40;"), true);
+  assert.deepEqual(JSON.parse(resumed.sent.at(-1)), { type: "resume_snapshot", epoch: 5, round: 2, snapshot: "v1;40;" });
+
+  resumed.onclose({ code: 4001 });
+  assert.equal(net.cloudflare_status(id), 2, "a superseded control socket is final");
+}
+
 console.log("PASS: cloudflare_net.js direct Node contract tests");
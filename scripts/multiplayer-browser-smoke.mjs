@@ -80,14 +80,14 @@ async function main() {
   await Promise.all(pages.map((page, index) => screenshot(page, `player-${index + 1}-interaction.png`)));
 
   const persisted = await Promise.all(pages.map(page => page.evaluate(key => localStorage.getItem(key), profileKey)));
-  assert(persisted[0]?.includes("\tSmokeOne\t"), "player 1 profile was not loaded/preserved");
-  assert(persisted[1]?.includes("\tSmokeTwo\t"), "player 2 profile was not loaded/preserved");
+  assert(persisted[0]?.includes("\tname=SmokeOne\t"), "player 1 profile was not loaded/preserved");
+  assert(persisted[1]?.includes("\tname=SmokeTwo\t"), "player 2 profile was not loaded/preserved");
 
   await Promise.all(pages.map(page => page.reload({ waitUntil: "domcontentloaded", timeout: readyTimeout })));
   await Promise.all(pages.map(page => page.waitForFunction(() => document.querySelector("canvas"), null,
     { timeout: readyTimeout })));
   const afterReload = await Promise.all(pages.map(page => page.evaluate(key => localStorage.getItem(key), profileKey)));
-  assert(afterReload[0]?.includes("\tSmokeOne\t") && afterReload[1]?.includes("\tSmokeTwo\t"),
+  assert(afterReload[0]?.includes("\tname=SmokeOne\t") && afterReload[1]?.includes("\tname=SmokeTwo\t"),
     "local profile did not survive reload in isolated contexts");
   await Promise.all(pages.map((page, index) => screenshot(page, `player-${index + 1}-reload.png`)));
 
@@ -13,7 +13,20 @@ const DEFAULT_ICE_SERVERS = Object.freeze([{ urls: "stun:stun.cloudflare.com:347
 const MAX_ICE_SERVERS = 8;
 const MAX_ICE_URLS = 8;
 const MAX_ICE_TEXT = 512;
-const PEER_PACKET_STALL_MS = 1500;
+// A peer or control socket lost mid-round may renegotiate inside this window
+// before the round is abandoned. Keep it shorter than the Worker's 30 s grace.
+const PEER_RECONNECT_WINDOW_MS = 10_000;
+const PEER_REOFFER_MS = 2_000;
+const CONTROL_REOPEN_DELAY_MS = 1_000;
+const PEER_PACKET_STALL_MS = PEER_RECONNECT_WINDOW_MS;
+const RESUME_SNAPSHOT_PATTERN = /^[0-9a-z:;.,]{1,2048}$/;
+const START_SIGNATURE_PATTERN = /^[0-9a-f]{128}$/;
+const SIMULATION_PATTERN = /^[0-9a-f]{16}$/;
+const COSMETICS_PATTERN = /^\d{1,4}\.\d{1,4}\.\d{1,4}$/;
+const RATINGS_PATTERN = /^\d{1,4}\.\d{1,4}$/;
+const DEFAULT_INPUT_DELAY = 2;
+const MAX_INPUT_DELAY = 4;
+const MAX_REPORTED_RTT_MS = 5000;
 
 function validIceUrl(value) {
     if (typeof value !== "string" || value.length === 0 || value.length > 256 || /[\u0000-\u0020\u007f]/.test(value)) return false;
@@ -50,16 +63,21 @@ function validatedIceConfiguration(message) {
     return { iceServers: servers, turnExpiresAt: message.turnExpiresAt, hasTurn };
 }
 
+function selectedCandidatePair(stats) {
+    let pair = null;
+    stats.forEach(report => {
+        if (report.type === "transport" && report.selectedCandidatePairId) pair = stats.get(report.selectedCandidatePairId) || pair;
+        if (!pair && report.type === "candidate-pair" && report.state === "succeeded" && (report.nominated || report.selected)) pair = report;
+    });
+    return pair;
+}
+
 async function recordCandidatePair(session, peer) {
     if (peer.__ghostStatsRecorded) return;
     try {
         const stats = await peer.getStats();
         if (!isCurrent(session) || peer.__ghostStatsRecorded) return;
-        let pair = null;
-        stats.forEach(report => {
-            if (report.type === "transport" && report.selectedCandidatePairId) pair = stats.get(report.selectedCandidatePairId) || pair;
-            if (!pair && report.type === "candidate-pair" && report.state === "succeeded" && (report.nominated || report.selected)) pair = report;
-        });
+        const pair = selectedCandidatePair(stats);
         if (!pair) return;
         const local = stats.get(pair.localCandidateId);
         const remote = stats.get(pair.remoteCandidateId);
@@ -72,6 +90,13 @@ async function recordCandidatePair(session, peer) {
     } catch (_) { /* Metrics must never affect connectivity. */ }
 }
 
+// Only the selected pair's ICE round-trip time leaves this function; candidate
+// addresses and types stay inside the browser.
+async function peerRoundTripMs(peer) {
+    const seconds = selectedCandidatePair(await peer.getStats())?.currentRoundTripTime;
+    return Number.isFinite(seconds) && seconds >= 0 ? Math.min(MAX_REPORTED_RTT_MS, Math.round(seconds * 1000)) : null;
+}
+
 function peerConfiguration(session) {
     const turnUsable = session.iceHasTurn && Number.isSafeInteger(session.turnExpiresAt) &&
         session.turnExpiresAt > Date.now() + 10 * 60 * 1000;
@@ -89,6 +114,8 @@ function isCurrent(session) {
 
 function closeSession(session, code, reason) {
     window.clearTimeout(session.timeout);
+    window.clearTimeout(session.controlReopen);
+    clearPeerReconnects(session);
     window.clearInterval(session.heartbeat);
     session.channel?.close();
     session.peer?.close();
@@ -118,6 +145,66 @@ function lobbySendSignal(session, to, data, epoch = session.epoch, round = sessi
     }
 }
 
+function dropPeer(session, peerId) {
+    const channel = session.channels.get(peerId);
+    const peer = session.peers.get(peerId);
+    session.channels.delete(peerId);
+    session.peers.delete(peerId);
+    session.pendingIce.delete(peerId);
+    session.openPeers.delete(peerId);
+    channel?.close();
+    peer?.close();
+}
+
+function clearPeerReconnect(session, peerId) {
+    const pending = session.peerReconnects?.get(peerId);
+    if (!pending) return;
+    window.clearTimeout(pending.deadline);
+    window.clearInterval(pending.reoffer);
+    session.peerReconnects.delete(peerId);
+}
+
+function clearPeerReconnects(session) {
+    for (const peerId of [...(session.peerReconnects?.keys() ?? [])]) clearPeerReconnect(session, peerId);
+}
+
+// Renegotiates one dropped peer inside the current epoch round while GGRS keeps
+// its session. The lower player ID re-offers until a fresh channel opens, as in
+// the first negotiation; the other side waits for that offer.
+function beginPeerReconnect(session, peerId, restartNow = true) {
+    const epoch = session.epoch, round = session.round;
+    if (!sameRound(session, epoch, round) || session.status !== 1 || session.peerReconnects.has(peerId)) return;
+    dropPeer(session, peerId);
+    session.telemetry[4]++;
+    const offerer = session.localPlayerId < peerId;
+    const restart = () => {
+        const peer = session.peers.get(peerId);
+        if (!sameRound(session, epoch, round) || session.openPeers.has(peerId) ||
+            (peer?.remoteDescription && !["failed", "closed"].includes(peer.connectionState))) return;
+        dropPeer(session, peerId);
+        session.signalChain = session.signalChain.then(() => lobbyCreatePeer(session, peerId, offerer, epoch, round)).catch(error => fail(session, error));
+    };
+    session.peerReconnects.set(peerId, {
+        deadline: window.setTimeout(() => { if (session.peerReconnects.has(peerId)) fail(session, "lobby peer did not reconnect in time"); }, PEER_RECONNECT_WINDOW_MS),
+        reoffer: offerer ? window.setInterval(restart, PEER_REOFFER_MS) : 0,
+        restart,
+    });
+    if (restartNow) restart();
+}
+
+function peerLost(session, peerId, epoch, error) {
+    if (!sameEpochTransport(session, epoch)) return;
+    if (session.status === 1) beginPeerReconnect(session, peerId);
+    else if (error) fail(session, error);
+}
+
+// Only a stale candidate or answer from a superseded negotiation may fail
+// while its peer is reconnecting; anything else is still a protocol error.
+async function tolerateReconnect(session, peerId, action) {
+    try { await action(); }
+    catch (error) { if (!session.peerReconnects?.has(peerId)) throw error; }
+}
+
 function lobbyBindChannel(session, peerId, channel, epoch, round) {
     if (!sameRound(session, epoch, round)) { channel.close(); return; }
     if (session.channels.has(peerId)) return fail(session, "duplicate lobby data channel");
@@ -135,10 +222,11 @@ function lobbyBindChannel(session, peerId, channel, epoch, round) {
         session.telemetry[1]++;
         session.inbox.push({ epoch: packetEpoch, from: peerId, packet: bytes.slice(8) });
     };
-    channel.onclose = () => { if (sameEpochTransport(session, epoch) && session.status === 1) fail(session, "lobby peer disconnected"); };
-    channel.onerror = () => { if (sameEpochTransport(session, epoch)) fail(session, "lobby peer data channel failed"); };
+    channel.onclose = () => { if (session.channels.get(peerId) === channel) peerLost(session, peerId, epoch, null); };
+    channel.onerror = () => { if (session.channels.get(peerId) === channel) peerLost(session, peerId, epoch, "lobby peer data channel failed"); };
     channel.onopen = () => {
         if (!sameEpochTransport(session, epoch)) return channel.close();
+        clearPeerReconnect(session, peerId);
         session.openPeers.add(peerId);
         session.peerLastPacketAt.set(peerId, Date.now());
         if (session.openPeers.size === session.roster.length - 1) {
@@ -156,9 +244,9 @@ async function lobbyCreatePeer(session, peerId, offerer, epoch, round) {
     peer.onicecandidate = ({ candidate }) => { if (sameRound(session, epoch, round)) lobbySendSignal(session, peerId, { type: "ice", candidate }, epoch, round); };
     peer.ondatachannel = ({ channel }) => lobbyBindChannel(session, peerId, channel, epoch, round);
     peer.onconnectionstatechange = () => {
-        if (!sameEpochTransport(session, epoch)) return;
+        if (!sameEpochTransport(session, epoch) || session.peers.get(peerId) !== peer) return;
         if (peer.connectionState === "connected") recordCandidatePair(session, peer);
-        if (peer.connectionState === "failed") fail(session, "lobby WebRTC connection failed");
+        if (peer.connectionState === "failed" && !session.peerReconnects.has(peerId)) peerLost(session, peerId, epoch, "lobby WebRTC connection failed");
     };
     if (offerer) {
         lobbyBindChannel(session, peerId, peer.createDataChannel("ggrs", { ordered: false, maxRetransmits: 0 }), epoch, round);
@@ -177,24 +265,32 @@ function validLobbySignal(session, message, start) {
 async function lobbyHandleSignal(session, message) {
     const epoch = session.epoch, round = session.round, from = message.from;
     if (!validLobbySignal(session, message, { epoch, round, roster: session.roster })) throw new Error("invalid lobby signal source");
+    const data = message.data;
+    if (data.type === "offer" && session.status === 1 && session.peers.get(from)?.remoteDescription) {
+        // The remote offerer restarted inside its reconnect window before this
+        // side noticed the drop; replace the stale peer and answer afresh.
+        if (session.localPlayerId < from) throw new Error("unexpected lobby offer");
+        beginPeerReconnect(session, from, false);
+        dropPeer(session, from);
+        await lobbyCreatePeer(session, from, false, epoch, round);
+    }
     const peer = session.peers.get(from);
     if (!peer) throw new Error("lobby signal before peer setup");
-    const data = message.data;
     if (data.type === "offer") {
         if (session.localPlayerId < from) throw new Error("unexpected lobby offer");
         await peer.setRemoteDescription({ type: "offer", sdp: data.sdp });
         if (!sameRound(session, epoch, round)) return;
-        for (const candidate of session.pendingIce.get(from).splice(0)) if (candidate) await peer.addIceCandidate(candidate);
+        for (const candidate of session.pendingIce.get(from).splice(0)) if (candidate) await tolerateReconnect(session, from, () => peer.addIceCandidate(candidate));
         await peer.setLocalDescription(await peer.createAnswer());
         if (sameRound(session, epoch, round)) lobbySendSignal(session, from, { type: "answer", sdp: peer.localDescription.sdp }, epoch, round);
     } else if (data.type === "answer") {
         if (session.localPlayerId > from) throw new Error("unexpected lobby answer");
-        await peer.setRemoteDescription({ type: "answer", sdp: data.sdp });
-        if (!sameRound(session, epoch, round)) return;
-        for (const candidate of session.pendingIce.get(from).splice(0)) if (candidate) await peer.addIceCandidate(candidate);
+        await tolerateReconnect(session, from, () => peer.setRemoteDescription({ type: "answer", sdp: data.sdp }));
+        if (!sameRound(session, epoch, round) || !peer.remoteDescription) return;
+        for (const candidate of session.pendingIce.get(from).splice(0)) if (candidate) await tolerateReconnect(session, from, () => peer.addIceCandidate(candidate));
     } else if (data.type === "ice") {
-        if (peer.remoteDescription) { if (data.candidate) await peer.addIceCandidate(data.candidate); }
-        else session.pendingIce.get(from).push(data.candidate);
+        if (peer.remoteDescription) { if (data.candidate) await tolerateReconnect(session, from, () => peer.addIceCandidate(data.candidate)); }
+        else session.pendingIce.get(from)?.push(data.candidate);
     }
 }
 
@@ -205,17 +301,25 @@ function validLobbyStart(session, message) {
         !((session.mode === 0 && message.roster.length === 2) ||
           (session.mode === 1 && message.roster.length >= 3 && message.roster.length <= session.capacity)) ||
         !Number.isInteger(message.matchGeneration ?? 0) || (message.matchGeneration ?? 0) < 0 || (message.matchGeneration ?? 0) > 0xffffffff) return null;
+    const inputDelay = message.inputDelay ?? DEFAULT_INPUT_DELAY;
+    if (!Number.isInteger(inputDelay) || inputDelay < 0 || inputDelay > MAX_INPUT_DELAY) return null;
+    if (message.resume !== undefined && (typeof message.resume !== "string" || !RESUME_SNAPSHOT_PATTERN.test(message.resume))) return null;
+    if (message.signature !== undefined && (typeof message.signature !== "string" || !START_SIGNATURE_PATTERN.test(message.signature))) return null;
     const roster = [...message.roster].sort((a,b) => a.playerId.localeCompare(b.playerId));
     if (roster.some((entry,index) => entry.index !== index || !/^[0-9a-f]{32}$/.test(entry.playerId) ||
+        (entry.simulation !== undefined && (typeof entry.simulation !== "string" || !SIMULATION_PATTERN.test(entry.simulation))) ||
+        (entry.cosmetics !== undefined && (typeof entry.cosmetics !== "string" || !COSMETICS_PATTERN.test(entry.cosmetics))) ||
+        (entry.ratings !== undefined && (typeof entry.ratings !== "string" || !RATINGS_PATTERN.test(entry.ratings))) ||
         !Number.isSafeInteger(entry.score) || entry.score < 0 || entry.score > 0xffffffff) ||
         !roster.some(entry => entry.playerId === session.localPlayerId)) return null;
-    return { ...message, roster, matchGeneration: message.matchGeneration ?? session.matchGeneration };
+    return { ...message, roster, inputDelay, resume: message.resume ?? "", signature: message.signature ?? "", matchGeneration: message.matchGeneration ?? session.matchGeneration };
 }
 
 function closeLobbyRound(session, epoch, round) {
     if (session.epoch !== epoch || session.round !== round || session.closedRound === `${epoch}:${round}`) return false;
     session.closedRound = `${epoch}:${round}`;
     session.status = 0;
+    clearPeerReconnects(session);
     for (const channel of session.channels.values()) channel.close();
     for (const peer of session.peers.values()) peer.close();
     session.channels.clear();
@@ -234,6 +338,10 @@ async function installLobbyStart(session, start, bufferedSignals = []) {
     session.epoch = start.epoch;
     session.round = start.round;
     session.matchGeneration = start.matchGeneration;
+    session.inputDelay = start.inputDelay;
+    session.resume = start.resume;
+    session.signature = start.signature;
+    session.resumeRequest = null;
     session.status = 0;
     session.closedRound = null;
     window.clearTimeout(session.timeout);
@@ -248,7 +356,7 @@ async function installLobbyStart(session, start, bufferedSignals = []) {
     }
 }
 
-function connectLobbyInternal(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId, assignment = null, existingId = 0) {
+function connectLobbyInternal(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId, cosmetics, ratings, simulation, assignment = null, existingId = 0) {
     const endpoint = (baseUrl || `${location.protocol === "https:" ? "wss:" : "ws:"}//${location.host}/lobby`).replace(/\/match\/?$/, "/lobby").replace(/\/queue\/?$/, "/lobby");
     const modeName = mode === 0 ? "duel" : "deathmatch";
     const identityKey = `ghost-lobby-v3:${room}`;
@@ -259,15 +367,53 @@ function connectLobbyInternal(baseUrl, room, mode, capacity, profileName, palett
     const reconnect = credentials && /^[0-9a-f]{32}$/.test(credentials.playerId) && /^[0-9a-f]{32}$/.test(credentials.reconnectToken)
         ? `&playerId=${credentials.playerId}&reconnectToken=${credentials.reconnectToken}` : "";
     const handoff = assignment ? `&queueTicket=${assignment.ticket}&queueExpires=${assignment.expiresAt}&queueToken=${assignment.token}` : "";
-    const url = `${endpoint.replace(/\/$/, "")}/${encodeURIComponent(room)}?protocol=3&mode=${modeName}&capacity=${capacity}${reconnect}${handoff}`;
-    const ws = new WebSocket(url);
+    const lobbyUrl = `${endpoint.replace(/\/$/, "")}/${encodeURIComponent(room)}?protocol=3&mode=${modeName}&capacity=${capacity}`;
+    const ws = new WebSocket(`${lobbyUrl}${reconnect}${handoff}`);
     const id = existingId || nextTransportId++ || nextTransportId++;
-    const session = { id, ws, identityKey, status: 0, error: "", lobby: true, assignmentHandoff: !!assignment, mode, capacity, inbox: [], peers: new Map(), channels: new Map(), pendingIce: new Map(), openPeers: new Set(), peerLastPacketAt: new Map(), roster: [], localPlayerId: "", seed: "", epoch: 0, round: 0, matchGeneration: 0, pendingStart: null, pendingSignals: [], closedRound: null, control: [], signalChain: Promise.resolve(), timeout: 0, heartbeat: 0, queuePhase: assignment ? 4 : 0, queueCount: 0, profileName, paletteId, cosmeticId, iceServers: DEFAULT_ICE_SERVERS, turnExpiresAt: null, iceHasTurn: false, telemetry: [0,0,0,0,reconnect ? 1 : 0,0,0,0,0,0,0] };
+    const session = { id, ws, lobbyUrl, identityKey, status: 0, error: "", lobby: true, assignmentHandoff: !!assignment, mode, capacity, inbox: [], peers: new Map(), channels: new Map(), pendingIce: new Map(), openPeers: new Set(), peerLastPacketAt: new Map(), roster: [], localPlayerId: "", seed: "", epoch: 0, round: 0, matchGeneration: 0, inputDelay: DEFAULT_INPUT_DELAY, latencySampling: false, latencyReported: null, peerReconnects: new Map(), controlReopen: 0, controlResumeUntil: 0, resume: "", resumeRequest: null, signature: "", pendingStart: null, pendingSignals: [], closedRound: null, control: [], signalChain: Promise.resolve(), timeout: 0, heartbeat: 0, queuePhase: assignment ? 4 : 0, queueCount: 0, profileName, paletteId, cosmeticId, cosmetics: COSMETICS_PATTERN.test(cosmetics ?? "") ? cosmetics : "", ratings: RATINGS_PATTERN.test(ratings ?? "") ? ratings : "", simulation: SIMULATION_PATTERN.test(simulation ?? "") ? simulation : "", iceServers: DEFAULT_ICE_SERVERS, turnExpiresAt: null, iceHasTurn: false, telemetry: [0,0,0,0,reconnect ? 1 : 0,0,0,0,0,0,0] };
     networks.set(id, session);
     session.timeout = window.setTimeout(() => fail(session, assignment ? "assignment handoff timed out" : "lobby matchmaking timed out"), assignment ? ASSIGNMENT_HANDOFF_TIMEOUT_MS : MATCHMAKING_TIMEOUT_MS);
+    bindLobbySocket(session, ws);
+    return id;
+}
+
+function storedLobbyIdentity(session) {
+    let credentials = null;
+    try { credentials = JSON.parse(sessionStorage.getItem(session.identityKey) || "null"); } catch (_) {}
+    return credentials && /^[0-9a-f]{32}$/.test(credentials.playerId) && /^[0-9a-f]{32}$/.test(credentials.reconnectToken) ? credentials : null;
+}
+
+function controlResumable(session) {
+    return isCurrent(session) && session.status === 1 && session.roster.length > 0 &&
+        (!session.controlResumeUntil || Date.now() < session.controlResumeUntil);
+}
+
+// Reopens a dropped control socket with the stored rotating identity while the
+// round's peers keep playing. The resumed epoch/round lets the Worker keep the
+// current bootstrap instead of batching a changed epoch as it does for reloads.
+function reopenLobbyControl(session) {
+    if (!controlResumable(session)) return false;
+    const credentials = storedLobbyIdentity(session);
+    if (!credentials) return false;
+    if (!session.controlResumeUntil) {
+        session.controlResumeUntil = Date.now() + PEER_RECONNECT_WINDOW_MS;
+        session.telemetry[4]++;
+    }
+    window.clearTimeout(session.controlReopen);
+    session.controlReopen = window.setTimeout(() => {
+        if (!isCurrent(session) || session.status !== 1) return;
+        if (!controlResumable(session)) return fail(session, "lobby service did not reconnect in time");
+        const ws = new WebSocket(`${session.lobbyUrl}&playerId=${credentials.playerId}&reconnectToken=${credentials.reconnectToken}&resumeEpoch=${session.epoch}&resumeRound=${session.round}`);
+        session.ws = ws;
+        bindLobbySocket(session, ws);
+    }, CONTROL_REOPEN_DELAY_MS);
+    return true;
+}
+
+function bindLobbySocket(session, ws) {
     ws.onopen = () => {};
     ws.onmessage = ({ data }) => {
-        if (!isCurrent(session) || typeof data !== "string" || data.length > 16384) return;
+        if (!isCurrent(session) || session.ws !== ws || typeof data !== "string" || data.length > 16384) return;
         session.signalChain = session.signalChain.then(async () => {
             const message = JSON.parse(data);
             if (!message || typeof message !== "object" || typeof message.type !== "string") throw new Error("invalid lobby message");
@@ -283,8 +429,15 @@ function connectLobbyInternal(baseUrl, room, mode, capacity, profileName, palett
                     window.clearTimeout(session.timeout);
                     session.timeout = window.setTimeout(() => fail(session, "lobby WebRTC timed out"), MATCHMAKING_TIMEOUT_MS);
                 }
-                sessionStorage.setItem(identityKey, JSON.stringify({ playerId: message.playerId, reconnectToken: message.reconnectToken }));
-                session.ws.send(JSON.stringify({ type: "profile", name: profileName, paletteId, cosmeticId }));
+                sessionStorage.setItem(session.identityKey, JSON.stringify({ playerId: message.playerId, reconnectToken: message.reconnectToken }));
+                if (session.controlResumeUntil) {
+                    // Profile and readiness are already durable on the Worker;
+                    // only peers that dropped alongside the socket need signals.
+                    session.controlResumeUntil = 0;
+                    for (const pending of session.peerReconnects.values()) pending.restart();
+                    return;
+                }
+                session.ws.send(JSON.stringify({ type: "profile", name: session.profileName, paletteId: session.paletteId, cosmeticId: session.cosmeticId, ...(session.cosmetics ? { cosmetics: session.cosmetics } : {}), ...(session.ratings ? { ratings: session.ratings } : {}), ...(session.simulation ? { simulation: session.simulation } : {}) }));
                 session.ws.send(JSON.stringify({ type: "ready" }));
             } else if (message.type === "start") {
                 const start = validLobbyStart(session, message);
@@ -326,20 +479,34 @@ function connectLobbyInternal(baseUrl, room, mode, capacity, profileName, palett
                     session.reconnecting = true;
                 }
                 return;
+            } else if (message.type === "resume_request") {
+                // A surviving client answers with its confirmed-frame snapshot
+                // so a reloaded peer's changed epoch resumes the same round.
+                if (Number.isInteger(message.epoch) && Number.isInteger(message.round) && sameRound(session, message.epoch, message.round) && session.status === 1) {
+                    session.resumeRequest = { epoch: message.epoch, round: message.round };
+                }
+                return;
             } else if (["round_commit", "round_abort", "presence", "profile_accepted", "report_ack", "leave_at_boundary_ack", "pong"].includes(message.type)) {
                 return;
             } else if (message.type === "error") {
+                // Signals to a peer whose control socket is itself reopening are
+                // retried by the reconnect re-offer until the window closes.
+                if (message.error === "target_offline" && session.peerReconnects.size > 0) return;
                 throw new Error(typeof message.error === "string" ? message.error : "lobby error");
             } else throw new Error("unsupported lobby message");
         }).catch(error => fail(session, error));
     };
-    ws.onerror = () => fail(session, "could not reach lobby service");
-    ws.onclose = () => { if (isCurrent(session) && session.status !== 2) fail(session, "lobby service disconnected"); };
-    return id;
+    ws.onerror = () => { if (session.ws === ws && !controlResumable(session)) fail(session, "could not reach lobby service"); };
+    ws.onclose = ({ code } = {}) => {
+        if (!isCurrent(session) || session.ws !== ws || session.status === 2) return;
+        // Supersession, policy violations and deliberate closes are final.
+        if (![1000, 1008, 4001].includes(code) && reopenLobbyControl(session)) return;
+        fail(session, "lobby service disconnected");
+    };
 }
 
-export function cloudflare_connect_lobby(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId) {
-    return connectLobbyInternal(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId);
+export function cloudflare_connect_lobby(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId, cosmetics, ratings, simulation) {
+    return connectLobbyInternal(baseUrl, room, mode, capacity, profileName, paletteId, cosmeticId, cosmetics, ratings, simulation);
 }
 
 function validAssignment(message, ticket) {
@@ -372,7 +539,7 @@ function queueStatus(session, message) {
     return false;
 }
 
-export function cloudflare_connect_queue(baseUrl, compatibilityRoom, preference, profileName, paletteId, cosmeticId) {
+export function cloudflare_connect_queue(baseUrl, compatibilityRoom, preference, profileName, paletteId, cosmeticId, cosmetics, ratings, simulation) {
     const endpoint = (baseUrl || `${location.protocol === "https:" ? "wss:" : "ws:"}//${location.host}/queue`).replace(/\/match\/?$/, "/queue").replace(/\/lobby\/?$/, "/queue");
     const url = `${endpoint.replace(/\/$/, "")}/${encodeURIComponent(compatibilityRoom)}?protocol=4&preference=${encodeURIComponent(preference)}`;
     const ws = new WebSocket(url);
@@ -410,7 +577,7 @@ export function cloudflare_connect_queue(baseUrl, compatibilityRoom, preference,
                 networks.delete(id);
                 ws.close(1000, "assignment accepted");
                 const mode = message.mode === "duel" ? 0 : 1;
-                connectLobbyInternal(baseUrl, message.room, mode, message.capacity, profileName, paletteId, cosmeticId, message, id);
+                connectLobbyInternal(baseUrl, message.room, mode, message.capacity, profileName, paletteId, cosmeticId, cosmetics, ratings, simulation, message, id);
                 return;
             }
             if (message?.type === "error") throw new Error(typeof message.error === "string" ? message.error : "queue error");
@@ -443,12 +610,49 @@ export function cloudflare_queue_withdraw_start_vote(id) {
 
 export function cloudflare_lobby_local_id(id) { return current(id)?.localPlayerId || ""; }
 export function cloudflare_lobby_mode(id) { return current(id)?.mode ?? 0; }
+export function cloudflare_lobby_capacity(id) { return current(id)?.capacity ?? 0; }
 export function cloudflare_lobby_generation(id) { return current(id)?.matchGeneration ?? 0; }
 export function cloudflare_lobby_control(id) { return current(id)?.control?.shift() ?? null; }
 export function cloudflare_lobby_rematch_request(id, generation, nonce) { const session=current(id); if (!session || session.ws.readyState!==WebSocket.OPEN) return false; try { session.ws.send(JSON.stringify({type:"rematch_request",generation,nonce})); return true; } catch (error) { fail(session,error); return false; } }
 export function cloudflare_lobby_rematch_response(id, generation, nonce, accept) { const session=current(id); if (!session || session.ws.readyState!==WebSocket.OPEN) return false; try { session.ws.send(JSON.stringify({type:"rematch_response",generation,nonce,accept})); return true; } catch (error) { fail(session,error); return false; } }
 export function cloudflare_lobby_leave(id, requeue) { const session=current(id); if (!session || session.ws.readyState!==WebSocket.OPEN) return false; try { session.ws.send(JSON.stringify({type:requeue?"requeue":"leave"})); return true; } catch (error) { fail(session,error); return false; } }
 export function cloudflare_lobby_leave_at_boundary(id) { const session=current(id); if (!session || session.ws.readyState!==WebSocket.OPEN || !session.roster.some(entry => entry.playerId === session.localPlayerId)) return false; try { session.ws.send(JSON.stringify({type:"leave_at_boundary"})); return true; } catch (error) { fail(session,error); return false; } }
+export function cloudflare_lobby_input_delay(id) { return current(id)?.inputDelay ?? DEFAULT_INPUT_DELAY; }
+// Samples every open peer once per epoch round and reports only the slowest
+// RTT. Measurement is best-effort and must never affect connectivity.
+export function cloudflare_lobby_sample_latency(id) {
+    const session = current(id);
+    if (!session?.lobby || session.status !== 1 || session.latencySampling || session.peers.size === 0 ||
+        session.ws.readyState !== WebSocket.OPEN) return false;
+    const epoch = session.epoch, round = session.round, key = `${epoch}:${round}`;
+    if (session.latencyReported === key) return false;
+    session.latencySampling = true;
+    Promise.all([...session.peers.values()].map(peer => peerRoundTripMs(peer).catch(() => null))).then(samples => {
+        session.latencySampling = false;
+        if (!sameRound(session, epoch, round) || session.latencyReported === key || samples.some(sample => sample === null)) return;
+        try {
+            session.ws.send(JSON.stringify({ type: "latency", epoch, round, rttMs: Math.max(...samples) }));
+            session.latencyReported = key;
+        } catch (_) { /* The next barrier samples again. */ }
+    });
+    return true;
+}
+export function cloudflare_lobby_resume(id) { return current(id)?.resume || ""; }
+export function cloudflare_lobby_signature(id) { return current(id)?.signature || ""; }
+export function cloudflare_lobby_take_resume_request(id) {
+    const session = current(id);
+    if (!session?.resumeRequest) return false;
+    const request = session.resumeRequest;
+    session.resumeRequest = null;
+    return sameRound(session, request.epoch, request.round);
+}
+export function cloudflare_lobby_send_resume(id, epoch, round, snapshot) {
+    const session = current(id);
+    if (!session?.lobby || session.ws.readyState !== WebSocket.OPEN || !sameRound(session, epoch, round) ||
+        !RESUME_SNAPSHOT_PATTERN.test(snapshot)) return false;
+    try { session.ws.send(JSON.stringify({ type: "resume_snapshot", epoch, round, snapshot })); return true; }
+    catch (_) { return false; }
+}
 export function cloudflare_lobby_seed(id) { return current(id)?.seed || ""; }
 export function cloudflare_lobby_epoch(id) { return current(id)?.epoch ?? 0; }
 export function cloudflare_lobby_round(id) { return current(id)?.round ?? 0; }
@@ -458,6 +662,9 @@ export function cloudflare_lobby_pending_round(id) { return current(id)?.pending
 export function cloudflare_lobby_roster_len(id) { return current(id)?.roster?.length ?? 0; }
 export function cloudflare_lobby_roster_id(id, index) { return current(id)?.roster?.[index]?.playerId || ""; }
 export function cloudflare_lobby_roster_score(id, index) { return current(id)?.roster?.[index]?.score ?? 0; }
+export function cloudflare_lobby_roster_simulation(id, index) { return current(id)?.roster?.[index]?.simulation || ""; }
+export function cloudflare_lobby_roster_cosmetics(id, index) { return current(id)?.roster?.[index]?.cosmetics || ""; }
+export function cloudflare_lobby_roster_ratings(id, index) { return current(id)?.roster?.[index]?.ratings || ""; }
 export function cloudflare_lobby_send(id, epoch, to, packet) {
     const session = current(id);
     const channel = session?.channels?.get?.(to);
@@ -510,6 +717,10 @@ export function cloudflare_lobby_promote_pending(id, oldEpoch, oldRound, pending
         session.seed = start.seed;
         session.round = start.round;
         session.matchGeneration = start.matchGeneration;
+        session.inputDelay = start.inputDelay;
+        session.resume = start.resume;
+        session.signature = start.signature;
+        session.resumeRequest = null;
         session.inbox.length = 0;
         session.pendingStart = null;
         session.pendingSignals.length = 0;
@@ -526,7 +737,9 @@ export function cloudflare_close_lobby(id) {
     if (!session) return;
     networks.delete(id);
     window.clearTimeout(session.timeout);
+    window.clearTimeout(session.controlReopen);
     window.clearInterval(session.heartbeat);
+    clearPeerReconnects(session);
     try { if (session.identityKey) sessionStorage.removeItem(session.identityKey); } catch (_) {}
     if (session.queue && session.ws.readyState === WebSocket.OPEN && session.ticket) {
         try { session.ws.send(JSON.stringify({ type: "cancel" })); } catch (_) {}
@@ -84,6 +84,9 @@ fn queue_status_from_scalars(
 pub struct LobbyMatchInfo {
     pub local_player: PlayerId,
     pub mode: u32,
+    /// Roster capacity this client asked the lobby for.
+    pub capacity: u32,
+    pub match_generation: u32,
     pub seed: u64,
     pub match_id: u128,
     pub epoch: u32,
@@ -91,6 +94,69 @@ pub struct LobbyMatchInfo {
     pub roster: Vec<(PlayerId, usize)>,
     /// Server-committed scores from the immutable start snapshot.
     pub scores: Vec<(PlayerId, u32)>,
+    /// Roster-wide input delay the lobby derived from reported peer RTTs.
+    pub input_delay: usize,
+    /// Survivor-provided confirmed-frame snapshot that continues an
+    /// interrupted round after a reload, or empty for an ordinary start.
+    pub resume: String,
+    /// Lobby service's Ed25519 signature over `canonical_start`, as hex.
+    pub signature: String,
+    /// Simulation fingerprint each roster member reported with its profile;
+    /// `None` when the member or lobby service predates the handshake.
+    pub simulations: Vec<(PlayerId, Option<u64>)>,
+    /// Equipped cosmetics each roster member reported with its profile, in
+    /// the `hat.trail.bullet` form; empty when not reported.
+    pub cosmetics: Vec<(PlayerId, String)>,
+    /// Self-reported skill ratings each roster member sent with its profile,
+    /// in the `duel.deathmatch` form; empty when not reported.
+    pub ratings: Vec<(PlayerId, String)>,
+}
+
+impl LobbyMatchInfo {
+    /// Mirrors the worker's `canonicalLobbyStart`: every field this struct
+    /// carries except the local player and the signature itself, with the
+    /// roster in handle order.
+    pub fn canonical_start(&self) -> String {
+        let mode = if self.mode == 0 { "duel" } else { "deathmatch" };
+        let mut entries = self.scores.clone();
+        entries.sort_by_key(|entry| entry.0);
+        let roster = entries
+            .iter()
+            .map(|(player_id, score)| {
+                let simulation = self
+                    .simulations
+                    .iter()
+                    .find(|(id, _)| id == player_id)
+                    .and_then(|(_, simulation)| *simulation)
+                    .map(|simulation| format!("{simulation:016x}"))
+                    .unwrap_or_default();
+                format!(
+                    "{:032x}:{score}:{simulation}:{}:{}",
+                    player_id.0,
+                    reported(&self.cosmetics, *player_id),
+                    reported(&self.ratings, *player_id),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "v3-start\n{mode}\n{}\n{}\n{}\n{}\n{:032x}\n{}\n{roster}\n{}",
+            self.capacity,
+            self.match_generation,
+            self.epoch,
+            self.round,
+            self.match_id,
+            self.input_delay,
+            self.resume
+        )
+    }
+}
+
+fn reported(values: &[(PlayerId, String)], player_id: PlayerId) -> &str {
+    values
+        .iter()
+        .find(|(id, _)| *id == player_id)
+        .map_or("", |(_, value)| value.as_str())
 }
 
 #[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -127,6 +193,7 @@ pub struct CloudflareSocket {
 }
 
 impl CloudflareSocket {
+    #[allow(clippy::too_many_arguments)]
     pub fn connect_queue(
         &mut self,
         signaling_url: &str,
@@ -135,6 +202,9 @@ impl CloudflareSocket {
         profile_name: &str,
         palette_id: u8,
         cosmetic_id: u8,
+        cosmetics: &str,
+        ratings: &str,
+        simulation: &str,
     ) {
         self.close();
         if !matches!(preference, "any" | "duel" | "deathmatch") {
@@ -150,6 +220,9 @@ impl CloudflareSocket {
                 profile_name,
                 palette_id as u32,
                 cosmetic_id as u32,
+                cosmetics,
+                ratings,
+                simulation,
             );
             self.epoch = 0;
             self.round = 0;
@@ -164,11 +237,15 @@ impl CloudflareSocket {
                 profile_name,
                 palette_id,
                 cosmetic_id,
+                cosmetics,
+                ratings,
+                simulation,
             );
             self.native_error = Some("online play is only supported in browser builds".into());
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn connect_lobby(
         &mut self,
         signaling_url: &str,
@@ -178,6 +255,9 @@ impl CloudflareSocket {
         profile_name: &str,
         palette_id: u8,
         cosmetic_id: u8,
+        cosmetics: &str,
+        ratings: &str,
+        simulation: &str,
     ) {
         self.close();
         if room.is_empty()
@@ -203,6 +283,9 @@ impl CloudflareSocket {
                 profile_name,
                 palette_id as u32,
                 cosmetic_id as u32,
+                cosmetics,
+                ratings,
+                simulation,
             );
             self.epoch = 0;
             self.round = 0;
@@ -217,6 +300,9 @@ impl CloudflareSocket {
                 profile_name,
                 palette_id,
                 cosmetic_id,
+                cosmetics,
+                ratings,
+                simulation,
             );
             self.native_error = Some("online play is only supported in browser builds".into());
         }
@@ -304,11 +390,31 @@ impl CloudflareSocket {
                 return None;
             }
             let mut snapshot = Vec::with_capacity(len);
+            let mut simulations = Vec::with_capacity(len);
+            let mut cosmetics = Vec::with_capacity(len);
+            let mut ratings = Vec::with_capacity(len);
             for index in 0..len {
+                let player_id =
+                    parse_player_id(&cloudflare_lobby_roster_id(self.transport_id, index as u32))?;
                 snapshot.push((
-                    parse_player_id(&cloudflare_lobby_roster_id(self.transport_id, index as u32))?,
+                    player_id,
                     cloudflare_lobby_roster_score(self.transport_id, index as u32),
                 ));
+                simulations.push((
+                    player_id,
+                    parse_simulation(&cloudflare_lobby_roster_simulation(
+                        self.transport_id,
+                        index as u32,
+                    )),
+                ));
+                cosmetics.push((
+                    player_id,
+                    cloudflare_lobby_roster_cosmetics(self.transport_id, index as u32),
+                ));
+                ratings.push((
+                    player_id,
+                    cloudflare_lobby_roster_ratings(self.transport_id, index as u32),
+                ));
             }
             snapshot.sort_by_key(|entry| entry.0);
             let roster = snapshot
@@ -324,12 +430,20 @@ impl CloudflareSocket {
             return Some(LobbyMatchInfo {
                 local_player,
                 mode,
+                capacity: cloudflare_lobby_capacity(self.transport_id),
+                match_generation: cloudflare_lobby_generation(self.transport_id),
                 seed: match_id as u64,
                 match_id,
                 epoch,
                 round: cloudflare_lobby_round(self.transport_id),
                 roster,
                 scores,
+                input_delay: cloudflare_lobby_input_delay(self.transport_id) as usize,
+                resume: cloudflare_lobby_resume(self.transport_id),
+                signature: cloudflare_lobby_signature(self.transport_id),
+                simulations,
+                cosmetics,
+                ratings,
             });
         }
         #[cfg(not(target_arch = "wasm32"))]
@@ -527,6 +641,36 @@ impl CloudflareSocket {
         false
     }
 
+    /// Best-effort RTT sample of every open peer for the current round. The
+    /// slowest result is reported to the lobby and shapes the next start.
+    pub fn sample_peer_latency(&self) -> bool {
+        #[cfg(target_arch = "wasm32")]
+        if self.transport_id != 0 {
+            return cloudflare_lobby_sample_latency(self.transport_id);
+        }
+        false
+    }
+
+    /// Takes a pending request from the lobby for this round's confirmed
+    /// snapshot, sent while a reloaded roster member reconnects.
+    pub fn take_resume_request(&self) -> bool {
+        #[cfg(target_arch = "wasm32")]
+        if self.transport_id != 0 {
+            return cloudflare_lobby_take_resume_request(self.transport_id);
+        }
+        false
+    }
+
+    pub fn send_resume_snapshot(&self, epoch: u32, round: u32, snapshot: &str) -> bool {
+        #[cfg(target_arch = "wasm32")]
+        if self.transport_id != 0 {
+            return cloudflare_lobby_send_resume(self.transport_id, epoch, round, snapshot);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = (epoch, round, snapshot);
+        false
+    }
+
     pub fn transport_stalled(&self) -> bool {
         #[cfg(target_arch = "wasm32")]
         if self.transport_id != 0 {
@@ -602,6 +746,14 @@ impl Drop for CloudflareSocket {
     }
 }
 
+#[cfg(any(test, target_arch = "wasm32"))]
+fn parse_simulation(value: &str) -> Option<u64> {
+    if value.len() != 16 || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(value, 16).ok()
+}
+
 fn parse_player_id(value: &str) -> Option<PlayerId> {
     if value.len() != 32 || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
         return None;
@@ -690,6 +842,9 @@ extern "C" {
         profile_name: &str,
         palette_id: u32,
         cosmetic_id: u32,
+        cosmetics: &str,
+        ratings: &str,
+        simulation: &str,
     ) -> u32;
     fn cloudflare_queue_phase(id: u32) -> u32;
     fn cloudflare_queue_count(id: u32) -> u32;
@@ -707,9 +862,13 @@ extern "C" {
         profile_name: &str,
         palette_id: u32,
         cosmetic_id: u32,
+        cosmetics: &str,
+        ratings: &str,
+        simulation: &str,
     ) -> u32;
     fn cloudflare_lobby_local_id(id: u32) -> String;
     fn cloudflare_lobby_mode(id: u32) -> u32;
+    fn cloudflare_lobby_capacity(id: u32) -> u32;
     fn cloudflare_lobby_generation(id: u32) -> u32;
     fn cloudflare_lobby_control(id: u32) -> wasm_bindgen::JsValue;
     fn cloudflare_lobby_rematch_request(id: u32, generation: u32, nonce: &str) -> bool;
@@ -721,6 +880,15 @@ extern "C" {
     ) -> bool;
     fn cloudflare_lobby_leave(id: u32, requeue: bool) -> bool;
     fn cloudflare_lobby_leave_at_boundary(id: u32) -> bool;
+    fn cloudflare_lobby_input_delay(id: u32) -> u32;
+    fn cloudflare_lobby_sample_latency(id: u32) -> bool;
+    fn cloudflare_lobby_resume(id: u32) -> String;
+    fn cloudflare_lobby_signature(id: u32) -> String;
+    fn cloudflare_lobby_roster_simulation(id: u32, index: u32) -> String;
+    fn cloudflare_lobby_roster_cosmetics(id: u32, index: u32) -> String;
+    fn cloudflare_lobby_roster_ratings(id: u32, index: u32) -> String;
+    fn cloudflare_lobby_take_resume_request(id: u32) -> bool;
+    fn cloudflare_lobby_send_resume(id: u32, epoch: u32, round: u32, snapshot: &str) -> bool;
     fn cloudflare_lobby_seed(id: u32) -> String;
     fn cloudflare_lobby_epoch(id: u32) -> u32;
     fn cloudflare_lobby_round(id: u32) -> u32;
@@ -753,6 +921,53 @@ extern "C" {
 mod tests {
     use super::*;
 
+    #[test]
+    fn simulation_fingerprints_parse_strictly() {
+        assert_eq!(
+            parse_simulation("0123456789abcdef"),
+            Some(0x0123_4567_89ab_cdef)
+        );
+        for invalid in ["", "0123", "0123456789abcdefa", "+123456789abcdef"] {
+            assert_eq!(parse_simulation(invalid), None);
+        }
+    }
+
+    #[test]
+    fn canonical_start_matches_the_worker_vector() {
+        let id = |digit: char| parse_player_id(&digit.to_string().repeat(32)).unwrap();
+        let info = LobbyMatchInfo {
+            local_player: id('a'),
+            mode: 1,
+            capacity: 4,
+            match_generation: 3,
+            seed: 0x0123456789abcdef,
+            match_id: 0x0123456789abcdef0123456789abcdef,
+            epoch: 4,
+            round: 1,
+            roster: vec![(id('a'), 0), (id('b'), 1), (id('c'), 2)],
+            scores: vec![(id('c'), 1), (id('a'), 0), (id('b'), 2)],
+            input_delay: 2,
+            resume: String::new(),
+            signature: String::new(),
+            simulations: vec![
+                (id('c'), Some(0xfedcba9876543210)),
+                (id('a'), None),
+                (id('b'), Some(0x0123456789abcdef)),
+            ],
+            cosmetics: vec![(id('b'), "1.0.2".into()), (id('a'), String::new())],
+            ratings: vec![(id('b'), "1510.1490".into())],
+        };
+        assert_eq!(
+            info.canonical_start(),
+            format!(
+                "v3-start\ndeathmatch\n4\n3\n4\n1\n0123456789abcdef0123456789abcdef\n2\n{}:0:::,{}:2:0123456789abcdef:1.0.2:1510.1490,{}:1:fedcba9876543210::\n",
+                "a".repeat(32),
+                "b".repeat(32),
+                "c".repeat(32)
+            )
+        );
+    }
+
     #[test]
     fn queue_status_scalars_are_strict_and_bounded() {
         assert_eq!(
@@ -789,7 +1004,7 @@ mod tests {
     #[test]
     fn queue_connect_rejects_invalid_preference_before_transport() {
         let mut socket = CloudflareSocket::default();
-        socket.connect_queue("", "battle-0-7-0", "surprise", "Ghost", 0, 0);
+        socket.connect_queue("", "battle-0-7-0", "surprise", "Ghost", 0, 0, "");
         assert_eq!(
             socket.state(),
             ConnectionState::Failed("invalid public queue preference".into())
@@ -0,0 +1,224 @@
+//! Casual achievements earned from confirmed round outcomes.
+//!
+//! Achievements are evaluated only when `CasualProfile` first records a
+//! round's stable event ID, so rollback replays and reloads cannot award one
+//! twice. Keys are stored in the profile and referenced by the cosmetics
+//! catalog; never rename one.
+
+use super::session::GameMode;
+
+const REGULAR_ROUNDS: u64 = 25;
+const TRIPLE_HAUNT_ELIMINATIONS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AchievementDefinition {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    condition: Condition,
+}
+
+/// What a round must show to earn an achievement. Each definition names its
+/// own, so adding an achievement without a rule does not compile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Condition {
+    WinRound,
+    WinShielded,
+    TripleHaunt,
+    WinMatch,
+    CleanSweep,
+    Regular,
+}
+
+pub const ACHIEVEMENTS: [AchievementDefinition; 6] = [
+    AchievementDefinition {
+        key: "first-point",
+        name: "First Point",
+        description: "Win a round.",
+        condition: Condition::WinRound,
+    },
+    AchievementDefinition {
+        key: "still-shielded",
+        name: "Still Shielded",
+        description: "Win a round with a shield charge left.",
+        condition: Condition::WinShielded,
+    },
+    AchievementDefinition {
+        key: "triple-haunt",
+        name: "Triple Haunt",
+        description: "Eliminate 3 ghosts in one Last Ghost Standing round.",
+        condition: Condition::TripleHaunt,
+    },
+    AchievementDefinition {
+        key: "match-point",
+        name: "Match Point",
+        description: "Win a match.",
+        condition: Condition::WinMatch,
+    },
+    AchievementDefinition {
+        key: "clean-sweep",
+        name: "Clean Sweep",
+        description: "Win a Duel 3–0.",
+        condition: Condition::CleanSweep,
+    },
+    AchievementDefinition {
+        key: "regular",
+        name: "Regular",
+        description: "Play 25 rounds.",
+        condition: Condition::Regular,
+    },
+];
+
+pub fn achievement(key: &str) -> Option<&'static AchievementDefinition> {
+    ACHIEVEMENTS.iter().find(|definition| definition.key == key)
+}
+
+/// The local player's view of one confirmed round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundStats {
+    pub mode: GameMode,
+    pub won_round: bool,
+    /// Eliminations credited to the local ghost this round.
+    pub eliminations: u32,
+    /// The local ghost still held a shield charge when the round resolved.
+    pub shield_left: bool,
+    pub match_completed: bool,
+    pub won_match: bool,
+    /// Highest score among the other players after this round.
+    pub best_opponent_score: u32,
+}
+
+impl RoundStats {
+    fn earns(&self, condition: Condition, rounds_played: u64) -> bool {
+        match condition {
+            Condition::WinRound => self.won_round,
+            Condition::WinShielded => self.won_round && self.shield_left,
+            Condition::TripleHaunt => {
+                self.mode == GameMode::Deathmatch && self.eliminations >= TRIPLE_HAUNT_ELIMINATIONS
+            }
+            Condition::WinMatch => self.won_match,
+            Condition::CleanSweep => {
+                self.mode == GameMode::Duel && self.won_match && self.best_opponent_score == 0
+            }
+            Condition::Regular => rounds_played >= REGULAR_ROUNDS,
+        }
+    }
+}
+
+/// Achievements this round earns that `held` does not already contain.
+/// `rounds_played` already counts the round.
+pub fn newly_earned(
+    stats: &RoundStats,
+    rounds_played: u64,
+    held: impl Fn(&str) -> bool,
+) -> Vec<&'static AchievementDefinition> {
+    ACHIEVEMENTS
+        .iter()
+        .filter(|definition| {
+            !held(definition.key) && stats.earns(definition.condition, rounds_played)
+        })
+        .collect()
+}
+
+/// A stored key is kept even when this build does not define it, so a
+/// profile written by a newer build survives a round trip.
+pub fn valid_achievement_key(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 32
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> RoundStats {
+        RoundStats {
+            mode: GameMode::Duel,
+            won_round: false,
+            eliminations: 0,
+            shield_left: false,
+            match_completed: false,
+            won_match: false,
+            best_opponent_score: 0,
+        }
+    }
+
+    fn keys(earned: Vec<&'static AchievementDefinition>) -> Vec<&'static str> {
+        earned
+            .into_iter()
+            .map(|definition| definition.key)
+            .collect()
+    }
+
+    #[test]
+    fn achievements_follow_their_round_conditions() {
+        assert!(keys(newly_earned(&stats(), 1, |_| false)).is_empty());
+        let shielded_win = RoundStats {
+            won_round: true,
+            shield_left: true,
+            ..stats()
+        };
+        assert_eq!(
+            keys(newly_earned(&shielded_win, 1, |_| false)),
+            ["first-point", "still-shielded"]
+        );
+        let triple = RoundStats {
+            mode: GameMode::Deathmatch,
+            eliminations: 3,
+            ..stats()
+        };
+        assert_eq!(keys(newly_earned(&triple, 1, |_| false)), ["triple-haunt"]);
+        // Only Last Ghost Standing rounds count toward a triple.
+        let duel_triple = RoundStats {
+            eliminations: 3,
+            ..stats()
+        };
+        assert!(keys(newly_earned(&duel_triple, 1, |_| false)).is_empty());
+        assert_eq!(keys(newly_earned(&stats(), 25, |_| false)), ["regular"]);
+    }
+
+    #[test]
+    fn clean_sweep_needs_a_shutout_duel_win() {
+        let sweep = RoundStats {
+            won_round: true,
+            match_completed: true,
+            won_match: true,
+            ..stats()
+        };
+        assert_eq!(
+            keys(newly_earned(&sweep, 3, |key| key == "first-point")),
+            ["match-point", "clean-sweep"]
+        );
+        let close = RoundStats {
+            best_opponent_score: 2,
+            ..sweep
+        };
+        assert_eq!(
+            keys(newly_earned(&close, 5, |_| false)),
+            ["first-point", "match-point"]
+        );
+        let lgs = RoundStats {
+            mode: GameMode::Deathmatch,
+            ..sweep
+        };
+        assert!(!keys(newly_earned(&lgs, 3, |_| false)).contains(&"clean-sweep"));
+    }
+
+    #[test]
+    fn keys_are_unique_and_storable() {
+        for (index, definition) in ACHIEVEMENTS.iter().enumerate() {
+            assert!(valid_achievement_key(definition.key));
+            assert_eq!(achievement(definition.key), Some(definition));
+            assert!(ACHIEVEMENTS[..index]
+                .iter()
+                .all(|other| other.key != definition.key));
+        }
+        let long = "x".repeat(33);
+        for invalid in ["", "Bad", "a,b", "a\tb", long.as_str()] {
+            assert!(!valid_achievement_key(invalid));
+        }
+    }
+}
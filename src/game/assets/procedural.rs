@@ -4,6 +4,8 @@
 
 use bevy::prelude::*;
 
+use crate::game::components::Weapon;
+
 pub const TRAP_SIZE: f32 = 0.70;
 pub const PICKUP_SIZE: f32 = 0.55;
 
@@ -67,10 +69,25 @@ pub fn wall_mortar_color() -> Color {
     Color::rgb(0.075, 0.13, 0.10)
 }
 
+/// Sandier than solid walls and darker with every hit taken.
+pub fn cracked_wall_color(hits_taken: u8) -> Color {
+    let shade = 1.0 - 0.18 * f32::from(hits_taken);
+    Color::rgb(0.42 * shade, 0.36 * shade, 0.26 * shade)
+}
+
 pub fn trap_color() -> Color {
     Color::rgb(0.75, 0.12, 0.20)
 }
 
+pub fn teleporter_color() -> Color {
+    Color::rgb(0.45, 0.30, 0.95)
+}
+
+/// Ectoplasm is drawn as a faint floor stain, readable under ghosts and pickups.
+pub fn ectoplasm_color() -> Color {
+    Color::rgba(0.40, 0.90, 0.30, 0.35)
+}
+
 pub fn speed_pickup_color() -> Color {
     Color::rgb(0.15, 0.85, 0.95)
 }
@@ -79,6 +96,24 @@ pub fn shield_pickup_color() -> Color {
     Color::rgb(0.95, 0.75, 0.15)
 }
 
+pub fn phase_pickup_color() -> Color {
+    Color::rgb(0.80, 0.85, 1.0)
+}
+
+pub fn health_pickup_color() -> Color {
+    Color::rgb(0.35, 0.95, 0.40)
+}
+
+/// Pickup tint, also reused for that weapon's bullets.
+pub fn weapon_pickup_color(weapon: Weapon) -> Color {
+    match weapon {
+        Weapon::Blaster => Color::WHITE,
+        Weapon::Spread => Color::rgb(0.95, 0.45, 0.85),
+        Weapon::Ricochet => Color::rgb(0.55, 0.60, 1.0),
+        Weapon::Heavy => Color::rgb(1.0, 0.45, 0.20),
+    }
+}
+
 /// Visible warning field outside the compact duel arena. Deliberately distinct
 /// from the black world background and solid green walls.
 pub fn void_color() -> Color {
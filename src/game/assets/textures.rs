@@ -15,12 +15,6 @@ pub struct ImageAssets {
     pub bullet: Handle<Image>,
     #[asset(path = "textures/character/ghost_base.png")]
     pub ghost: Handle<Image>,
-    #[asset(path = "textures/character/cosmetics/ghost_crown.png")]
-    pub ghost_crown: Handle<Image>,
-    #[asset(path = "textures/character/cosmetics/ghost_wizard.png")]
-    pub ghost_wizard: Handle<Image>,
-    #[asset(path = "textures/character/cosmetics/ghost_bow.png")]
-    pub ghost_bow: Handle<Image>,
     #[asset(texture_atlas(
         tile_size_x = 16.,
         tile_size_y = 16.,
@@ -0,0 +1,436 @@
+//! Daily and weekly challenges derived from the calendar.
+//!
+//! Every build picks the same challenges for the same UTC day or Monday-start
+//! week from a hash of the period, so they need no server and work offline.
+//! Round objectives advance only when `CasualProfile` first records a
+//! confirmed round, exactly like points. Progress is kept per challenge ID,
+//! and IDs embed their period, so yesterday's progress never counts today.
+
+use std::collections::BTreeMap;
+
+use super::{
+    achievements::RoundStats, fingerprint::fnv1a, practice::DRILL_SECONDS, session::GameMode,
+};
+
+pub const DAILY_REWARD_POINTS: u64 = 10;
+pub const WEEKLY_REWARD_POINTS: u64 = 40;
+const SECONDS_PER_DAY: u64 = 86_400;
+/// 1970-01-01 was a Thursday; shifting by three days starts weeks on Monday.
+const WEEK_OFFSET_DAYS: u64 = 3;
+const MAX_TRACKED_CHALLENGES: usize = 16;
+const MAX_CHALLENGE_ID_BYTES: usize = 24;
+
+const DAILY_OBJECTIVES: [Objective; 5] = [
+    Objective::WinRounds {
+        mode: Some(GameMode::Deathmatch),
+        count: 3,
+    },
+    Objective::WinRounds {
+        mode: Some(GameMode::Duel),
+        count: 3,
+    },
+    Objective::WinRounds {
+        mode: None,
+        count: 4,
+    },
+    Objective::PlayRounds { count: 8 },
+    Objective::Eliminations { count: 5 },
+];
+
+const WEEKLY_OBJECTIVES: [Objective; 4] = [
+    Objective::WinMatches { count: 3 },
+    Objective::WinRounds {
+        mode: Some(GameMode::Deathmatch),
+        count: 12,
+    },
+    Objective::Eliminations { count: 25 },
+    Objective::PlayRounds { count: 40 },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Daily,
+    Weekly,
+}
+
+impl Period {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Daily => "Daily",
+            Self::Weekly => "Weekly",
+        }
+    }
+
+    fn tag(self) -> char {
+        match self {
+            Self::Daily => 'd',
+            Self::Weekly => 'w',
+        }
+    }
+
+    fn length_days(self) -> u64 {
+        match self {
+            Self::Daily => 1,
+            Self::Weekly => 7,
+        }
+    }
+
+    fn offset_days(self) -> u64 {
+        match self {
+            Self::Daily => 0,
+            Self::Weekly => WEEK_OFFSET_DAYS,
+        }
+    }
+
+    /// Which day or week `unix_seconds` falls in.
+    fn index(self, unix_seconds: u64) -> u64 {
+        (unix_seconds / SECONDS_PER_DAY + self.offset_days()) / self.length_days()
+    }
+
+    /// Seconds until the next period starts.
+    pub fn seconds_left(self, unix_seconds: u64) -> u64 {
+        let next = (self.index(unix_seconds) + 1) * self.length_days() - self.offset_days();
+        next * SECONDS_PER_DAY - unix_seconds
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Win rounds, in one mode or any.
+    WinRounds {
+        mode: Option<GameMode>,
+        count: u32,
+    },
+    PlayRounds {
+        count: u32,
+    },
+    Eliminations {
+        count: u32,
+    },
+    WinMatches {
+        count: u32,
+    },
+    /// Reach `score` in a timed practice-arena run whose targets follow
+    /// `seed`, so everyone gets the same run that day.
+    TargetRun {
+        seed: u64,
+        score: u32,
+    },
+}
+
+impl Objective {
+    pub fn goal(self) -> u32 {
+        match self {
+            Self::WinRounds { count, .. }
+            | Self::PlayRounds { count }
+            | Self::Eliminations { count }
+            | Self::WinMatches { count } => count,
+            Self::TargetRun { score, .. } => score,
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::WinRounds { mode, count } => format!(
+                "Win {count} {}rounds",
+                match mode {
+                    Some(GameMode::Duel) => "Duel ",
+                    Some(GameMode::Deathmatch) => "Last Ghost Standing ",
+                    None => "",
+                }
+            ),
+            Self::PlayRounds { count } => format!("Play {count} rounds"),
+            Self::Eliminations { count } => format!("Eliminate {count} ghosts"),
+            Self::WinMatches { count } => format!("Win {count} matches"),
+            Self::TargetRun { score, .. } => format!(
+                "Score {score} in a {}-second target run",
+                DRILL_SECONDS as u32
+            ),
+        }
+    }
+
+    /// Progress one confirmed round adds.
+    fn round_progress(self, stats: &RoundStats) -> u32 {
+        match self {
+            Self::WinRounds { mode, .. } => {
+                u32::from(stats.won_round && (mode.is_none() || mode == Some(stats.mode)))
+            }
+            Self::PlayRounds { .. } => 1,
+            Self::Eliminations { .. } => stats.eliminations,
+            Self::WinMatches { .. } => u32::from(stats.won_match),
+            Self::TargetRun { .. } => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub id: String,
+    pub period: Period,
+    pub objective: Objective,
+    pub reward: u64,
+}
+
+/// The challenges running at `unix_seconds`: a round objective and a target
+/// run each day, and a larger round objective each week.
+pub fn active_challenges(unix_seconds: u64) -> Vec<Challenge> {
+    let daily = Period::Daily.index(unix_seconds);
+    let weekly = Period::Weekly.index(unix_seconds);
+    let daily_seed = period_seed(Period::Daily, daily);
+    let weekly_seed = period_seed(Period::Weekly, weekly);
+    let challenge = |period: Period, index: u64, slot: u8, objective, reward| Challenge {
+        id: format!("{}{index}-{slot}", period.tag()),
+        period,
+        objective,
+        reward,
+    };
+    vec![
+        challenge(
+            Period::Daily,
+            daily,
+            0,
+            DAILY_OBJECTIVES[(daily_seed % DAILY_OBJECTIVES.len() as u64) as usize],
+            DAILY_REWARD_POINTS,
+        ),
+        challenge(
+            Period::Daily,
+            daily,
+            1,
+            Objective::TargetRun {
+                seed: daily_seed,
+                score: 150 + (daily_seed >> 32) as u32 % 7 * 25,
+            },
+            DAILY_REWARD_POINTS,
+        ),
+        challenge(
+            Period::Weekly,
+            weekly,
+            0,
+            WEEKLY_OBJECTIVES[(weekly_seed % WEEKLY_OBJECTIVES.len() as u64) as usize],
+            WEEKLY_REWARD_POINTS,
+        ),
+    ]
+}
+
+fn period_seed(period: Period, index: u64) -> u64 {
+    fnv1a(format!("ghosties-challenge:{}{index}", period.tag()).as_bytes())
+}
+
+/// Progress toward the active challenges, keyed by challenge ID. A challenge
+/// is complete, and has paid its reward, once its progress reaches the goal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChallengeProgress(BTreeMap<String, u32>);
+
+impl ChallengeProgress {
+    pub fn get(&self, id: &str) -> u32 {
+        self.0.get(id).copied().unwrap_or_default()
+    }
+
+    pub fn is_complete(&self, challenge: &Challenge) -> bool {
+        self.get(&challenge.id) >= challenge.objective.goal()
+    }
+
+    /// Adds one confirmed round to every active round objective and returns
+    /// the challenges it completed.
+    pub fn advance_round(&mut self, active: &[Challenge], stats: &RoundStats) -> Vec<Challenge> {
+        self.retain_active(active);
+        active
+            .iter()
+            .filter(|challenge| {
+                self.raise(challenge, |progress| {
+                    progress.saturating_add(challenge.objective.round_progress(stats))
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Keeps the best score of a target run and returns whether it completed
+    /// the challenge just now.
+    pub fn record_target_run(&mut self, active: &[Challenge], id: &str, score: u32) -> bool {
+        self.retain_active(active);
+        active
+            .iter()
+            .find(|challenge| {
+                challenge.id == id && matches!(challenge.objective, Objective::TargetRun { .. })
+            })
+            .is_some_and(|challenge| self.raise(challenge, |best| best.max(score)))
+    }
+
+    /// Keeps the larger progress of both profiles for each challenge.
+    pub fn merge(&mut self, other: ChallengeProgress) {
+        for (id, progress) in other.0 {
+            let entry = self.0.entry(id).or_default();
+            *entry = (*entry).max(progress);
+        }
+        self.truncate();
+    }
+
+    pub fn encode(&self) -> String {
+        self.0
+            .iter()
+            .map(|(id, progress)| format!("{id}:{progress}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn decode(value: &str) -> Self {
+        let mut progress = Self(
+            value
+                .split(',')
+                .filter_map(|entry| entry.split_once(':'))
+                .filter(|(id, _)| valid_challenge_id(id))
+                .filter_map(|(id, progress)| Some((id.to_owned(), progress.parse().ok()?)))
+                .collect(),
+        );
+        progress.truncate();
+        progress
+    }
+
+    /// Applies `update` and reports whether it crossed the goal.
+    fn raise(&mut self, challenge: &Challenge, update: impl Fn(u32) -> u32) -> bool {
+        let before = self.get(&challenge.id);
+        let after = update(before);
+        if after == before {
+            return false;
+        }
+        self.0.insert(challenge.id.clone(), after);
+        before < challenge.objective.goal() && after >= challenge.objective.goal()
+    }
+
+    fn retain_active(&mut self, active: &[Challenge]) {
+        self.0
+            .retain(|id, _| active.iter().any(|challenge| challenge.id == *id));
+    }
+
+    fn truncate(&mut self) {
+        while self.0.len() > MAX_TRACKED_CHALLENGES {
+            self.0.pop_first();
+        }
+    }
+}
+
+fn valid_challenge_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_CHALLENGE_ID_BYTES
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2023-11-14 22:13 UTC, a Tuesday.
+    const TUESDAY: u64 = 1_700_000_000;
+
+    fn round(mode: GameMode, won_round: bool, eliminations: u32) -> RoundStats {
+        RoundStats {
+            mode,
+            won_round,
+            eliminations,
+            shield_left: false,
+            match_completed: false,
+            won_match: false,
+            best_opponent_score: 0,
+        }
+    }
+
+    #[test]
+    fn challenges_follow_the_calendar() {
+        let today = active_challenges(TUESDAY);
+        assert_eq!(today, active_challenges(TUESDAY - 22 * 3600));
+        assert_eq!(today.len(), 3);
+        assert_eq!(today[0].id, "d19675-0");
+        assert_eq!(today[2].id, "w2811-0");
+        assert!(
+            matches!(today[1].objective, Objective::TargetRun { score, .. }
+            if (150..=300).contains(&score))
+        );
+
+        // The day changes at UTC midnight; the week on Monday.
+        let tomorrow = active_challenges(TUESDAY + Period::Daily.seconds_left(TUESDAY));
+        assert_ne!(tomorrow[0].id, today[0].id);
+        assert_eq!(tomorrow[2], today[2]);
+        let monday = TUESDAY + Period::Weekly.seconds_left(TUESDAY);
+        assert_eq!(monday % SECONDS_PER_DAY, 0);
+        assert_eq!(Period::Weekly.seconds_left(monday), 7 * SECONDS_PER_DAY);
+        assert_ne!(active_challenges(monday)[2].id, today[2].id);
+        assert_eq!(active_challenges(monday - 1)[2].id, today[2].id);
+
+        // Different days usually pick different runs.
+        let seeds: std::collections::BTreeSet<_> = (0..14)
+            .filter_map(|day| {
+                match active_challenges(TUESDAY + day * SECONDS_PER_DAY)[1].objective {
+                    Objective::TargetRun { seed, .. } => Some(seed),
+                    _ => None,
+                }
+            })
+            .collect();
+        assert_eq!(seeds.len(), 14);
+    }
+
+    #[test]
+    fn round_objectives_complete_once_and_reset_with_the_period() {
+        let active = vec![
+            Challenge {
+                id: "d1-0".into(),
+                period: Period::Daily,
+                objective: Objective::WinRounds {
+                    mode: Some(GameMode::Deathmatch),
+                    count: 2,
+                },
+                reward: DAILY_REWARD_POINTS,
+            },
+            Challenge {
+                id: "w1-0".into(),
+                period: Period::Weekly,
+                objective: Objective::Eliminations { count: 3 },
+                reward: WEEKLY_REWARD_POINTS,
+            },
+        ];
+        let mut progress = ChallengeProgress::decode("d0-0:7,w1-0:1");
+        assert!(progress
+            .advance_round(&active, &round(GameMode::Duel, true, 1))
+            .is_empty());
+        assert_eq!(progress.get("d0-0"), 0);
+        assert_eq!(progress.get("d1-0"), 0);
+        let completed = progress.advance_round(&active, &round(GameMode::Deathmatch, true, 1));
+        assert_eq!(completed, [active[1].clone()]);
+        let completed = progress.advance_round(&active, &round(GameMode::Deathmatch, true, 0));
+        assert_eq!(completed, [active[0].clone()]);
+        assert!(progress
+            .advance_round(&active, &round(GameMode::Deathmatch, true, 4))
+            .is_empty());
+        assert!(active
+            .iter()
+            .all(|challenge| progress.is_complete(challenge)));
+
+        let decoded = ChallengeProgress::decode(&progress.encode());
+        assert_eq!(decoded, progress);
+        assert_eq!(
+            ChallengeProgress::decode("d1-0:x,Bad:2,:3,w1-0:4"),
+            ChallengeProgress::decode("w1-0:4")
+        );
+    }
+
+    #[test]
+    fn target_runs_keep_the_best_score() {
+        let active = active_challenges(TUESDAY);
+        let run = &active[1];
+        let goal = run.objective.goal();
+        let mut progress = ChallengeProgress::default();
+        assert!(!progress.record_target_run(&active, &active[0].id, goal));
+        assert!(!progress.record_target_run(&active, &run.id, goal - 1));
+        assert!(progress.record_target_run(&active, &run.id, goal + 5));
+        assert!(!progress.record_target_run(&active, &run.id, goal + 50));
+        assert!(!progress.record_target_run(&active, &run.id, 10));
+        assert_eq!(progress.get(&run.id), goal + 50);
+
+        let mut other = ChallengeProgress::default();
+        other.record_target_run(&active, &run.id, 20);
+        other.merge(progress.clone());
+        assert_eq!(other, progress);
+    }
+}
@@ -14,12 +14,50 @@ pub struct Player {
 #[derive(Component, Reflect, Default)]
 pub struct BulletReady(pub u8);
 
+/// Ordered by `(owner, id)` everywhere bullets interact. Pellets fired in the
+/// same shot get consecutive ids, so that order stays canonical per owner.
 #[derive(Component, Reflect, Default, Clone, Copy)]
 pub struct Bullet {
     pub id: u64,
     pub owner: PlayerId,
     pub owner_handle: usize,
     pub active: bool,
+    pub weapon: Weapon,
+    /// Wall reflections left for ricochet bullets.
+    pub bounces_left: u8,
+}
+
+#[derive(Debug, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weapon {
+    #[default]
+    Blaster,
+    /// Three pellets in a narrow fan.
+    Spread,
+    /// Bullets reflect off walls a limited number of times.
+    Ricochet,
+    /// A slow, large shot that passes through one shield charge.
+    Heavy,
+}
+
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct WeaponPickup {
+    pub cell: (u16, u16),
+    pub weapon: Weapon,
+}
+
+/// Timed weapon granted by a pickup. Removed when it runs out, after which
+/// the ghost fires the default blaster again.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct EquippedWeapon {
+    pub weapon: Weapon,
+    pub frames_left: u16,
+}
+
+/// A collected pickup waiting on its cell to come back. The entity keeps its
+/// pickup component, so collection only has to skip charging entities.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct PickupCharging {
+    pub frames_left: u16,
 }
 
 #[derive(Component, Reflect, Default, Clone, Copy)]
@@ -40,9 +78,80 @@ pub struct ShieldPickup {
 #[derive(Component, Reflect, Default, Clone, Copy)]
 pub struct ShieldCharges(pub u8);
 
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct PhasePickup {
+    pub cell: (u16, u16),
+}
+
+/// Ghostly phase granted by a pickup: the ghost drifts through walls (never
+/// the void) and cannot fire until it runs out.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct Phasing {
+    pub frames_left: u16,
+}
+
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct HealthPickup {
+    pub cell: (u16, u16),
+}
+
+/// Remaining hit points under the multi-hit health rule. One-shot matches
+/// never insert it, so its absence means the first unshielded hit eliminates.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct Health(pub u8);
+
+/// Post-hit grace period. Bullets pass through the ghost until it expires.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct HitInvulnerability {
+    pub frames_left: u8,
+}
+
+/// Deterministic dash state. `frames_left` counts the remaining burst frames,
+/// during which the player is also immune to bullets; `cooldown` counts the
+/// frames until another dash may start.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct Dash {
+    pub frames_left: u8,
+    pub cooldown: u8,
+}
+
+/// Set when a ghost teleports. It only counts down once the ghost has stepped
+/// off the teleporter pads, so arriving on the partner pad never bounces back.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct TeleportCooldown {
+    pub frames_left: u8,
+}
+
+/// Lives left under the stock rule, counting the current one. Kept at zero
+/// on a ghost that has run out so the scoreboard can still show it.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct Stocks(pub u8);
+
+/// A ghost that lost a stock and sits out until `frames_left` runs down. It
+/// also carries `MarkedForDeath`, which keeps it out of play meanwhile.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct Respawning {
+    pub frames_left: u16,
+}
+
 #[derive(Component, Reflect, Default, Clone, Copy)]
 pub struct MoveDir(pub Vec2);
 
+/// Decoded twin-stick aim for the current frame, or zero when the player is
+/// not aiming and bullets should follow `MoveDir`.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+pub struct AimDir(pub Vec2);
+
+impl AimDir {
+    pub fn or(self, move_dir: MoveDir) -> Vec2 {
+        if self.0 == Vec2::ZERO {
+            move_dir.0
+        } else {
+            self.0
+        }
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 pub struct LookTowardsParentMove;
 
@@ -72,6 +181,12 @@ impl MarkedForDeath {
 #[derive(Component)]
 pub struct MapBlock;
 
+/// Presentation of a destructible wall cell, restyled from `WallDamage`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CrackedBlock {
+    pub cell: (u16, u16),
+}
+
 // Presentation-only components below are deliberately not rollback registered.
 // They are reconciled from authoritative gameplay components after each frame,
 // so rollback can freely remove/recreate their owners without visual residue.
@@ -82,6 +197,20 @@ pub struct ShieldBubble {
     pub owner: Entity,
 }
 
+/// Pip row hanging under a ghost with `Health`. It is a child of the player
+/// so it follows the body like the eyes do.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HealthBar {
+    pub owner: Entity,
+}
+
+/// One of the four void bands drawn over rings closed during overtime,
+/// indexed left, right, bottom, top.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ClosingVoid {
+    pub side: usize,
+}
+
 /// Presentation-only speed trail state. It lives separately from the player so
 /// none of its wall-clock timing can enter deterministic simulation state.
 #[derive(Component)]
@@ -92,6 +221,14 @@ pub struct SpeedTrailEmitter {
     pub sequence: u32,
 }
 
+/// Presentation-only colors resolved from a ghost's cosmetic loadout; `None`
+/// keeps the default look for that slot.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CosmeticTints {
+    pub trail: Option<Color>,
+    pub bullet: Option<Color>,
+}
+
 /// Short-lived afterimage or spark emitted by a speed trail.
 #[derive(Component)]
 pub struct SpeedTrailParticle {
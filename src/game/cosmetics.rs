@@ -0,0 +1,542 @@
+//! Data-driven cosmetics catalog.
+//!
+//! Items ship in `assets/cosmetics/catalog.cosmetics`, so new cosmetics need
+//! no code change. Item ids are stable: profiles store them in unlock sets and
+//! lobbies exchange them in loadouts, and both tolerate ids this build does
+//! not know about.
+
+use std::collections::BTreeSet;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use bevy_asset_loader::prelude::*;
+
+use super::achievements::{achievement, AchievementDefinition};
+
+/// Highest id a catalog item, unlock set or loadout may use.
+pub const MAX_COSMETIC_ID: u16 = 4095;
+const UNLOCK_WORD_BITS: usize = u64::BITS as usize;
+const MAX_UNLOCK_WORDS: usize = MAX_COSMETIC_ID as usize / UNLOCK_WORD_BITS + 1;
+
+#[derive(AssetCollection, Resource)]
+pub struct CosmeticAssets {
+    #[asset(path = "cosmetics/catalog.cosmetics")]
+    pub catalog: Handle<CosmeticCatalog>,
+}
+
+impl CosmeticAssets {
+    pub fn get<'a>(&self, catalogs: &'a Assets<CosmeticCatalog>) -> Option<&'a CosmeticCatalog> {
+        catalogs.get(&self.catalog)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CosmeticSlot {
+    Hat,
+    Trail,
+    BulletTint,
+}
+
+impl CosmeticSlot {
+    pub const ALL: [Self; 3] = [Self::Hat, Self::Trail, Self::BulletTint];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Hat => "Hat",
+            Self::Trail => "Trail",
+            Self::BulletTint => "Bullet tint",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hat" => Some(Self::Hat),
+            "trail" => Some(Self::Trail),
+            "bullet" => Some(Self::BulletTint),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CosmeticArt {
+    /// Ghost body texture. The loader fills in `handle` from `path`.
+    Texture {
+        path: String,
+        handle: Handle<Image>,
+    },
+    Tint(Color),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CosmeticItem {
+    pub id: u16,
+    pub slot: CosmeticSlot,
+    pub name: String,
+    pub unlock: CosmeticUnlock,
+    pub art: CosmeticArt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CosmeticUnlock {
+    LifetimePoints(u64),
+    Achievement(&'static AchievementDefinition),
+}
+
+impl CosmeticUnlock {
+    /// `achievement:<key>` names an achievement; anything else is a points
+    /// threshold.
+    fn parse(value: &str) -> Option<Self> {
+        match value.strip_prefix("achievement:") {
+            Some(key) => achievement(key).map(Self::Achievement),
+            None => value.parse::<u64>().ok().map(Self::LifetimePoints),
+        }
+    }
+
+    pub fn is_met(self, lifetime_points: u64, achievements: &BTreeSet<String>) -> bool {
+        match self {
+            Self::LifetimePoints(points) => lifetime_points >= points,
+            Self::Achievement(definition) => achievements.contains(definition.key),
+        }
+    }
+
+    pub fn requirement(self) -> String {
+        match self {
+            Self::LifetimePoints(points) => format!("requires {points} lifetime points"),
+            Self::Achievement(definition) => format!("earn “{}”", definition.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogError {
+    MalformedLine(usize),
+    InvalidId(usize),
+    DuplicateId(usize),
+    UnknownSlot(usize),
+    InvalidUnlock(usize),
+    InvalidArt(usize),
+}
+
+impl CatalogError {
+    pub fn line(self) -> usize {
+        match self {
+            Self::MalformedLine(line)
+            | Self::InvalidId(line)
+            | Self::DuplicateId(line)
+            | Self::UnknownSlot(line)
+            | Self::InvalidUnlock(line)
+            | Self::InvalidArt(line) => line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, TypeUuid, TypePath)]
+#[uuid = "5f6b1a8e-3c2d-4f7a-9b0e-7d41c2a9e613"]
+pub struct CosmeticCatalog {
+    items: Vec<CosmeticItem>,
+}
+
+impl CosmeticCatalog {
+    /// Parses the `id | slot | name | unlock | art` manifest. Blank lines and
+    /// `#` comments are skipped; any malformed item rejects the whole file so
+    /// a typo cannot silently drop a cosmetic players already own.
+    pub fn parse(text: &str) -> Result<Self, CatalogError> {
+        let mut items: Vec<CosmeticItem> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<_> = line.split('|').map(str::trim).collect();
+            let [id, slot, name, unlock, art] = fields[..] else {
+                return Err(CatalogError::MalformedLine(number));
+            };
+            let id = id
+                .parse::<u16>()
+                .ok()
+                .filter(|id| (1..=MAX_COSMETIC_ID).contains(id))
+                .ok_or(CatalogError::InvalidId(number))?;
+            if items.iter().any(|item| item.id == id) {
+                return Err(CatalogError::DuplicateId(number));
+            }
+            let slot = CosmeticSlot::parse(slot).ok_or(CatalogError::UnknownSlot(number))?;
+            if name.is_empty() {
+                return Err(CatalogError::MalformedLine(number));
+            }
+            let unlock =
+                CosmeticUnlock::parse(unlock).ok_or(CatalogError::InvalidUnlock(number))?;
+            let art = match slot {
+                CosmeticSlot::Hat if art.ends_with(".png") => CosmeticArt::Texture {
+                    path: art.to_owned(),
+                    handle: Handle::default(),
+                },
+                CosmeticSlot::Trail | CosmeticSlot::BulletTint => art
+                    .strip_prefix('#')
+                    .filter(|hex| hex.len() == 6)
+                    .and_then(|hex| Color::hex(hex).ok())
+                    .map(CosmeticArt::Tint)
+                    .ok_or(CatalogError::InvalidArt(number))?,
+                CosmeticSlot::Hat => return Err(CatalogError::InvalidArt(number)),
+            };
+            items.push(CosmeticItem {
+                id,
+                slot,
+                name: name.to_owned(),
+                unlock,
+                art,
+            });
+        }
+        Ok(Self { items })
+    }
+
+    pub fn items(&self) -> &[CosmeticItem] {
+        &self.items
+    }
+
+    pub fn item(&self, id: u16) -> Option<&CosmeticItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    pub fn in_slot(&self, slot: CosmeticSlot) -> impl Iterator<Item = &CosmeticItem> {
+        self.items.iter().filter(move |item| item.slot == slot)
+    }
+
+    pub fn unlocked_by(&self, lifetime_points: u64, achievements: &BTreeSet<String>) -> UnlockSet {
+        let mut unlocked = UnlockSet::default();
+        for item in &self.items {
+            if item.unlock.is_met(lifetime_points, achievements) {
+                unlocked.insert(item.id);
+            }
+        }
+        unlocked
+    }
+
+    /// Keeps each slot's item only if it exists in that slot and is unlocked.
+    pub fn sanitize(&self, loadout: Loadout, unlocked: &UnlockSet) -> Loadout {
+        let mut sanitized = Loadout::default();
+        for slot in CosmeticSlot::ALL {
+            let id = loadout.get(slot);
+            if self.item(id).is_some_and(|item| item.slot == slot) && unlocked.contains(id) {
+                sanitized.set(slot, id);
+            }
+        }
+        sanitized
+    }
+
+    pub fn texture(&self, id: u16) -> Option<Handle<Image>> {
+        match &self.item(id)?.art {
+            CosmeticArt::Texture { handle, .. } => Some(handle.clone()),
+            CosmeticArt::Tint(_) => None,
+        }
+    }
+
+    pub fn tint(&self, slot: CosmeticSlot, id: u16) -> Option<Color> {
+        match self.item(id).filter(|item| item.slot == slot)?.art {
+            CosmeticArt::Tint(color) => Some(color),
+            CosmeticArt::Texture { .. } => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CosmeticCatalogLoader;
+
+impl AssetLoader for CosmeticCatalogLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes)?;
+            let mut catalog = CosmeticCatalog::parse(text).map_err(|error| {
+                bevy::asset::Error::msg(format!("catalog line {}: {error:?}", error.line()))
+            })?;
+            let mut dependencies = Vec::new();
+            for item in &mut catalog.items {
+                if let CosmeticArt::Texture { path, handle } = &mut item.art {
+                    let asset_path = AssetPath::from(path.as_str()).to_owned();
+                    *handle = load_context.get_handle(asset_path.clone());
+                    dependencies.push(asset_path);
+                }
+            }
+            let mut asset = LoadedAsset::new(catalog);
+            for path in dependencies {
+                asset = asset.with_dependency(path);
+            }
+            load_context.set_default_asset(asset);
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cosmetics"]
+    }
+}
+
+/// Cosmetic ids a profile has unlocked, as a little-endian bitset of any
+/// width. Stored as dot-separated decimal words, so the single `u8` mask of a
+/// v1 profile reads back as the first word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnlockSet(Vec<u64>);
+
+impl UnlockSet {
+    pub fn insert(&mut self, id: u16) {
+        let (word, bit) = (
+            id as usize / UNLOCK_WORD_BITS,
+            id as usize % UNLOCK_WORD_BITS,
+        );
+        if word >= MAX_UNLOCK_WORDS {
+            return;
+        }
+        if self.0.len() <= word {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << bit;
+    }
+
+    /// Id 0 is "nothing equipped" and always available.
+    pub fn contains(&self, id: u16) -> bool {
+        id == 0
+            || self
+                .0
+                .get(id as usize / UNLOCK_WORD_BITS)
+                .is_some_and(|word| word & (1 << (id as usize % UNLOCK_WORD_BITS)) != 0)
+    }
+
+    pub fn decode(value: &str) -> Self {
+        let words: Option<Vec<u64>> = value
+            .split('.')
+            .take(MAX_UNLOCK_WORDS)
+            .map(|word| word.parse::<u64>().ok())
+            .collect();
+        let mut set = Self(words.unwrap_or_default());
+        set.trim();
+        set
+    }
+
+    pub fn encode(&self) -> String {
+        if self.0.is_empty() {
+            return "0".into();
+        }
+        self.0
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+}
+
+/// One equipped item per slot; 0 leaves a slot at its default look. The
+/// `hat.trail.bullet` text form is what profiles store and lobbies exchange.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Loadout {
+    pub hat: u16,
+    pub trail: u16,
+    pub bullet: u16,
+}
+
+impl Loadout {
+    pub fn get(self, slot: CosmeticSlot) -> u16 {
+        match slot {
+            CosmeticSlot::Hat => self.hat,
+            CosmeticSlot::Trail => self.trail,
+            CosmeticSlot::BulletTint => self.bullet,
+        }
+    }
+
+    pub fn set(&mut self, slot: CosmeticSlot, id: u16) {
+        match slot {
+            CosmeticSlot::Hat => self.hat = id,
+            CosmeticSlot::Trail => self.trail = id,
+            CosmeticSlot::BulletTint => self.bullet = id,
+        }
+    }
+
+    pub fn is_canonical(self) -> bool {
+        CosmeticSlot::ALL
+            .into_iter()
+            .all(|slot| self.get(slot) <= MAX_COSMETIC_ID)
+    }
+
+    /// Drops equipped items that are not unlocked.
+    pub fn retain_unlocked(self, unlocked: &UnlockSet) -> Self {
+        let mut retained = self;
+        for slot in CosmeticSlot::ALL {
+            if !unlocked.contains(self.get(slot)) {
+                retained.set(slot, 0);
+            }
+        }
+        retained
+    }
+
+    /// Pre-catalog lobby services only accept hat ids 0–3 as `cosmeticId`.
+    pub fn legacy_cosmetic_id(self) -> u8 {
+        if self.hat < 4 {
+            self.hat as u8
+        } else {
+            0
+        }
+    }
+
+    pub fn encode(self) -> String {
+        format!("{}.{}.{}", self.hat, self.trail, self.bullet)
+    }
+
+    /// Missing trailing slots read as empty, so a v1 profile's single
+    /// cosmetic id decodes as its hat.
+    pub fn decode(value: &str) -> Option<Self> {
+        let ids: Vec<_> = value
+            .split('.')
+            .map(|id| id.parse::<u16>().ok())
+            .collect::<Option<_>>()?;
+        if ids.is_empty() || ids.len() > CosmeticSlot::ALL.len() {
+            return None;
+        }
+        let mut loadout = Self::default();
+        for (slot, id) in CosmeticSlot::ALL.into_iter().zip(ids) {
+            loadout.set(slot, id);
+        }
+        loadout.is_canonical().then_some(loadout)
+    }
+}
+
+#[cfg(test)]
+pub(super) fn shipped_catalog() -> CosmeticCatalog {
+    CosmeticCatalog::parse(include_str!("../../assets/cosmetics/catalog.cosmetics")).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_catalog_parses_and_keeps_the_v1_hat_ids() {
+        let catalog = shipped_catalog();
+        for (id, name, points) in [(1, "Crown", 5), (2, "Wizard", 12), (3, "Bow", 25)] {
+            let item = catalog.item(id).unwrap();
+            assert_eq!(
+                (item.slot, item.name.as_str(), item.unlock),
+                (
+                    CosmeticSlot::Hat,
+                    name,
+                    CosmeticUnlock::LifetimePoints(points)
+                )
+            );
+        }
+        for slot in CosmeticSlot::ALL {
+            assert!(catalog.in_slot(slot).next().is_some());
+        }
+    }
+
+    #[test]
+    fn malformed_items_reject_the_catalog() {
+        let valid = "1 | hat | Crown | 5 | crown.png\n# note\n\n9 | trail | Ember | 0 | #ff8a3d";
+        assert_eq!(CosmeticCatalog::parse(valid).unwrap().items.len(), 2);
+        for (text, error) in [
+            ("1 | hat | Crown | 5", CatalogError::MalformedLine(1)),
+            ("0 | hat | None | 0 | a.png", CatalogError::InvalidId(1)),
+            (
+                "1 | hat | A | 0 | a.png\n1 | hat | B | 0 | b.png",
+                CatalogError::DuplicateId(2),
+            ),
+            ("1 | cape | Cape | 0 | a.png", CatalogError::UnknownSlot(1)),
+            (
+                "1 | hat | Crown | -5 | a.png",
+                CatalogError::InvalidUnlock(1),
+            ),
+            (
+                "1 | hat | Crown | achievement:no-such | a.png",
+                CatalogError::InvalidUnlock(1),
+            ),
+            (
+                "1 | trail | Ember | 0 | ff8a3d",
+                CatalogError::InvalidArt(1),
+            ),
+            ("1 | hat | Crown | 0 | #ff8a3d", CatalogError::InvalidArt(1)),
+        ] {
+            assert_eq!(CosmeticCatalog::parse(text), Err(error));
+        }
+    }
+
+    #[test]
+    fn unlock_sets_grow_past_one_word_and_read_v1_masks() {
+        let mut unlocked = UnlockSet::default();
+        assert!(unlocked.contains(0));
+        unlocked.insert(3);
+        unlocked.insert(129);
+        unlocked.insert(MAX_COSMETIC_ID);
+        assert!(unlocked.contains(129) && !unlocked.contains(128));
+        assert_eq!(UnlockSet::decode(&unlocked.encode()), unlocked);
+
+        let v1 = UnlockSet::decode("15");
+        assert!((1..=3).all(|id| v1.contains(id)) && !v1.contains(4));
+        assert_eq!(UnlockSet::decode("bad"), UnlockSet::default());
+        assert_eq!(UnlockSet::default().encode(), "0");
+    }
+
+    #[test]
+    fn loadouts_round_trip_and_sanitize_per_slot() {
+        let loadout = Loadout {
+            hat: 2,
+            trail: 64,
+            bullet: 128,
+        };
+        assert_eq!(loadout.encode(), "2.64.128");
+        assert_eq!(Loadout::decode("2.64.128"), Some(loadout));
+        assert_eq!(
+            Loadout::decode("3"),
+            Some(Loadout {
+                hat: 3,
+                ..default()
+            })
+        );
+        assert_eq!(Loadout::decode("1.2.3.4"), None);
+        assert_eq!(Loadout::decode("4096"), None);
+        assert_eq!(Loadout::decode(""), None);
+
+        let catalog = shipped_catalog();
+        let unlocked = catalog.unlocked_by(20, &BTreeSet::new());
+        assert!(!unlocked.contains(130));
+        let achievements = BTreeSet::from(["clean-sweep".to_owned()]);
+        assert!(catalog.unlocked_by(0, &achievements).contains(130));
+        // Bow needs 25 points, and a hat id never fits the trail slot.
+        let sanitized = catalog.sanitize(
+            Loadout {
+                hat: 3,
+                trail: 1,
+                bullet: 128,
+            },
+            &unlocked,
+        );
+        assert_eq!(
+            sanitized,
+            Loadout {
+                hat: 0,
+                trail: 0,
+                bullet: 128,
+            }
+        );
+        assert_eq!(loadout.legacy_cosmetic_id(), 2);
+        assert_eq!(
+            Loadout {
+                hat: 64,
+                ..default()
+            }
+            .legacy_cosmetic_id(),
+            0
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use super::session::LOBBY_PROTOCOL_VERSION;
+
+/// Everything `GgrsPlugin` snapshots, in registration order. The source test
+/// below keeps this list in step with `mod.rs`.
+pub const ROLLBACK_REGISTRATIONS: &[&str] = &[
+    "roll_state:RollbackState",
+    "resource:RoundEndTimer",
+    "resource:Scores",
+    "resource:GameSeed",
+    "resource:SoundIdSeed",
+    "resource:Map<CellType, MAP_SIZE, MAP_SIZE>",
+    "resource:WallDamage",
+    "resource:RoundProgress",
+    "resource:MatchFlow",
+    "resource:GGFrameCount",
+    "component:Player",
+    "component:Transform",
+    "component:Bullet",
+    "component:BulletReady",
+    "component:Dash",
+    "component:SpeedPickup",
+    "component:SpeedBoost",
+    "component:ShieldPickup",
+    "component:ShieldCharges",
+    "component:HealthPickup",
+    "component:Health",
+    "component:HitInvulnerability",
+    "component:WeaponPickup",
+    "component:EquippedWeapon",
+    "component:PickupCharging",
+    "component:PhasePickup",
+    "component:Phasing",
+    "component:TeleportCooldown",
+    "component:Stocks",
+    "component:Respawning",
+    "component:MoveDir",
+    "component:AimDir",
+    "component:LookTowardsParentMove",
+    "component:MarkedForDeath",
+    "component:RollbackSound",
+    "component:ExplosionCue",
+    "component:Name",
+    "component:Sprite",
+    "component:Handle<Image>",
+    "component:Handle<TextureAtlas>",
+    "component:TextureAtlasSprite",
+];
+
+/// Every module whose code runs inside the rollback schedule or shapes the
+/// state it starts from, plus the lockfile pinning bevy, ggrs and glam. Any
+/// edit to these, not just a retuned constant, changes the fingerprint.
+const SIMULATION_SOURCES: &[(&str, &str)] = &[
+    ("mod.rs", include_str!("mod.rs")),
+    ("components.rs", include_str!("components.rs")),
+    ("ggrs_framecount.rs", include_str!("ggrs_framecount.rs")),
+    ("input.rs", include_str!("input.rs")),
+    ("map.rs", include_str!("map.rs")),
+    ("networking.rs", include_str!("networking.rs")),
+    ("player.rs", include_str!("player.rs")),
+    ("resume.rs", include_str!("resume.rs")),
+    ("rollback_audio.rs", include_str!("rollback_audio.rs")),
+    ("round_clock.rs", include_str!("round_clock.rs")),
+    ("session.rs", include_str!("session.rs")),
+    ("Cargo.lock", include_str!("../../Cargo.lock")),
+];
+
+/// 64-bit FNV-1a. Stable across builds and targets, unlike `DefaultHasher`.
+pub(super) fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_extend(0xcbf2_9ce4_8422_2325, bytes)
+}
+
+fn fnv1a_extend(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Identifies the deterministic simulation: crate version, lobby protocol,
+/// rollback registration list and the simulation sources themselves. Peers
+/// exchange it through their lobby profile and refuse a roster that
+/// disagrees.
+pub fn simulation_fingerprint() -> u64 {
+    let header = format!(
+        "{}|{LOBBY_PROTOCOL_VERSION}|{}",
+        env!("CARGO_PKG_VERSION"),
+        ROLLBACK_REGISTRATIONS.join(";")
+    );
+    SIMULATION_SOURCES
+        .iter()
+        .fold(fnv1a(header.as_bytes()), |hash, (name, source)| {
+            // Carriage returns are skipped so a Windows checkout of the same
+            // commit agrees with everyone else.
+            let source: Vec<u8> = source.bytes().filter(|byte| *byte != b'\r').collect();
+            let hash = fnv1a_extend(hash, format!("|{name}|{}|", source.len()).as_bytes());
+            fnv1a_extend(hash, &source)
+        })
+}
+
+/// Wire form shared with the browser transport and lobby service.
+pub fn simulation_fingerprint_hex() -> String {
+    format!("{:016x}", simulation_fingerprint())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_registrations_match_the_ggrs_plugin() {
+        let source = include_str!("mod.rs");
+        let registered: Vec<String> = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with("//"))
+            .filter_map(|line| {
+                [
+                    (".register_roll_state::<", "roll_state"),
+                    (".register_rollback_resource::<", "resource"),
+                    (".register_rollback_component::<", "component"),
+                ]
+                .into_iter()
+                .find_map(|(prefix, kind)| {
+                    let name = line.strip_prefix(prefix)?;
+                    let name = &name[..name.rfind(">()")?];
+                    Some(format!("{kind}:{name}"))
+                })
+            })
+            .collect();
+        assert_eq!(registered, ROLLBACK_REGISTRATIONS);
+    }
+
+    /// Modules that never run inside the rollback schedule: menus, local
+    /// progression, offline modes and diagnostics.
+    const OUTSIDE_SIMULATION: &[&str] = &[
+        "achievements",
+        "assets",
+        "challenges",
+        "cosmetics",
+        "debug_render",
+        "fingerprint",
+        "gui",
+        "match_history",
+        "network_transition_test",
+        "practice",
+        "profile_code",
+        "profile_store",
+        "progression",
+        "rating",
+        "session_log",
+        "toasts",
+        "tutorial",
+    ];
+
+    #[test]
+    fn every_game_module_is_fingerprinted_or_deliberately_left_out() {
+        let modules = include_str!("mod.rs").lines().filter_map(|line| {
+            let line = line.trim().strip_suffix(';')?;
+            line.strip_prefix("mod ")
+                .or_else(|| line.strip_prefix("pub(crate) mod "))
+        });
+        for module in modules {
+            let fingerprinted = SIMULATION_SOURCES
+                .iter()
+                .any(|(name, _)| name.strip_suffix(".rs") == Some(module));
+            assert!(
+                fingerprinted != OUTSIDE_SIMULATION.contains(&module),
+                "classify the {module} module as simulation source or not"
+            );
+        }
+    }
+
+    #[test]
+    fn fingerprint_is_stable_hex_and_sensitive_to_input() {
+        assert_eq!(simulation_fingerprint(), simulation_fingerprint());
+        let hex = simulation_fingerprint_hex();
+        assert_eq!(hex.len(), 16);
+        assert!(hex.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        // Published FNV-1a 64 vectors.
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(fnv1a(b"0.35"), fnv1a(b"0.36"));
+    }
+}
@@ -7,12 +7,28 @@ use bevy_egui::{
 use instant::SystemTime;
 
 use super::{
+    achievements::ACHIEVEMENTS,
     assets::sounds::AudioConfig,
-    components::{MarkedForDeath, Player, ShieldCharges, SpeedBoost},
-    networking::{sanitize_room_code, LocalPlayerHandle, MatchmakingRoom},
-    practice::{PracticeCooldown, PracticeScore},
-    progression::{CasualProfile, COSMETICS},
-    session::{mode_label, MatchPreference, PlayerProfile, RoundBootstrap, MATCH_POINTS_TO_WIN},
+    challenges::{active_challenges, Objective, Period},
+    components::{MarkedForDeath, Player, Respawning, ShieldCharges, SpeedBoost, Stocks},
+    cosmetics::{CosmeticAssets, CosmeticCatalog, CosmeticSlot, CosmeticUnlock},
+    match_history::format_timestamp,
+    networking::{
+        sanitize_room_code, InputDelaySetting, LocalPlayerHandle, MatchmakingRoom,
+        ReconnectingPeers, MAX_INPUT_DELAY, RECONNECT_WINDOW_MS,
+    },
+    player::STOCK_LIVES,
+    practice::{Difficulty, Drill, PracticeCooldown, PracticePlayer, PracticeRun, PracticeScore},
+    profile_code::export_code,
+    progression::{unix_now, CasualProfile, ProfileTransfer},
+    round_clock::{in_overtime, remaining_frames, round_elapsed},
+    session::{
+        mode_label, GameMode, MatchPreference, PlayerProfile, RoundBootstrap, RoundOutcome,
+        MATCH_POINTS_TO_WIN,
+    },
+    session_log::{export_session_log, SessionLog},
+    toasts::Toasts,
+    tutorial::{TutorialProgress, TutorialStatus, TutorialStatusRequest, TutorialStep},
     GameState, MatchFlow, PendingPlayerProfile, RematchFlow, RollbackState, Scores,
 };
 use crate::cloudflare_net::{CloudflareSocket, QueueStatus};
@@ -25,6 +41,10 @@ pub enum MenuState {
     DirectConnect,
     Pause,
     Settings,
+    Profile,
+    History,
+    Challenges,
+    Practice,
     #[cfg(feature = "sync_test")]
     SyncTest,
 }
@@ -291,6 +311,21 @@ pub fn update_main_menu(
                         if ui.button("SyncTest").clicked() {
                             next_menu_state.set(MenuState::SyncTest);
                         }
+                        if ui.button("🏆 Profile").clicked() {
+                            next_menu_state.set(MenuState::Profile);
+                        }
+                        if ui.button("📜 History").clicked() {
+                            next_menu_state.set(MenuState::History);
+                        }
+                        if ui.button("📅 Challenges").clicked() {
+                            next_menu_state.set(MenuState::Challenges);
+                        }
+                        if ui.button("🎯 Practice").clicked() {
+                            next_menu_state.set(MenuState::Practice);
+                        }
+                        if ui.button("🎓 Tutorial").clicked() {
+                            next_game_state.set(GameState::Tutorial);
+                        }
                         if ui.button("⚙ Settings").clicked() {
                             next_menu_state.set(MenuState::Settings);
                         }
@@ -331,6 +366,7 @@ pub fn update_direct_connect_ui(
                             if ui.selectable_label(room.private_mode == super::session::GameMode::Duel, "Dueling Ghosts (2)").clicked() {
                                 room.private_mode = super::session::GameMode::Duel;
                                 room.private_capacity = 2;
+                                room.private_rules.stock_lives = false;
                             }
                             if ui.selectable_label(room.private_mode == super::session::GameMode::Deathmatch, "Last Ghost Standing (Exact 3–8)").clicked() {
                                 room.private_mode = super::session::GameMode::Deathmatch;
@@ -340,7 +376,17 @@ pub fn update_direct_connect_ui(
                         if room.private_mode == super::session::GameMode::Deathmatch {
                             ui.label(RichText::new("Choose the exact private LGS roster size").strong().color(ACCENT));
                             ui.add(Slider::new(&mut room.private_capacity, 3..=8).text("Exact ghosts (3–8)"));
+                            ui.checkbox(
+                                &mut room.private_rules.stock_lives,
+                                format!(
+                                    "{STOCK_LIVES} stock lives with respawns (every device must match)"
+                                ),
+                            );
                         }
+                        ui.checkbox(
+                            &mut room.private_rules.multi_hit_health,
+                            "Multi-hit health (every device must match)",
+                        );
                         if ui.text_edit_singleline(&mut *code).changed() {
                             *code = sanitize_room_code(code.as_str());
                         }
@@ -369,6 +415,346 @@ pub fn update_direct_connect_ui(
         });
 }
 
+pub fn update_profile_ui(
+    mut contexts: EguiContexts,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+    casual: Res<CasualProfile>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
+) {
+    let scale = responsive_scale(contexts.ctx_mut());
+    let margin = panel_margin(contexts.ctx_mut());
+    let catalog = cosmetics.get(&catalogs);
+    CentralPanel::default()
+        .frame(
+            Frame::none()
+                .outer_margin(margin)
+                .inner_margin(Margin::same(0.0))
+                .fill(PANEL_DARK),
+        )
+        .show(contexts.ctx_mut(), |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.style_mut().spacing.item_spacing.y = 10.0 * scale;
+                    ui.vertical_centered_justified(|ui| {
+                        ui.heading(casual.name.as_str());
+                        ui.label(format!(
+                            "{} points • {} rounds • {} matches",
+                            casual.lifetime_points, casual.rounds_played, casual.matches_played,
+                        ));
+                        ui.heading("Skill rating");
+                        for (label, mode) in [
+                            ("Duel", GameMode::Duel),
+                            ("Last Ghost Standing", GameMode::Deathmatch),
+                        ] {
+                            let rating = casual.ratings.get(mode);
+                            let status = if rating.rated_rounds == 0 {
+                                " • unrated"
+                            } else if rating.is_provisional() {
+                                " • provisional"
+                            } else {
+                                ""
+                            };
+                            ui.label(
+                                RichText::new(format!("{label}: {}{status}", rating.rating))
+                                    .strong(),
+                            );
+                            let history = rating.history();
+                            if history.len() > 1 {
+                                let recent = history[history.len().saturating_sub(8)..]
+                                    .iter()
+                                    .map(u16::to_string)
+                                    .collect::<Vec<_>>();
+                                ui.small(format!("Recent: {}", recent.join(" → ")));
+                            }
+                        }
+                        let earned = ACHIEVEMENTS
+                            .iter()
+                            .filter(|definition| casual.has_achievement(definition.key))
+                            .count();
+                        ui.heading(format!("Achievements {earned}/{}", ACHIEVEMENTS.len()));
+                        for definition in &ACHIEVEMENTS {
+                            let unlocked = casual.has_achievement(definition.key);
+                            let title = if unlocked {
+                                RichText::new(format!("🏆 {}", definition.name)).color(ACCENT)
+                            } else {
+                                RichText::new(format!("🔒 {}", definition.name)).weak()
+                            };
+                            ui.label(title.strong());
+                            ui.small(definition.description);
+                            let rewards = catalog
+                                .map(|catalog| catalog.items())
+                                .unwrap_or_default()
+                                .iter()
+                                .filter(|item| {
+                                    matches!(item.unlock, CosmeticUnlock::Achievement(reward)
+                                        if reward.key == definition.key)
+                                })
+                                .map(|item| item.name.as_str())
+                                .collect::<Vec<_>>();
+                            if !rewards.is_empty() {
+                                ui.small(format!("Reward: {}", rewards.join(", ")));
+                            }
+                        }
+                        if ui
+                            .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
+                            .clicked()
+                        {
+                            next_menu_state.set(MenuState::Main);
+                        }
+                    });
+                });
+        });
+}
+
+pub fn update_history_ui(
+    mut contexts: EguiContexts,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+    casual: Res<CasualProfile>,
+    mut selected: Local<Option<String>>,
+) {
+    let scale = responsive_scale(contexts.ctx_mut());
+    let margin = panel_margin(contexts.ctx_mut());
+    let history = &casual.match_history;
+    CentralPanel::default()
+        .frame(
+            Frame::none()
+                .outer_margin(margin)
+                .inner_margin(Margin::same(0.0))
+                .fill(PANEL_DARK),
+        )
+        .show(contexts.ctx_mut(), |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.style_mut().spacing.item_spacing.y = 10.0 * scale;
+                    ui.vertical_centered_justified(|ui| {
+                        let detail = selected
+                            .as_deref()
+                            .and_then(|event_id| history.find(event_id));
+                        if let Some(record) = detail {
+                            ui.heading(mode_label(record.mode));
+                            ui.label(format_timestamp(record.finished_at));
+                            let result = if record.local_won() {
+                                RichText::new("Victory").color(ACCENT)
+                            } else {
+                                RichText::new(format!("Finished #{}", record.local_placement()))
+                            };
+                            ui.label(result.strong());
+                            for participant in &record.participants {
+                                let marker = if participant.player_id == record.local_player {
+                                    "YOU • "
+                                } else {
+                                    ""
+                                };
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{marker}{}: {}/{}",
+                                        participant.name, participant.score, MATCH_POINTS_TO_WIN
+                                    ))
+                                    .strong()
+                                    .color(palette_color(participant.palette_id)),
+                                );
+                            }
+                            let (match_id, epoch) = record.match_and_epoch();
+                            ui.small(format!("Match {match_id} • epoch {epoch}"));
+                            if ui
+                                .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
+                                .clicked()
+                            {
+                                *selected = None;
+                            }
+                            return;
+                        }
+                        ui.heading("Match History");
+                        if history.records().is_empty() {
+                            ui.label("Finished matches appear here.");
+                        }
+                        for record in history.records() {
+                            let result = if record.local_won() {
+                                "Won".to_owned()
+                            } else {
+                                format!("#{}", record.local_placement())
+                            };
+                            let opponents = record
+                                .participants
+                                .iter()
+                                .filter(|participant| participant.player_id != record.local_player)
+                                .map(|participant| participant.name.as_str())
+                                .collect::<Vec<_>>();
+                            if ui
+                                .button(format!(
+                                    "{result} • {} vs {}",
+                                    format_timestamp(record.finished_at),
+                                    opponents.join(", ")
+                                ))
+                                .clicked()
+                            {
+                                *selected = Some(record.event_id.clone());
+                            }
+                        }
+                        if ui
+                            .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
+                            .clicked()
+                        {
+                            next_menu_state.set(MenuState::Main);
+                        }
+                    });
+                });
+        });
+}
+
+pub fn update_challenges_ui(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    casual: Res<CasualProfile>,
+) {
+    let scale = responsive_scale(contexts.ctx_mut());
+    let margin = panel_margin(contexts.ctx_mut());
+    let now = unix_now();
+    let active = active_challenges(now);
+    CentralPanel::default()
+        .frame(
+            Frame::none()
+                .outer_margin(margin)
+                .inner_margin(Margin::same(0.0))
+                .fill(PANEL_DARK),
+        )
+        .show(contexts.ctx_mut(), |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.style_mut().spacing.item_spacing.y = 10.0 * scale;
+                    ui.vertical_centered_justified(|ui| {
+                        ui.heading("Challenges");
+                        for period in [Period::Daily, Period::Weekly] {
+                            let left = period.seconds_left(now);
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} • resets in {}h {:02}m",
+                                    period.label(),
+                                    left / 3600,
+                                    left % 3600 / 60
+                                ))
+                                .strong()
+                                .color(ACCENT),
+                            );
+                            for challenge in
+                                active.iter().filter(|challenge| challenge.period == period)
+                            {
+                                let goal = challenge.objective.goal();
+                                let progress = casual.challenges.get(&challenge.id);
+                                let marker = if progress >= goal { "✔" } else { "•" };
+                                ui.label(format!("{marker} {}", challenge.objective.description()));
+                                let text = match challenge.objective {
+                                    Objective::TargetRun { .. } => {
+                                        format!("Best {progress}/{goal}")
+                                    }
+                                    _ => format!("{}/{goal}", progress.min(goal)),
+                                };
+                                let fraction = progress.min(goal) as f32 / goal.max(1) as f32;
+                                ui.add(ProgressBar::new(fraction).text(text));
+                                ui.small(format!("Reward: {} points", challenge.reward));
+                                if let Objective::TargetRun { seed, .. } = challenge.objective {
+                                    if ui.button("▶ Start Target Run").clicked() {
+                                        commands.insert_resource(PracticeRun {
+                                            challenge: Some(challenge.id.clone()),
+                                            ..PracticeRun::new(
+                                                Drill::ScoreAttack,
+                                                Difficulty::Normal,
+                                                seed,
+                                            )
+                                        });
+                                        next_menu_state.set(MenuState::Main);
+                                        next_game_state.set(GameState::Practice);
+                                    }
+                                }
+                            }
+                        }
+                        ui.small(
+                            "Daily challenges change at midnight UTC and weekly ones on Monday; \
+                             every player gets the same ones.",
+                        );
+                        if ui
+                            .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
+                            .clicked()
+                        {
+                            next_menu_state.set(MenuState::Main);
+                        }
+                    });
+                });
+        });
+}
+
+pub fn update_practice_menu_ui(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    casual: Res<CasualProfile>,
+    mut difficulty: Local<Difficulty>,
+) {
+    let scale = responsive_scale(contexts.ctx_mut());
+    let margin = panel_margin(contexts.ctx_mut());
+    CentralPanel::default()
+        .frame(
+            Frame::none()
+                .outer_margin(margin)
+                .inner_margin(Margin::same(0.0))
+                .fill(PANEL_DARK),
+        )
+        .show(contexts.ctx_mut(), |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.style_mut().spacing.item_spacing.y = 10.0 * scale;
+                    ui.vertical_centered_justified(|ui| {
+                        ui.heading("Practice");
+                        ui.horizontal(|ui| {
+                            ui.label("Difficulty");
+                            for option in Difficulty::ALL {
+                                if ui
+                                    .selectable_label(*difficulty == option, option.label())
+                                    .clicked()
+                                {
+                                    *difficulty = option;
+                                }
+                            }
+                        });
+                        for drill in Drill::ALL {
+                            ui.label(RichText::new(drill.label()).strong().color(ACCENT));
+                            ui.small(drill.description());
+                            let best = casual.practice_bests.get(drill, *difficulty);
+                            ui.label(if best > 0 {
+                                format!("Personal best ({}): {best}", difficulty.label())
+                            } else {
+                                format!("No {} best yet", difficulty.label())
+                            });
+                            if ui.button(format!("▶ Start {}", drill.label())).clicked() {
+                                // Free drills get a fresh pattern every time.
+                                commands.insert_resource(PracticeRun::new(
+                                    drill,
+                                    *difficulty,
+                                    unix_now(),
+                                ));
+                                next_menu_state.set(MenuState::Main);
+                                next_game_state.set(GameState::Practice);
+                            }
+                        }
+                        if ui
+                            .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
+                            .clicked()
+                        {
+                            next_menu_state.set(MenuState::Main);
+                        }
+                    });
+                });
+        });
+}
+
 pub fn update_in_game_controls_ui(
     mut contexts: EguiContexts,
     mut next_menu_state: ResMut<NextState<MenuState>>,
@@ -394,6 +780,8 @@ pub fn update_pause_ui(
     mut next_menu_state: ResMut<NextState<MenuState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
     socket: Res<CloudflareSocket>,
+    session_log: Res<SessionLog>,
+    mut toasts: ResMut<Toasts>,
 ) {
     mobile_input::hide();
     let safe = safe_screen_rect(contexts.ctx_mut());
@@ -439,6 +827,17 @@ pub fn update_pause_ui(
                                 }
                             }
                         }
+                        ui.separator();
+                        // Connection triage for support; see docs/network-telemetry.md.
+                        if ui
+                            .add_sized(vec2(ui.available_width(), 44.0), Button::new("Export Session Log"))
+                            .on_hover_text("Save this match's connection events as JSON Lines.")
+                            .clicked()
+                        {
+                            if let Err(error) = export_session_log(&session_log) {
+                                toasts.error(error.into());
+                            }
+                        }
                     });
                 });
         });
@@ -450,7 +849,12 @@ pub fn update_settings_ui(
     mut next_menu_state: ResMut<NextState<MenuState>>,
     mut audio_config: ResMut<AudioConfig>,
     mut profile: ResMut<PendingPlayerProfile>,
+    mut input_delay: ResMut<InputDelaySetting>,
     casual: Res<CasualProfile>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
+    mut transfer: ResMut<ProfileTransfer>,
+    mut toasts: ResMut<Toasts>,
 ) {
     mobile_input::show(MobileInputKind::PlayerName, &profile.name, 24);
     if let Some(value) = mobile_input::value(MobileInputKind::PlayerName) {
@@ -537,41 +941,107 @@ pub fn update_settings_ui(
                             "Casual progress: {} points • {} rounds • {} matches",
                             casual.lifetime_points, casual.rounds_played, casual.matches_played,
                         ));
-                        let selected = COSMETICS
-                            .get(profile.cosmetic_id as usize)
-                            .map(|cosmetic| cosmetic.name)
-                            .unwrap_or(COSMETICS[0].name);
-                        ComboBox::from_label("Cosmetic")
-                            .selected_text(selected)
-                            .show_ui(ui, |ui| {
-                                for cosmetic in COSMETICS {
-                                    let unlocked = casual.is_unlocked(cosmetic.id);
-                                    let label = if unlocked {
-                                        cosmetic.name.to_owned()
-                                    } else {
-                                        format!(
-                                            "🔒 {} — requires {} lifetime points",
-                                            cosmetic.name, cosmetic.required_points
-                                        )
-                                    };
-                                    if ui
-                                        .add_enabled(
-                                            unlocked,
-                                            SelectableLabel::new(
-                                                profile.cosmetic_id == cosmetic.id,
-                                                label,
-                                            ),
-                                        )
-                                        .clicked()
-                                    {
-                                        profile.cosmetic_id = cosmetic.id;
-                                    }
-                                }
-                            });
+                        if let Some(catalog) = cosmetics.get(&catalogs) {
+                            for slot in CosmeticSlot::ALL {
+                                let equipped = profile.loadout.get(slot);
+                                let default_name = if slot == CosmeticSlot::Hat {
+                                    "Classic"
+                                } else {
+                                    "None"
+                                };
+                                let selected = catalog
+                                    .item(equipped)
+                                    .map_or(default_name, |item| item.name.as_str());
+                                ComboBox::from_label(slot.label())
+                                    .selected_text(selected)
+                                    .show_ui(ui, |ui| {
+                                        if ui
+                                            .selectable_label(equipped == 0, default_name)
+                                            .clicked()
+                                        {
+                                            profile.loadout.set(slot, 0);
+                                        }
+                                        for item in catalog.in_slot(slot) {
+                                            let unlocked = casual.is_unlocked(item.id);
+                                            let label = if unlocked {
+                                                item.name.clone()
+                                            } else {
+                                                format!(
+                                                    "🔒 {} — {}",
+                                                    item.name,
+                                                    item.unlock.requirement()
+                                                )
+                                            };
+                                            if ui
+                                                .add_enabled(
+                                                    unlocked,
+                                                    SelectableLabel::new(
+                                                        equipped == item.id,
+                                                        label,
+                                                    ),
+                                                )
+                                                .clicked()
+                                            {
+                                                profile.loadout.set(slot, item.id);
+                                            }
+                                        }
+                                    });
+                            }
+                        }
                         ui.small(
-                            "Cosmetics are casual local rewards; Classic is always available.",
+                            "Cosmetics are casual local rewards; the classic look is always free.",
                         );
 
+                        ui.heading("Profile Transfer");
+                        if ui
+                            .add_sized(
+                                vec2(ui.available_width(), 44.0),
+                                Button::new("Export profile"),
+                            )
+                            .clicked()
+                        {
+                            let code = export_code(&casual);
+                            ui.output_mut(|output| output.copied_text = code.clone());
+                            #[cfg(not(target_arch = "wasm32"))]
+                            match super::profile_code::save_profile_file(&code) {
+                                Ok(filename) => toasts.success(
+                                    format!("Profile code copied and saved to {filename}.").into(),
+                                ),
+                                Err(error) => toasts.error(error.into()),
+                            }
+                            #[cfg(target_arch = "wasm32")]
+                            toasts.success("Profile code copied to the clipboard.".into());
+                            transfer.exported = code;
+                        }
+                        if !transfer.exported.is_empty() {
+                            ui.add(
+                                TextEdit::multiline(&mut transfer.exported.as_str())
+                                    .desired_rows(3),
+                            );
+                        }
+                        // Importing mid-match would swap the profile under a
+                        // round that is still being rewarded.
+                        if *game_state.get() == GameState::MainMenu {
+                            ui.add(
+                                TextEdit::multiline(&mut transfer.import_code)
+                                    .hint_text("Paste a profile code")
+                                    .desired_rows(3),
+                            );
+                            if ui
+                                .add_enabled(
+                                    !transfer.import_code.trim().is_empty(),
+                                    Button::new("Import profile"),
+                                )
+                                .clicked()
+                            {
+                                transfer.import_requested = true;
+                            }
+                            ui.small(
+                                "Importing keeps the larger progress of both profiles; cosmetics \
+                                 unlock from earned points and achievements only.",
+                            );
+                        }
+
                         ui.heading("Volume Settings");
 
                         if !wide {
@@ -615,6 +1085,27 @@ pub fn update_settings_ui(
                             slider
                         });
 
+                        ui.heading("Network Settings");
+
+                        ComboBox::from_label("Input Delay")
+                            .selected_text(input_delay_label(*input_delay))
+                            .show_ui(ui, |ui| {
+                                let options = std::iter::once(InputDelaySetting::Auto).chain(
+                                    (0..=MAX_INPUT_DELAY).map(InputDelaySetting::Fixed),
+                                );
+                                for option in options {
+                                    ui.selectable_value(
+                                        &mut *input_delay,
+                                        option,
+                                        input_delay_label(option),
+                                    );
+                                }
+                            });
+                        ui.small(
+                            "Auto uses the delay your lobby agrees on from measured ping. \
+                             Applies from the next round.",
+                        );
+
                         if ui
                             .add_sized(vec2(ui.available_width(), 44.0), Button::new("Back"))
                             .clicked()
@@ -626,6 +1117,14 @@ pub fn update_settings_ui(
         });
 }
 
+fn input_delay_label(setting: InputDelaySetting) -> String {
+    match setting {
+        InputDelaySetting::Auto => "Auto (ping based)".into(),
+        InputDelaySetting::Fixed(1) => "1 frame".into(),
+        InputDelaySetting::Fixed(frames) => format!("{frames} frames"),
+    }
+}
+
 fn palette_color(id: u8) -> Color32 {
     [
         Color32::from_rgb(204, 51, 51),
@@ -647,10 +1146,15 @@ pub fn update_score_ui(
     scores: Res<Scores>,
     bootstrap: Option<Res<RoundBootstrap>>,
     local: Option<Res<LocalPlayerHandle>>,
+    progress: Res<super::RoundProgress>,
+    frame: Res<super::ggrs_framecount::GGFrameCount>,
+    stocks: Query<(&Player, &Stocks)>,
 ) {
     let Some(bootstrap) = bootstrap else {
         return;
     };
+    let elapsed = round_elapsed(&progress, &frame);
+    let seconds_left = (remaining_frames(elapsed) + 59) / 60;
     let safe = safe_screen_rect(contexts.ctx_mut());
     let menu_reserve = if safe.width() >= 360.0 { 124.0 } else { 0.0 };
     let score_top = safe.top() + if menu_reserve == 0.0 { 52.0 } else { 0.0 };
@@ -664,6 +1168,16 @@ pub fn update_score_ui(
                         .strong()
                         .color(Color32::WHITE),
                 );
+                let clock = format!("{}:{:02}", seconds_left / 60, seconds_left % 60);
+                if in_overtime(elapsed) {
+                    ui.label(
+                        RichText::new(format!("OVERTIME {clock}"))
+                            .strong()
+                            .color(STATUS_DANGER),
+                    );
+                } else {
+                    ui.label(RichText::new(clock).color(Color32::WHITE));
+                }
                 ui.horizontal_wrapped(|ui| {
                     for score in scores.entries() {
                         let profile = bootstrap
@@ -690,9 +1204,18 @@ pub fn update_score_ui(
                         } else {
                             ""
                         };
+                        let lives = stocks
+                            .iter()
+                            .find(|(player, _)| player.player_id == score.player_id)
+                            .map(|(_, stocks)| format!(" ♥{}", stocks.0))
+                            .unwrap_or_default();
+                        let rating = profile
+                            .and_then(|profile| profile.rating)
+                            .map(|rating| format!(" ({rating})"))
+                            .unwrap_or_default();
                         ui.label(
                             RichText::new(format!(
-                                "{marker}{name}: {}/{}",
+                                "{marker}{name}{rating}: {}/{}{lives}",
                                 score.score, MATCH_POINTS_TO_WIN
                             ))
                             .strong()
@@ -715,11 +1238,13 @@ pub fn update_match_status_ui(
     flow: Res<MatchFlow>,
     rollback: Res<State<RollbackState>>,
     progress: Res<super::RoundProgress>,
+    reconnecting: Res<ReconnectingPeers>,
     players: Query<(
         &Player,
         Option<&SpeedBoost>,
         Option<&ShieldCharges>,
         Option<&MarkedForDeath>,
+        Option<&Respawning>,
     )>,
     mut next_game: ResMut<NextState<GameState>>,
     mut socket: ResMut<CloudflareSocket>,
@@ -737,20 +1262,46 @@ pub fn update_match_status_ui(
         .map(|entry| entry.player_id);
     let local_player = players
         .iter()
-        .find(|(player, _, _, _)| player.handle == local.0);
+        .find(|(player, _, _, _, _)| player.handle == local.0);
 
     let safe = safe_screen_rect(contexts.ctx_mut());
     Area::new("player status")
         .fixed_pos(pos2(safe.left(), (safe.bottom() - 88.0).max(safe.top())))
         .show(contexts.ctx_mut(), |ui| {
             ui.set_max_width(safe.width());
-            if let Some((_, boost, shield, marked)) = local_player {
+            if !reconnecting.0.is_empty() {
+                ui.label(
+                    RichText::new(format!(
+                        "RECONNECTING — waiting up to {}s for {} ghost(s)",
+                        RECONNECT_WINDOW_MS / 1000,
+                        reconnecting.0.len()
+                    ))
+                    .color(ACCENT)
+                    .strong(),
+                );
+            }
+            if let Some(RoundOutcome::Expired { point_winners }) = &progress.resolved {
+                let verdict = match point_winners.first().and_then(|winner| {
+                    bootstrap
+                        .profiles
+                        .iter()
+                        .find(|profile| profile.player_id == *winner)
+                }) {
+                    Some(profile) => format!("TIME UP — point to {}", profile.name),
+                    None => "TIME UP — draw".to_string(),
+                };
+                ui.label(RichText::new(verdict).color(ACCENT).strong());
+            }
+            if let Some((_, boost, shield, marked, respawning)) = local_player {
                 if marked.is_some() {
-                    ui.label(
-                        RichText::new("ELIMINATED — spectating until the next round")
-                            .color(STATUS_DANGER)
-                            .strong(),
-                    );
+                    // A ghost down to its next stock gets the respawn overlay.
+                    if respawning.is_none() {
+                        ui.label(
+                            RichText::new("ELIMINATED — spectating until the next round")
+                                .color(STATUS_DANGER)
+                                .strong(),
+                        );
+                    }
                 } else {
                     if let Some(boost) = boost {
                         ui.label(
@@ -1059,6 +1610,9 @@ pub fn update_practice_ui(
     mut contexts: EguiContexts,
     score: Res<PracticeScore>,
     cooldown: Res<PracticeCooldown>,
+    run: Option<Res<PracticeRun>>,
+    casual: Res<CasualProfile>,
+    mut next_game_state: ResMut<NextState<GameState>>,
 ) {
     let safe = safe_screen_rect(contexts.ctx_mut());
     Area::new("practice HUD")
@@ -1071,11 +1625,45 @@ pub fn update_practice_ui(
                 .inner_margin(Margin::symmetric(14.0, 8.0))
                 .show(ui, |ui| {
                     ui.vertical_centered(|ui| {
-                        ui.label(RichText::new("TARGET PRACTICE").strong().color(ACCENT));
+                        let title = match &run {
+                            Some(run) if run.challenge.is_some() => "TARGET RUN".to_string(),
+                            Some(run) => format!(
+                                "{} • {}",
+                                run.drill.label().to_uppercase(),
+                                run.difficulty.label().to_uppercase()
+                            ),
+                            None => "TARGET PRACTICE".to_string(),
+                        };
+                        ui.label(RichText::new(title).strong().color(ACCENT));
                         ui.label(format!(
-                            "Score {:04}  •  Streak {}  •  Best {}",
+                            "Score {:04}  •  Streak {}  •  Best streak {}",
                             score.score, score.streak, score.best_streak
                         ));
+                        if let Some(run) = &run {
+                            let best = casual.practice_bests.get(run.drill, run.difficulty);
+                            let result = run.result(&score);
+                            match run.shots_left() {
+                                Some(left) => ui.label(format!(
+                                    "Hits {result}  •  Shots left {left}  •  Personal best {best}"
+                                )),
+                                None => ui.label(format!("Personal best {best}")),
+                            };
+                            if run.is_finished() {
+                                ui.label(
+                                    RichText::new(format!("Time! Final result {result}")).strong(),
+                                );
+                            } else {
+                                ui.label(format!("Time {:.1}s", run.remaining));
+                            }
+                            let label = if run.is_finished() {
+                                "Back to Menu"
+                            } else {
+                                "Leave Run"
+                            };
+                            if ui.button(label).clicked() {
+                                next_game_state.set(GameState::MainMenu);
+                            }
+                        }
                         ui.small("Move: WASD / arrows  •  Fire: Space / Enter");
                         ui.small("Touch: drag LEFT to move  •  hold RIGHT to fire");
                         if cooldown.remaining > 0.0 {
@@ -1088,10 +1676,126 @@ pub fn update_practice_ui(
         });
 }
 
-pub fn update_respawn_ui(mut contexts: EguiContexts, flow: Res<MatchFlow>) {
+/// Offered once on the main menu to a profile that has never played.
+pub fn update_tutorial_prompt_ui(
+    mut contexts: EguiContexts,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut request: ResMut<TutorialStatusRequest>,
+    casual: Res<CasualProfile>,
+) {
+    if !casual.tutorial.should_offer(casual.rounds_played) || request.0.is_some() {
+        return;
+    }
+    egui::Window::new("WELCOME, GHOST")
+        .id(Id::new("tutorial prompt"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, vec2(0.0, 0.0))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered_justified(|ui| {
+                ui.label("New here? A two-minute tutorial covers moving, firing, pickups, traps and how matches are won.");
+                if ui.button("▶ Start Tutorial").clicked() {
+                    next_game_state.set(GameState::Tutorial);
+                }
+                if ui.button("Skip").clicked() {
+                    request.0 = Some(TutorialStatus::Skipped);
+                }
+                ui.small("You can replay it any time from the main menu.");
+            });
+        });
+}
+
+pub fn update_tutorial_ui(
+    mut contexts: EguiContexts,
+    progress: Res<TutorialProgress>,
+    cooldown: Res<PracticeCooldown>,
+    players: Query<&PracticePlayer>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let safe = safe_screen_rect(contexts.ctx_mut());
+    Area::new("tutorial HUD")
+        .fixed_pos(pos2(safe.left(), (safe.bottom() - 170.0).max(safe.top())))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.set_width(safe.width());
+            Frame::none()
+                .fill(Color32::from_rgba_unmultiplied(20, 24, 34, 225))
+                .stroke(Stroke::new(2.0_f32, OUTLINE))
+                .inner_margin(Margin::symmetric(14.0, 8.0))
+                .show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let title = if progress.is_complete() {
+                            progress.step.title().to_uppercase()
+                        } else {
+                            format!(
+                                "TUTORIAL {}/{} • {}",
+                                progress.step.number(),
+                                TutorialStep::COUNT,
+                                progress.step.title().to_uppercase()
+                            )
+                        };
+                        ui.label(RichText::new(title).strong().color(ACCENT));
+                        ui.label(progress.step.prompt());
+                        if let Some(goal) = progress.goal() {
+                            ui.label(format!("{}/{goal}", progress.count));
+                        }
+                        let mut status = Vec::new();
+                        if cooldown.remaining > 0.0 {
+                            status.push(format!("Blaster cooling {:.2}s", cooldown.remaining));
+                        } else {
+                            status.push("Blaster ready".to_string());
+                        }
+                        if let Ok(player) = players.get_single() {
+                            if player.speed_boost() > 0.0 {
+                                status.push(format!("Speed boost {:.1}s", player.speed_boost()));
+                            }
+                        }
+                        if progress.shielded {
+                            status.push("Shield up".to_string());
+                        }
+                        ui.small(status.join("  •  "));
+                        let label = if progress.is_complete() {
+                            "Finish"
+                        } else {
+                            "Leave Tutorial"
+                        };
+                        if ui.button(label).clicked() {
+                            next_game_state.set(GameState::MainMenu);
+                        }
+                    });
+                });
+        });
+}
+
+/// Between rounds this covers the arena reset; mid-round it counts down a
+/// local ghost that lost a stock.
+pub fn update_respawn_ui(
+    mut contexts: EguiContexts,
+    flow: Res<MatchFlow>,
+    rollback: Res<State<RollbackState>>,
+    local: Option<Res<LocalPlayerHandle>>,
+    players: Query<(&Player, &Stocks, Option<&Respawning>)>,
+) {
     if matches!(*flow, MatchFlow::MatchOver { .. }) {
         return;
     }
+    let local_stocks = local.and_then(|local| {
+        players
+            .iter()
+            .find(|(player, _, _)| player.handle == local.0)
+            .map(|(_, stocks, respawning)| {
+                (stocks.0, respawning.map(|respawning| respawning.frames_left))
+            })
+    });
+    let text = if rollback.get() == &RollbackState::RoundEnd {
+        "SCORE!\nRespawning...".to_string()
+    } else if let Some((stocks, Some(frames_left))) = local_stocks {
+        format!(
+            "STOCK LOST\nBack in {:.1}s — {stocks} of {STOCK_LIVES} left",
+            frames_left as f32 / 60.0
+        )
+    } else {
+        return;
+    };
     let safe = safe_screen_rect(contexts.ctx_mut());
     Area::new("respawn info")
         .fixed_pos(pos2(safe.left(), safe.center().y - 44.0))
@@ -1099,7 +1803,7 @@ pub fn update_respawn_ui(mut contexts: EguiContexts, flow: Res<MatchFlow>) {
             ui.set_width(safe.width());
             ui.vertical_centered(|ui| {
                 ui.label(
-                    RichText::new("SCORE!\nRespawning...")
+                    RichText::new(text)
                         .color(Color32::WHITE)
                         .font(FontId::proportional(32.0)),
                 );
@@ -1,12 +1,27 @@
-use bevy::{input::touch::*, prelude::*, window::PrimaryWindow};
+use bevy::{
+    input::touch::*,
+    prelude::*,
+    window::{CursorMoved, PrimaryWindow},
+};
 use bevy_ggrs::ggrs;
 
+use super::components::Player;
+
 // constants for encoding movement commands
-const INPUT_UP: u8 = 1 << 0;
-const INPUT_DOWN: u8 = 1 << 1;
-const INPUT_LEFT: u8 = 1 << 2;
-const INPUT_RIGHT: u8 = 1 << 3;
-const INPUT_FIRE: u8 = 1 << 4;
+pub(super) const INPUT_UP: u16 = 1 << 0;
+pub(super) const INPUT_DOWN: u16 = 1 << 1;
+pub(super) const INPUT_LEFT: u16 = 1 << 2;
+pub(super) const INPUT_RIGHT: u16 = 1 << 3;
+pub(super) const INPUT_FIRE: u16 = 1 << 4;
+pub(super) const INPUT_DASH: u16 = 1 << 5;
+/// Set when the high byte carries an aim direction. Without it bullets and
+/// eyes follow the movement direction as before.
+pub(super) const INPUT_AIM: u16 = 1 << 6;
+pub(super) const AIM_SHIFT: u16 = 8;
+/// Aim is quantized to 32 directions, 11.25° apart.
+pub(super) const AIM_DIRECTIONS: u16 = 32;
+
+const GAMEPAD_AIM_DEADZONE: f32 = 0.5;
 
 // const INPUT_ALL: u8 = INPUT_UP
 //                     & INPUT_DOWN
@@ -62,15 +77,68 @@ const INPUT_FIRE: u8 = 1 << 4;
 #[derive(Default)]
 pub struct TouchMap(pub(crate) Option<u64>);
 
+/// Which aim device the player used last. Mouse aim follows the cursor from
+/// the moment the mouse moves or clicks until a stick or touch takes over.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AimScheme {
+    #[default]
+    Movement,
+    Mouse,
+    StickOrTouch,
+}
+
+impl AimScheme {
+    fn next(self, mouse_used: bool, stick_or_touch_used: bool) -> Self {
+        if stick_or_touch_used {
+            Self::StickOrTouch
+        } else if mouse_used {
+            Self::Mouse
+        } else {
+            self
+        }
+    }
+}
+
+/// Share of the screen height, from the top, that the right-hand dash zone
+/// covers. The rest of the right half stays the fire zone under the thumb.
+const DASH_ZONE_HEIGHT: f32 = 0.35;
+
+#[derive(Debug, PartialEq, Eq)]
+enum TouchZone {
+    Move,
+    Fire,
+    Dash,
+}
+
+/// Classifies a touch by where it started. Window coordinates grow downwards.
+fn touch_zone(start: Vec2, width: f32, height: f32) -> TouchZone {
+    if start.x < width / 2.0 {
+        TouchZone::Move
+    } else if start.y < height * DASH_ZONE_HEIGHT {
+        TouchZone::Dash
+    } else {
+        TouchZone::Fire
+    }
+}
+
 pub fn input(
-    _: In<ggrs::PlayerHandle>,
+    local_handle: In<ggrs::PlayerHandle>,
     keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
     // mut touch_evr: EventReader<TouchInput>,
     touches: Res<Touches>,
     window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    players: Query<(&Player, &Transform)>,
+    mut cursor_moves: EventReader<CursorMoved>,
     mut touch_map: Local<TouchMap>,
-) -> u8 {
-    let mut input = 0u8;
+    mut scheme: Local<AimScheme>,
+) -> u16 {
+    let mut input = 0u16;
+    let mut aim = None;
 
     if keys.any_pressed([KeyCode::Up, KeyCode::W]) {
         input |= INPUT_UP;
@@ -87,11 +155,56 @@ pub fn input(
     if keys.any_pressed([KeyCode::Space, KeyCode::Return]) {
         input |= INPUT_FIRE;
     }
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        input |= INPUT_DASH;
+    }
+
+    for gamepad in gamepads.iter() {
+        let stick = Vec2::new(
+            gamepad_axes
+                .get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickX))
+                .unwrap_or(0.0),
+            gamepad_axes
+                .get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickY))
+                .unwrap_or(0.0),
+        );
+        if stick.length() > GAMEPAD_AIM_DEADZONE {
+            aim = Some(stick);
+        }
+        if gamepad_buttons.pressed(GamepadButton::new(
+            gamepad,
+            GamepadButtonType::RightTrigger2,
+        )) {
+            input |= INPUT_FIRE;
+        }
+    }
+
+    let mouse_used = cursor_moves.iter().count() > 0
+        || mouse.any_pressed([MouseButton::Left, MouseButton::Right]);
+    *scheme = scheme.next(mouse_used, aim.is_some() || touches.iter().next().is_some());
 
     let Ok(window) = window.get_single() else {
-        return input;
+        return input | encode_aim(aim);
     };
 
+    // While the mouse is the aim device the ghost aims at the cursor, button
+    // or not; the left button fires, so keyboard movement stays free to
+    // retreat.
+    if *scheme == AimScheme::Mouse {
+        let cursor = window.cursor_position().zip(cameras.get_single().ok());
+        let ghost = players
+            .iter()
+            .find(|(player, _)| player.handle == local_handle.0);
+        if let (Some((cursor, (camera, camera_transform))), Some((_, ghost))) = (cursor, ghost) {
+            if let Some(world) = camera.viewport_to_world_2d(camera_transform, cursor) {
+                aim = Some(world - ghost.translation.truncate());
+            }
+        }
+        if mouse.pressed(MouseButton::Left) {
+            input |= INPUT_FIRE;
+        }
+    }
+
     if touch_map
         .0
         .is_some_and(|id| !touches.iter().any(|finger| finger.id() == id))
@@ -99,12 +212,23 @@ pub fn input(
         touch_map.0 = None;
     }
 
-    let screen_midpoint = window.width() / 2.0;
     let deadzone = (window.width().min(window.height()) * 0.08).clamp(32.0, 72.0);
     for finger in touches.iter() {
-        if finger.start_position().x >= screen_midpoint {
-            input |= INPUT_FIRE;
-            continue;
+        match touch_zone(finger.start_position(), window.width(), window.height()) {
+            TouchZone::Fire => {
+                input |= INPUT_FIRE;
+                // Dragging the fire touch aims; window y grows downwards.
+                let drag = finger.position() - finger.start_position();
+                if drag.length() > deadzone {
+                    aim = Some(Vec2::new(drag.x, -drag.y));
+                }
+                continue;
+            }
+            TouchZone::Dash => {
+                input |= INPUT_DASH;
+                continue;
+            }
+            TouchZone::Move => {}
         }
 
         if touch_map.0.is_none() {
@@ -133,10 +257,44 @@ pub fn input(
     //     }
     // }
 
-    input
+    input | encode_aim(aim)
+}
+
+/// Quantizes an aim vector into the `INPUT_AIM` flag and a direction index.
+/// Only the sender evaluates `atan2`; every peer decodes the shared index.
+fn encode_aim(aim: Option<Vec2>) -> u16 {
+    let Some(aim) = aim.filter(|aim| *aim != Vec2::ZERO) else {
+        return 0;
+    };
+    let turns = aim.y.atan2(aim.x) / std::f32::consts::TAU;
+    let index =
+        ((turns * AIM_DIRECTIONS as f32).round() as i32).rem_euclid(AIM_DIRECTIONS as i32) as u16;
+    INPUT_AIM | (index << AIM_SHIFT)
 }
 
-pub fn direction(input: u8) -> Vec2 {
+/// cos(k·π/16) for k in 0..=8, written out so every peer decodes bit-identical
+/// aim vectors without relying on platform trigonometry.
+const AIM_COS: [f32; 9] = [
+    1.0, 0.98078525, 0.9238795, 0.8314696, 0.70710677, 0.55557024, 0.38268343, 0.19509032, 0.0,
+];
+
+/// The decoded aim direction, if the input carries one.
+pub fn aim(input: u16) -> Option<Vec2> {
+    if input & INPUT_AIM == 0 {
+        return None;
+    }
+    let index = ((input >> AIM_SHIFT) % AIM_DIRECTIONS) as usize;
+    let step = index % 8;
+    let (cos, sin) = (AIM_COS[step], AIM_COS[8 - step]);
+    Some(match index / 8 {
+        0 => Vec2::new(cos, sin),
+        1 => Vec2::new(-sin, cos),
+        2 => Vec2::new(-cos, -sin),
+        _ => Vec2::new(sin, -cos),
+    })
+}
+
+pub fn direction(input: u16) -> Vec2 {
     let mut direction = Vec2::ZERO;
     if input & INPUT_UP != 0 {
         direction.y += 1.;
@@ -181,7 +339,7 @@ const UNIT_BR: Vec2 = Vec2 {
     x: -DIAGONAL_NORMALIZED,
     y: -DIAGONAL_NORMALIZED,
 };
-fn input_from_vec(dir: Vec2, deadzone: f32) -> u8 {
+fn input_from_vec(dir: Vec2, deadzone: f32) -> u16 {
     let mut input = 0;
 
     let magnitude = dir.length();
@@ -230,14 +388,32 @@ fn input_from_vec(dir: Vec2, deadzone: f32) -> u8 {
     input
 }
 
-pub fn fire(input: u8) -> bool {
+pub fn fire(input: u16) -> bool {
     input & INPUT_FIRE != 0
 }
 
+pub fn dash(input: u16) -> bool {
+    input & INPUT_DASH != 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn mouse_aim_holds_until_a_stick_or_touch_takes_over() {
+        let scheme = AimScheme::default();
+        assert_eq!(scheme.next(false, false), AimScheme::Movement);
+        let scheme = scheme.next(true, false);
+        assert_eq!(scheme, AimScheme::Mouse);
+        // A still mouse with no button held keeps aiming at the cursor.
+        assert_eq!(scheme.next(false, false), AimScheme::Mouse);
+        let scheme = scheme.next(true, true);
+        assert_eq!(scheme, AimScheme::StickOrTouch);
+        assert_eq!(scheme.next(false, false), AimScheme::StickOrTouch);
+        assert_eq!(scheme.next(true, false), AimScheme::Mouse);
+    }
+
     #[test]
     fn touch_deadzone_scales_without_changing_directions() {
         for deadzone in [32.0, 72.0] {
@@ -278,4 +454,57 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn right_half_splits_into_dash_above_fire() {
+        let (width, height) = (800.0, 600.0);
+        assert_eq!(
+            touch_zone(Vec2::new(100.0, 50.0), width, height),
+            TouchZone::Move
+        );
+        assert_eq!(
+            touch_zone(Vec2::new(100.0, 550.0), width, height),
+            TouchZone::Move
+        );
+        assert_eq!(
+            touch_zone(Vec2::new(700.0, 50.0), width, height),
+            TouchZone::Dash
+        );
+        assert_eq!(
+            touch_zone(Vec2::new(700.0, 205.0), width, height),
+            TouchZone::Dash
+        );
+        assert_eq!(
+            touch_zone(Vec2::new(700.0, 215.0), width, height),
+            TouchZone::Fire
+        );
+        assert_eq!(
+            touch_zone(Vec2::new(400.0, 550.0), width, height),
+            TouchZone::Fire
+        );
+        assert!(dash(INPUT_DASH | INPUT_FIRE) && !dash(INPUT_FIRE));
+        assert_eq!(input_from_vec(Vec2::X * 100.0, 32.0) & INPUT_DASH, 0);
+    }
+
+    #[test]
+    fn aim_round_trips_through_the_quantized_payload() {
+        assert_eq!(encode_aim(None), 0);
+        assert_eq!(encode_aim(Some(Vec2::ZERO)), 0);
+        assert_eq!(aim(INPUT_FIRE | INPUT_LEFT), None);
+
+        for index in 0..AIM_DIRECTIONS {
+            let input = INPUT_AIM | (index << AIM_SHIFT);
+            let decoded = aim(input).unwrap();
+            assert!((decoded.length() - 1.0).abs() < 1e-6);
+            assert_eq!(encode_aim(Some(decoded)), input);
+            // The high byte never disturbs the button bits.
+            assert_eq!(input & 0xff & !INPUT_AIM, 0);
+        }
+
+        assert_eq!(aim(encode_aim(Some(Vec2::new(3.0, 0.1)))), Some(Vec2::X));
+        assert_eq!(aim(encode_aim(Some(Vec2::new(0.0, -2.0)))), Some(-Vec2::Y));
+        let behind = aim(encode_aim(Some(Vec2::new(-5.0, 5.0)))).unwrap();
+        assert_eq!(behind.x, -behind.y);
+        assert!(behind.x < 0.0);
+    }
 }
@@ -6,20 +6,39 @@ use bevy_ggrs::AddRollbackCommandExtension;
 
 use super::{
     assets::procedural::{
-        shield_pickup_color, speed_pickup_color, trap_color, void_color, wall_face_color,
-        wall_foundation_color, PICKUP_SIZE, TRAP_SIZE,
+        cracked_wall_color, ectoplasm_color, health_pickup_color, phase_pickup_color,
+        shield_pickup_color, speed_pickup_color, teleporter_color, trap_color, void_color,
+        wall_face_color, wall_foundation_color, weapon_pickup_color, PICKUP_SIZE, TRAP_SIZE,
     },
-    components::{MapBlock, ShieldPickup, SpeedPickup},
-    player::grid_to_world,
-    session::{GameMode, RoundBootstrap},
+    components::{
+        CrackedBlock, HealthPickup, MapBlock, PhasePickup, PickupCharging, ShieldPickup,
+        SpeedPickup, Weapon, WeaponPickup,
+    },
+    ggrs_framecount::GGFrameCount,
+    player::{grid_to_world, PICKUP_RESPAWN_FRAMES},
+    resume::RoundResume,
+    round_clock::close_ring,
+    session::{GameMode, MatchRules, RoundBootstrap},
     GameSeed, RollbackState, RoundProgress, MAP_SIZE,
 };
 
-const MAP_DOMAIN: u64 = 0x6d61_705f_726f_756e;
-const TRAP_DOMAIN: u64 = 0x7472_6170_5f70_6169;
-const PICKUP_DOMAIN: u64 = 0x7069_636b_7570_7061;
-const SHIELD_DOMAIN: u64 = 0x7368_6965_6c64_7061;
-const WALL_PERCENT: u64 = 23;
+pub(super) const MAP_DOMAIN: u64 = 0x6d61_705f_726f_756e;
+pub(super) const TRAP_DOMAIN: u64 = 0x7472_6170_5f70_6169;
+pub(super) const PICKUP_DOMAIN: u64 = 0x7069_636b_7570_7061;
+pub(super) const SHIELD_DOMAIN: u64 = 0x7368_6965_6c64_7061;
+pub(super) const HEALTH_DOMAIN: u64 = 0x6865_616c_7468_5f70;
+pub(super) const WEAPON_DOMAIN: u64 = 0x7765_6170_6f6e_7061;
+pub(super) const CRACKED_DOMAIN: u64 = 0x6372_6163_6b65_645f;
+pub(super) const TELEPORT_DOMAIN: u64 = 0x7465_6c65_706f_7274;
+pub(super) const SLOW_DOMAIN: u64 = 0x6563_746f_706c_6173;
+pub(super) const PHASE_DOMAIN: u64 = 0x7068_6173_655f_7069;
+/// Mirrored ectoplasm pairs per map.
+pub(super) const SLOW_ZONE_PAIRS: u64 = 2;
+pub(super) const WALL_PERCENT: u64 = 23;
+/// Share of wall pairs generated as destructible cracked walls.
+pub(super) const CRACKED_PERCENT: u64 = 20;
+/// Bullet hits a cracked wall absorbs before it crumbles to `Empty`.
+pub(super) const CRACKED_WALL_HITS: u8 = 3;
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
 pub enum CellType {
@@ -30,8 +49,64 @@ pub enum CellType {
     SpeedPickup,
     ShieldPickup,
     Void,
+    /// Only generated under the multi-hit health rule.
+    HealthPickup,
+    SpreadPickup,
+    RicochetPickup,
+    HeavyPickup,
+    /// Blocks like `WallBlock` until it has absorbed `CRACKED_WALL_HITS`.
+    CrackedWall,
+    /// Sends a ghost to its partner, the point-mirrored teleporter cell.
+    Teleporter,
+    /// Ectoplasm that slows every ghost crossing it.
+    SlowZone,
+    PhasePickup,
+}
+
+/// One weapon pair is placed per round; the seed picks which.
+const WEAPON_PICKUPS: [CellType; 3] = [
+    CellType::SpreadPickup,
+    CellType::RicochetPickup,
+    CellType::HeavyPickup,
+];
+
+impl CellType {
+    pub fn weapon(self) -> Option<Weapon> {
+        match self {
+            Self::SpreadPickup => Some(Weapon::Spread),
+            Self::RicochetPickup => Some(Weapon::Ricochet),
+            Self::HeavyPickup => Some(Weapon::Heavy),
+            _ => None,
+        }
+    }
+
+    /// Cells that stop players and bullets.
+    pub fn is_solid(self) -> bool {
+        matches!(self, Self::WallBlock | Self::CrackedWall | Self::Void)
+    }
+
+    /// Phasing ghosts drift through every wall but are still held by the void.
+    pub fn blocks_ghost(self, phasing: bool) -> bool {
+        if phasing {
+            self == Self::Void
+        } else {
+            self.is_solid()
+        }
+    }
+
+    /// Cells that spawn a collectable rollback entity.
+    pub fn is_pickup(self) -> bool {
+        matches!(
+            self,
+            Self::SpeedPickup | Self::ShieldPickup | Self::HealthPickup | Self::PhasePickup
+        ) || self.weapon().is_some()
+    }
 }
 
+/// Bullet hits taken per cell, rolled back next to the map it damages. Only
+/// `CrackedWall` cells ever count up; a crumbled wall keeps its final count.
+pub type WallDamage = Map<u8, MAP_SIZE, MAP_SIZE>;
+
 #[derive(Resource, Reflect, Clone)]
 #[reflect(Resource)]
 pub struct Map<T: Sized + Default + Copy, const WIDTH: usize, const HEIGHT: usize> {
@@ -51,10 +126,10 @@ impl<T: Default + Copy, const WIDTH: usize, const HEIGHT: usize> Default for Map
 impl<const SIZE: usize> Map<CellType, SIZE, SIZE> {
     #[cfg(any(test, feature = "sync_test"))]
     pub(crate) fn generated(seed: u64) -> Self {
-        Self::generated_with_size(seed, SIZE)
+        Self::generated_with_size(seed, SIZE, false)
     }
 
-    pub(crate) fn generated_with_size(seed: u64, active_size: usize) -> Self {
+    pub(crate) fn generated_with_size(seed: u64, active_size: usize, health_pickups: bool) -> Self {
         assert!(active_size > 0 && active_size <= SIZE && active_size % 2 == 1);
         let mut cells = [[CellType::Void; SIZE]; SIZE];
         let center = SIZE / 2;
@@ -99,6 +174,7 @@ impl<const SIZE: usize> Map<CellType, SIZE, SIZE> {
             }
         }
 
+        crack_walls(&mut cells, seed ^ CRACKED_DOMAIN);
         place_feature_pair(
             &mut cells,
             seed ^ TRAP_DOMAIN,
@@ -123,10 +199,131 @@ impl<const SIZE: usize> Map<CellType, SIZE, SIZE> {
             end,
             CellType::ShieldPickup,
         );
+        place_feature_pair(
+            &mut cells,
+            seed ^ TELEPORT_DOMAIN,
+            center,
+            start,
+            end,
+            CellType::Teleporter,
+        );
+        for pair in 0..SLOW_ZONE_PAIRS {
+            place_feature_pair(
+                &mut cells,
+                seed ^ SLOW_DOMAIN ^ pair,
+                center,
+                start,
+                end,
+                CellType::SlowZone,
+            );
+        }
+        place_feature_pair(
+            &mut cells,
+            seed ^ PHASE_DOMAIN,
+            center,
+            start,
+            end,
+            CellType::PhasePickup,
+        );
+        let weapon = (splitmix64(seed ^ WEAPON_DOMAIN) >> 32) % WEAPON_PICKUPS.len() as u64;
+        place_feature_pair(
+            &mut cells,
+            seed ^ WEAPON_DOMAIN,
+            center,
+            start,
+            end,
+            WEAPON_PICKUPS[weapon as usize],
+        );
+        // Placed last so one-shot maps are unchanged by the optional rule.
+        if health_pickups {
+            place_feature_pair(
+                &mut cells,
+                seed ^ HEALTH_DOMAIN,
+                center,
+                start,
+                end,
+                CellType::HealthPickup,
+            );
+        }
         Self { cells, active_size }
     }
 }
 
+/// Turns a seeded share of mirrored wall pairs into cracked walls. Runs after
+/// the reachability pass, so sealed pockets may open up once walls crumble.
+fn crack_walls<const SIZE: usize>(cells: &mut [[CellType; SIZE]; SIZE], seed: u64) {
+    for x in 0..SIZE {
+        for y in 0..SIZE {
+            let mirror = (SIZE - 1 - x, SIZE - 1 - y);
+            if (x, y) > mirror || cells[x][y] != CellType::WallBlock {
+                continue;
+            }
+            let coordinate = ((x as u64) << 32) | y as u64;
+            if splitmix64(seed ^ coordinate) % 100 < CRACKED_PERCENT {
+                cells[x][y] = CellType::CrackedWall;
+                cells[mirror.0][mirror.1] = CellType::CrackedWall;
+            }
+        }
+    }
+}
+
+/// Teleporters are placed as a mirrored pair, so each one's partner is its
+/// point reflection through the arena centre.
+pub fn teleporter_partner((x, y): (u32, u32)) -> (u32, u32) {
+    (MAP_SIZE as u32 - 1 - x, MAP_SIZE as u32 - 1 - y)
+}
+
+/// Records a bullet hit on a cracked wall and crumbles it on the last one.
+/// Returns true when the cell became `Empty`.
+pub fn damage_wall(
+    map: &mut Map<CellType, MAP_SIZE, MAP_SIZE>,
+    damage: &mut WallDamage,
+    (x, y): (usize, usize),
+) -> bool {
+    if map.cells[x][y] != CellType::CrackedWall {
+        return false;
+    }
+    damage.cells[x][y] = damage.cells[x][y].saturating_add(1);
+    if damage.cells[x][y] < CRACKED_WALL_HITS {
+        return false;
+    }
+    map.cells[x][y] = CellType::Empty;
+    true
+}
+
+/// Cracked wall cells that have taken hits, crumbled ones included, in the
+/// form a resume snapshot carries them.
+pub fn damaged_walls(damage: &WallDamage) -> Vec<((u16, u16), u8)> {
+    let mut walls = Vec::new();
+    for x in 0..MAP_SIZE {
+        for y in 0..MAP_SIZE {
+            if damage.cells[x][y] > 0 {
+                walls.push(((x as u16, y as u16), damage.cells[x][y]));
+            }
+        }
+    }
+    walls
+}
+
+/// Re-applies snapshot wall damage to a freshly generated map, crumbling the
+/// walls that had already taken their last hit.
+fn restore_wall_damage(
+    map: &mut Map<CellType, MAP_SIZE, MAP_SIZE>,
+    damage: &mut WallDamage,
+    walls: &[((u16, u16), u8)],
+) {
+    for &((x, y), hits) in walls {
+        let (x, y) = (x as usize, y as usize);
+        if map.cells[x][y] != CellType::CrackedWall {
+            continue;
+        }
+        damage.cells[x][y] = hits.min(CRACKED_WALL_HITS);
+        if hits >= CRACKED_WALL_HITS {
+            map.cells[x][y] = CellType::Empty;
+        }
+    }
+}
+
 fn place_feature_pair<const SIZE: usize>(
     cells: &mut [[CellType; SIZE]; SIZE],
     seed: u64,
@@ -196,21 +393,43 @@ pub(crate) fn splitmix64(mut value: u64) -> u64 {
     value ^ (value >> 31)
 }
 
+/// Side length of the playable square a round starts with.
+pub fn arena_size(mode: GameMode) -> usize {
+    match mode {
+        GameMode::Duel => 21,
+        GameMode::Deathmatch => MAP_SIZE,
+    }
+}
+
 pub fn generate_map(
     mut commands: Commands,
     mut seed: ResMut<GameSeed>,
+    frame: Res<GGFrameCount>,
     bootstrap: Res<RoundBootstrap>,
+    rules: Res<MatchRules>,
+    resume: Res<RoundResume>,
     mut state: ResMut<NextState<RollbackState>>,
 ) {
-    let active_size = match bootstrap.mode {
-        GameMode::Duel => 21,
-        GameMode::Deathmatch => MAP_SIZE,
-    };
-    commands.insert_resource(RoundProgress::default());
-    commands.insert_resource(Map::<CellType, MAP_SIZE, MAP_SIZE>::generated_with_size(
+    let active_size = arena_size(bootstrap.mode);
+    let mut started_frame = frame.frame;
+    let mut map = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated_with_size(
         seed.0,
         active_size,
-    ));
+        rules.multi_hit_health,
+    );
+    let mut damage = WallDamage::default();
+    if let Some(snapshot) = resume.at_round_start(&frame) {
+        // Continue the interrupted round's clock and overtime rings.
+        started_frame = frame.frame.wrapping_sub(snapshot.elapsed);
+        restore_wall_damage(&mut map, &mut damage, &snapshot.walls);
+        while map.active_size > snapshot.active_size && close_ring(&mut map) {}
+    }
+    commands.insert_resource(RoundProgress {
+        started_frame,
+        ..default()
+    });
+    commands.insert_resource(damage);
+    commands.insert_resource(map);
     seed.0 = splitmix64(seed.0 ^ MAP_DOMAIN);
     state.set(RollbackState::InRound);
 }
@@ -233,9 +452,28 @@ fn is_void_boundary<const SIZE: usize>(
         })
 }
 
-pub fn spawn_map_sprites(mut commands: Commands, map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>) {
+pub fn spawn_map_sprites(
+    mut commands: Commands,
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    resume: Res<RoundResume>,
+    frame: Res<GGFrameCount>,
+) {
+    let charging = resume
+        .at_round_start(&frame)
+        .map(|snapshot| snapshot.charging.as_slice())
+        .unwrap_or_default();
     for x in 0..MAP_SIZE {
         for y in 0..MAP_SIZE {
+            let cell = (x as u16, y as u16);
+            let pickup_charging = map_data.cells[x][y]
+                .is_pickup()
+                .then(|| {
+                    charging
+                        .iter()
+                        .find(|(charging_cell, _)| *charging_cell == cell)
+                        .map(|(_, frames_left)| *frames_left)
+                })
+                .flatten();
             let wall_neighbors = [
                 x > 0 && map_data.cells[x - 1][y] == CellType::WallBlock,
                 x + 1 < MAP_SIZE && map_data.cells[x + 1][y] == CellType::WallBlock,
@@ -254,47 +492,78 @@ pub fn spawn_map_sprites(mut commands: Commands, map_data: Res<Map<CellType, MAP
                     },
                     Vec2::splat(if wall_neighbors < 2 { 0.86 } else { 0.96 }),
                 ),
-                CellType::Trap => (trap_color(), Vec2::splat(TRAP_SIZE)),
-                CellType::SpeedPickup => {
-                    commands
-                        .spawn((
-                            SpeedPickup {
-                                cell: (x as u16, y as u16),
-                            },
-                            SpriteBundle {
-                                transform: Transform::from_translation(
-                                    grid_to_world((x as u32, y as u32)).extend(0.),
-                                ),
-                                sprite: Sprite {
-                                    color: speed_pickup_color(),
-                                    custom_size: Some(Vec2::splat(PICKUP_SIZE)),
-                                    ..default()
-                                },
+                CellType::CrackedWall => {
+                    commands.spawn((
+                        MapBlock,
+                        CrackedBlock {
+                            cell: (x as u16, y as u16),
+                        },
+                        SpriteBundle {
+                            transform: Transform::from_translation(
+                                grid_to_world((x as u32, y as u32)).extend(-1.),
+                            ),
+                            sprite: Sprite {
+                                color: cracked_wall_color(0),
+                                custom_size: Some(Vec2::splat(0.86)),
                                 ..default()
                             },
-                        ))
-                        .add_rollback();
+                            ..default()
+                        },
+                    ));
+                    continue;
+                }
+                CellType::Trap => (trap_color(), Vec2::splat(TRAP_SIZE)),
+                CellType::Teleporter => (teleporter_color(), Vec2::splat(TRAP_SIZE)),
+                CellType::SlowZone => (ectoplasm_color(), Vec2::splat(0.96)),
+                CellType::SpeedPickup => {
+                    spawn_pickup(
+                        &mut commands,
+                        SpeedPickup { cell },
+                        cell,
+                        speed_pickup_color(),
+                        pickup_charging,
+                    );
                     continue;
                 }
                 CellType::ShieldPickup => {
-                    commands
-                        .spawn((
-                            ShieldPickup {
-                                cell: (x as u16, y as u16),
-                            },
-                            SpriteBundle {
-                                transform: Transform::from_translation(
-                                    grid_to_world((x as u32, y as u32)).extend(0.),
-                                ),
-                                sprite: Sprite {
-                                    color: shield_pickup_color(),
-                                    custom_size: Some(Vec2::splat(PICKUP_SIZE)),
-                                    ..default()
-                                },
-                                ..default()
-                            },
-                        ))
-                        .add_rollback();
+                    spawn_pickup(
+                        &mut commands,
+                        ShieldPickup { cell },
+                        cell,
+                        shield_pickup_color(),
+                        pickup_charging,
+                    );
+                    continue;
+                }
+                CellType::HealthPickup => {
+                    spawn_pickup(
+                        &mut commands,
+                        HealthPickup { cell },
+                        cell,
+                        health_pickup_color(),
+                        pickup_charging,
+                    );
+                    continue;
+                }
+                CellType::PhasePickup => {
+                    spawn_pickup(
+                        &mut commands,
+                        PhasePickup { cell },
+                        cell,
+                        phase_pickup_color(),
+                        pickup_charging,
+                    );
+                    continue;
+                }
+                CellType::SpreadPickup | CellType::RicochetPickup | CellType::HeavyPickup => {
+                    let weapon = map_data.cells[x][y].weapon().unwrap_or_default();
+                    spawn_pickup(
+                        &mut commands,
+                        WeaponPickup { cell, weapon },
+                        cell,
+                        weapon_pickup_color(weapon),
+                        pickup_charging,
+                    );
                     continue;
                 }
                 CellType::Void if is_void_boundary(&map_data, x, y) => {
@@ -320,10 +589,106 @@ pub fn spawn_map_sprites(mut commands: Commands, map_data: Res<Map<CellType, MAP
     }
 }
 
+/// Spawns a rollback pickup on its cell. A resumed round passes the respawn
+/// frames of a pickup that was still charging when the snapshot was taken.
+fn spawn_pickup(
+    commands: &mut Commands,
+    pickup: impl Bundle,
+    cell: (u16, u16),
+    color: Color,
+    charging: Option<u16>,
+) {
+    let mut entity = commands.spawn((
+        pickup,
+        SpriteBundle {
+            transform: Transform::from_translation(
+                grid_to_world((u32::from(cell.0), u32::from(cell.1))).extend(0.),
+            ),
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(PICKUP_SIZE)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+    entity.add_rollback();
+    if let Some(frames_left) = charging {
+        entity.insert(PickupCharging { frames_left });
+    }
+}
+
+/// Restyles only the cracked wall cells from the rolled back map. Crumbled
+/// walls are hidden rather than despawned so a rollback can bring them back.
+pub fn reconcile_cracked_walls(
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    damage: Res<WallDamage>,
+    mut blocks: Query<(&CrackedBlock, &mut Sprite, &mut Visibility)>,
+) {
+    for (block, mut sprite, mut visibility) in &mut blocks {
+        let (x, y) = (block.cell.0 as usize, block.cell.1 as usize);
+        let standing = map_data.cells[x][y] == CellType::CrackedWall;
+        let wanted = if standing {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+        let color = cracked_wall_color(damage.cells[x][y]);
+        if standing && sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+/// Size and alpha of a pickup sprite. A charging pickup is drawn as a faint
+/// square that grows back to full size as its respawn timer runs down.
+fn pickup_indicator(charging: Option<&PickupCharging>) -> (f32, f32) {
+    let Some(charging) = charging else {
+        return (PICKUP_SIZE, 1.0);
+    };
+    let progress = 1.0 - f32::from(charging.frames_left) / f32::from(PICKUP_RESPAWN_FRAMES);
+    (PICKUP_SIZE * (0.2 + 0.6 * progress.clamp(0.0, 1.0)), 0.35)
+}
+
+pub fn reconcile_pickup_charging(
+    mut pickups: Query<
+        (Option<&PickupCharging>, &mut Sprite),
+        Or<(
+            With<SpeedPickup>,
+            With<ShieldPickup>,
+            With<HealthPickup>,
+            With<WeaponPickup>,
+            With<PhasePickup>,
+        )>,
+    >,
+) {
+    for (charging, mut sprite) in &mut pickups {
+        let (size, alpha) = pickup_indicator(charging);
+        if sprite.custom_size != Some(Vec2::splat(size)) {
+            sprite.custom_size = Some(Vec2::splat(size));
+        }
+        if sprite.color.a() != alpha {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
+
 pub fn clear_map_sprites(
     mut commands: Commands,
     blocks: Query<Entity, With<MapBlock>>,
-    pickups: Query<Entity, Or<(With<SpeedPickup>, With<ShieldPickup>)>>,
+    pickups: Query<
+        Entity,
+        Or<(
+            With<SpeedPickup>,
+            With<ShieldPickup>,
+            With<HealthPickup>,
+            With<WeaponPickup>,
+            With<PhasePickup>,
+        )>,
+    >,
 ) {
     for entity in blocks.iter().chain(pickups.iter()) {
         commands.entity(entity).despawn_recursive();
@@ -341,6 +706,7 @@ mod tests {
         let mut traps = 0;
         let mut pickups = 0;
         let mut shields = 0;
+        let mut weapons = 0;
 
         for x in 0..MAP_SIZE {
             for y in 0..MAP_SIZE {
@@ -349,10 +715,7 @@ mod tests {
                     first.cells[x][y],
                     first.cells[MAP_SIZE - 1 - x][MAP_SIZE - 1 - y]
                 );
-                if matches!(
-                    first.cells[x][y],
-                    CellType::Trap | CellType::SpeedPickup | CellType::ShieldPickup
-                ) {
+                if first.cells[x][y] == CellType::Trap || first.cells[x][y].is_pickup() {
                     assert!(x > 0 && y > 0 && x + 1 < MAP_SIZE && y + 1 < MAP_SIZE);
                     assert_ne!(x, MAP_SIZE / 2);
                     assert_ne!(y, MAP_SIZE / 2);
@@ -360,17 +723,33 @@ mod tests {
                 traps += usize::from(first.cells[x][y] == CellType::Trap);
                 pickups += usize::from(first.cells[x][y] == CellType::SpeedPickup);
                 shields += usize::from(first.cells[x][y] == CellType::ShieldPickup);
+                weapons += usize::from(first.cells[x][y].weapon().is_some());
             }
         }
 
         assert_eq!(traps, 2);
         assert_eq!(pickups, 2);
         assert_eq!(shields, 2);
+        assert_eq!(weapons, 2);
         assert_eq!(first.cells[MAP_SIZE / 2][MAP_SIZE / 2], CellType::Empty);
         assert_eq!(first.cells[0][0], CellType::Empty);
         assert_eq!(first.cells[MAP_SIZE - 1][MAP_SIZE - 1], CellType::Empty);
 
-        let duel = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated_with_size(42, 21);
+        let healing = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated_with_size(42, MAP_SIZE, true);
+        let mut health = 0;
+        for x in 0..MAP_SIZE {
+            for y in 0..MAP_SIZE {
+                if healing.cells[x][y] == CellType::HealthPickup {
+                    health += 1;
+                    assert_eq!(first.cells[x][y], CellType::Empty);
+                } else {
+                    assert_eq!(healing.cells[x][y], first.cells[x][y]);
+                }
+            }
+        }
+        assert_eq!(health, 2);
+
+        let duel = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated_with_size(42, 21, false);
         assert_eq!(duel.active_size, 21);
         assert_eq!(duel.cells[0][0], CellType::Void);
         assert_ne!(void_color(), Color::BLACK);
@@ -379,4 +758,96 @@ mod tests {
         assert!(is_void_boundary(&duel, edge - 1, MAP_SIZE / 2));
         assert_eq!(duel.cells[MAP_SIZE / 2][MAP_SIZE / 2], CellType::Empty);
     }
+
+    #[test]
+    fn cracked_walls_are_mirrored_and_crumble_after_enough_hits() {
+        let (seed, mut map, (x, y)) = (0..64)
+            .find_map(|seed| {
+                let map = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated(seed);
+                let cell = (0..MAP_SIZE)
+                    .flat_map(|x| (0..MAP_SIZE).map(move |y| (x, y)))
+                    .find(|&(x, y)| map.cells[x][y] == CellType::CrackedWall)?;
+                Some((seed, map, cell))
+            })
+            .expect("some seed generates a cracked wall");
+        assert_eq!(
+            map.cells[MAP_SIZE - 1 - x][MAP_SIZE - 1 - y],
+            CellType::CrackedWall
+        );
+        assert!(CellType::CrackedWall.is_solid());
+
+        let mut damage = WallDamage::default();
+        for _ in 1..CRACKED_WALL_HITS {
+            assert!(!damage_wall(&mut map, &mut damage, (x, y)));
+            assert_eq!(map.cells[x][y], CellType::CrackedWall);
+        }
+        assert!(damage_wall(&mut map, &mut damage, (x, y)));
+        assert_eq!(map.cells[x][y], CellType::Empty);
+        assert!(!damage_wall(&mut map, &mut damage, (x, y)));
+        assert_eq!(damage.cells[x][y], CRACKED_WALL_HITS);
+
+        let (mirror_x, mirror_y) = (MAP_SIZE - 1 - x, MAP_SIZE - 1 - y);
+        damage_wall(&mut map, &mut damage, (mirror_x, mirror_y));
+        let walls = damaged_walls(&damage);
+        assert_eq!(walls.len(), 2);
+        let mut resumed = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated(seed);
+        let mut resumed_damage = WallDamage::default();
+        restore_wall_damage(&mut resumed, &mut resumed_damage, &walls);
+        assert_eq!(resumed.cells, map.cells);
+        assert_eq!(resumed_damage.cells, damage.cells);
+    }
+
+    #[test]
+    fn teleporters_pair_up_and_ectoplasm_is_mirrored() {
+        let map = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated(42);
+        let teleporters: Vec<_> = (0..MAP_SIZE as u32)
+            .flat_map(|x| (0..MAP_SIZE as u32).map(move |y| (x, y)))
+            .filter(|&(x, y)| map.cells[x as usize][y as usize] == CellType::Teleporter)
+            .collect();
+        assert_eq!(teleporters.len(), 2);
+        assert_eq!(teleporter_partner(teleporters[0]), teleporters[1]);
+        assert_eq!(teleporter_partner(teleporters[1]), teleporters[0]);
+        assert_eq!(
+            teleporter_partner(teleporter_partner((3, 17))),
+            (3, 17),
+            "a partner's partner is the original pad"
+        );
+
+        let slow = map
+            .cells
+            .iter()
+            .flatten()
+            .filter(|cell| **cell == CellType::SlowZone)
+            .count();
+        assert_eq!(slow as u64, SLOW_ZONE_PAIRS * 2);
+        assert!(!CellType::Teleporter.is_solid() && !CellType::SlowZone.is_solid());
+        assert!(CellType::WallBlock.blocks_ghost(false) && !CellType::WallBlock.blocks_ghost(true));
+        assert!(CellType::Void.blocks_ghost(true));
+    }
+
+    #[test]
+    fn charging_pickups_grow_back_to_full_size() {
+        assert_eq!(pickup_indicator(None), (PICKUP_SIZE, 1.0));
+        let just_taken = pickup_indicator(Some(&PickupCharging {
+            frames_left: PICKUP_RESPAWN_FRAMES,
+        }));
+        let almost_back = pickup_indicator(Some(&PickupCharging { frames_left: 1 }));
+        assert!(just_taken.0 < almost_back.0 && almost_back.0 < PICKUP_SIZE);
+        assert!(just_taken.1 < 1.0 && almost_back.1 < 1.0);
+    }
+
+    #[test]
+    fn every_weapon_pickup_appears_across_seeds() {
+        let mut seen: Vec<_> = (0..64)
+            .filter_map(|seed| {
+                let map = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated(seed);
+                map.cells.iter().flatten().find_map(|cell| cell.weapon())
+            })
+            .collect();
+        seen.sort_by_key(|weapon| *weapon as u8);
+        seen.dedup();
+        assert_eq!(seen, [Weapon::Spread, Weapon::Ricochet, Weapon::Heavy]);
+        assert_eq!(CellType::Empty.weapon(), None);
+        assert!(!CellType::Trap.is_pickup());
+    }
 }
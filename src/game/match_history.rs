@@ -0,0 +1,391 @@
+//! The last few completed matches, kept in the casual profile.
+//!
+//! A match is recorded when `CasualProfile` first processes the event ID of
+//! the round that ended it, so like points and ratings a record is written at
+//! most once per match, however often rollback or a reload revisits that
+//! frame. Records are display-only; nothing is derived from them.
+
+use super::{
+    progression::valid_event_id,
+    session::{match_winner, GameMode, PlayerId, PlayerProfile, PlayerScore, MAX_LOBBY_PLAYERS},
+};
+
+pub const MAX_MATCH_HISTORY: usize = 20;
+const RECORD_SEPARATOR: char = ';';
+const FIELD_SEPARATOR: char = ',';
+const PARTICIPANT_SEPARATOR: char = '|';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchParticipant {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub palette_id: u8,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRecord {
+    /// Event ID of the round that ended the match, from `outcome_event_id`.
+    pub event_id: String,
+    /// Unix seconds on the local clock when the result was confirmed.
+    pub finished_at: u64,
+    pub mode: GameMode,
+    pub local_player: PlayerId,
+    /// Final scores in canonical player-ID order.
+    pub participants: Vec<MatchParticipant>,
+}
+
+impl MatchRecord {
+    pub fn winner(&self) -> Option<&MatchParticipant> {
+        let scores: Vec<_> = self
+            .participants
+            .iter()
+            .map(|participant| PlayerScore {
+                player_id: participant.player_id,
+                score: participant.score,
+            })
+            .collect();
+        let winner = match_winner(&scores)?;
+        self.participants
+            .iter()
+            .find(|participant| participant.player_id == winner.player_id)
+    }
+
+    pub fn local(&self) -> Option<&MatchParticipant> {
+        self.participants
+            .iter()
+            .find(|participant| participant.player_id == self.local_player)
+    }
+
+    pub fn local_won(&self) -> bool {
+        self.winner()
+            .is_some_and(|winner| winner.player_id == self.local_player)
+    }
+
+    /// 1-based standing by final score; tied ghosts share a place.
+    pub fn local_placement(&self) -> usize {
+        let local_score = self.local().map_or(0, |local| local.score);
+        1 + self
+            .participants
+            .iter()
+            .filter(|participant| participant.score > local_score)
+            .count()
+    }
+
+    /// The match ID and session epoch parts of the event ID.
+    pub fn match_and_epoch(&self) -> (&str, &str) {
+        let mut parts = self.event_id.split(':');
+        (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+        )
+    }
+
+    fn encode(&self) -> String {
+        let participants = self
+            .participants
+            .iter()
+            .map(|participant| {
+                format!(
+                    "{:x}.{}.{}.{}",
+                    participant.player_id.0,
+                    participant.palette_id,
+                    participant.score,
+                    escape_name(&participant.name)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(&PARTICIPANT_SEPARATOR.to_string());
+        [
+            self.event_id.clone(),
+            self.finished_at.to_string(),
+            mode_code(self.mode).to_owned(),
+            format!("{:x}", self.local_player.0),
+            participants,
+        ]
+        .join(&FIELD_SEPARATOR.to_string())
+    }
+
+    fn decode(value: &str) -> Option<Self> {
+        let mut fields = value.split(FIELD_SEPARATOR);
+        let event_id = fields.next().filter(|id| valid_event_id(id))?.to_owned();
+        let finished_at = fields.next()?.parse().ok()?;
+        let mode = match fields.next()? {
+            "duel" => GameMode::Duel,
+            "lgs" => GameMode::Deathmatch,
+            _ => return None,
+        };
+        let local_player = parse_player_id(fields.next()?)?;
+        let participants = fields
+            .next()?
+            .split(PARTICIPANT_SEPARATOR)
+            .map(decode_participant)
+            .collect::<Option<Vec<_>>>()?;
+        let record = Self {
+            event_id,
+            finished_at,
+            mode,
+            local_player,
+            participants,
+        };
+        let ordered = record
+            .participants
+            .windows(2)
+            .all(|pair| pair[0].player_id < pair[1].player_id);
+        (fields.next().is_none()
+            && ordered
+            && record.participants.len() <= MAX_LOBBY_PLAYERS
+            && record.local().is_some())
+        .then_some(record)
+    }
+}
+
+/// Newest first, at most `MAX_MATCH_HISTORY` records, one per event ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchHistory(Vec<MatchRecord>);
+
+impl MatchHistory {
+    pub fn records(&self) -> &[MatchRecord] {
+        &self.0
+    }
+
+    pub fn find(&self, event_id: &str) -> Option<&MatchRecord> {
+        self.0.iter().find(|record| record.event_id == event_id)
+    }
+
+    /// Returns whether the record was new.
+    pub fn record(&mut self, record: MatchRecord) -> bool {
+        if self.find(&record.event_id).is_some() {
+            return false;
+        }
+        self.0.push(record);
+        self.normalize();
+        true
+    }
+
+    /// Keeps the newest records of both histories.
+    pub fn merge(&mut self, other: MatchHistory) {
+        for record in other.0 {
+            self.record(record);
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        self.0
+            .iter()
+            .map(MatchRecord::encode)
+            .collect::<Vec<_>>()
+            .join(&RECORD_SEPARATOR.to_string())
+    }
+
+    /// Damaged records are dropped one by one; the rest still read.
+    pub fn decode(value: &str) -> Self {
+        let mut history = Self::default();
+        for record in value
+            .split(RECORD_SEPARATOR)
+            .filter_map(MatchRecord::decode)
+        {
+            history.record(record);
+        }
+        history
+    }
+
+    fn normalize(&mut self) {
+        self.0.sort_by(|a, b| {
+            b.finished_at
+                .cmp(&a.finished_at)
+                .then_with(|| b.event_id.cmp(&a.event_id))
+        });
+        self.0.truncate(MAX_MATCH_HISTORY);
+    }
+}
+
+/// `YYYY-MM-DD HH:MM UTC` for a Unix timestamp.
+pub fn format_timestamp(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let minutes = seconds % 86_400 / 60;
+    // Days-to-civil conversion for the proleptic Gregorian calendar, with
+    // eras of 400 years starting on March 1st.
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+fn mode_code(mode: GameMode) -> &'static str {
+    match mode {
+        GameMode::Duel => "duel",
+        GameMode::Deathmatch => "lgs",
+    }
+}
+
+fn parse_player_id(value: &str) -> Option<PlayerId> {
+    (!value.is_empty() && value.len() <= 32)
+        .then(|| u128::from_str_radix(value, 16).ok().map(PlayerId))
+        .flatten()
+}
+
+fn decode_participant(value: &str) -> Option<MatchParticipant> {
+    let mut fields = value.splitn(4, '.');
+    let player_id = parse_player_id(fields.next()?)?;
+    let palette_id = fields
+        .next()?
+        .parse::<u8>()
+        .ok()
+        .filter(|id| usize::from(*id) < MAX_LOBBY_PLAYERS)?;
+    let score = fields.next()?.parse().ok()?;
+    let name = unescape_name(fields.next()?)?;
+    (!name.is_empty() && name == PlayerProfile::sanitized_name(&name)).then_some(MatchParticipant {
+        player_id,
+        name,
+        palette_id,
+        score,
+    })
+}
+
+/// Names may contain any separator this format uses, so those characters
+/// and `%` itself are written as `%XX`.
+fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for character in name.chars() {
+        match character {
+            '%' | ';' | ',' | '|' => escaped.push_str(&format!("%{:02X}", character as u32)),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn unescape_name(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let high = char::from(input.next()?).to_digit(16)?;
+            let low = char::from(input.next()?).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(event_id: &str, finished_at: u64, scores: &[(u128, &str, u32)]) -> MatchRecord {
+        MatchRecord {
+            event_id: event_id.into(),
+            finished_at,
+            mode: if scores.len() == 2 {
+                GameMode::Duel
+            } else {
+                GameMode::Deathmatch
+            },
+            local_player: PlayerId(scores[0].0),
+            participants: scores
+                .iter()
+                .enumerate()
+                .map(|(palette, (id, name, score))| MatchParticipant {
+                    player_id: PlayerId(*id),
+                    name: (*name).into(),
+                    palette_id: palette as u8,
+                    score: *score,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn records_round_trip_with_awkward_names() {
+        let lgs = record(
+            "0b:00000002:00000004:00000120",
+            1_700_000_000,
+            &[
+                (0x1a, "50% Ghost", 1),
+                (0x2b, "A,B;C|D", 3),
+                (0x3c, "Dr. Boo=ok", 2),
+            ],
+        );
+        let duel = record(
+            "0c:00000001:00000002:00000090",
+            1_700_000_500,
+            &[(7, "Me", 3), (9, "You", 0)],
+        );
+        let mut history = MatchHistory::default();
+        assert!(history.record(lgs.clone()));
+        assert!(history.record(duel.clone()));
+        assert!(!history.record(duel.clone()));
+
+        let encoded = history.encode();
+        assert!(!encoded.contains('\t'));
+        let decoded = MatchHistory::decode(&encoded);
+        assert_eq!(decoded, history);
+        assert_eq!(decoded.records(), [duel, lgs.clone()]);
+
+        assert!(!lgs.local_won());
+        assert_eq!(lgs.local_placement(), 3);
+        assert_eq!(
+            lgs.winner().map(|winner| winner.name.as_str()),
+            Some("A,B;C|D")
+        );
+        assert_eq!(lgs.match_and_epoch(), ("0b", "00000002"));
+        assert!(decoded.records()[0].local_won());
+    }
+
+    #[test]
+    fn history_keeps_the_newest_and_drops_damaged_records() {
+        let mut history = MatchHistory::default();
+        for index in 0..MAX_MATCH_HISTORY as u64 + 5 {
+            history.record(record(
+                &format!("{index:x}:01"),
+                index,
+                &[(1, "A", 3), (2, "B", 1)],
+            ));
+        }
+        assert_eq!(history.records().len(), MAX_MATCH_HISTORY);
+        assert_eq!(
+            history.records()[0].finished_at,
+            MAX_MATCH_HISTORY as u64 + 4
+        );
+        assert_eq!(history.records().last().unwrap().finished_at, 5);
+
+        let kept = record("aa:01", 10, &[(1, "A", 3), (2, "B", 1)]).encode();
+        let damaged = [
+            "zz:01,10,duel,1,1.0.3.A|2.1.1.B",
+            "bb:01,10,solo,1,1.0.3.A|2.1.1.B",
+            "cc:01,10,duel,5,1.0.3.A|2.1.1.B",
+            "dd:01,10,duel,1,2.0.3.A|1.1.1.B",
+            "ee:01,10,duel,1,1.9.3.A|2.1.1.B",
+            "ff:01,10,duel,1,1.0.3.%4|2.1.1.B",
+            "",
+        ];
+        let decoded = MatchHistory::decode(&format!("{};{kept}", damaged.join(";")));
+        assert_eq!(decoded.records().len(), 1);
+        assert_eq!(decoded.records()[0].event_id, "aa:01");
+    }
+
+    #[test]
+    fn timestamps_format_as_utc_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13 UTC");
+    }
+}
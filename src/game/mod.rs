@@ -8,21 +8,34 @@ use bevy_ggrs::{GgrsAppExtension, GgrsPlugin, GgrsSchedule};
 use bevy_kira_audio::prelude::*;
 use bevy_roll_safe::prelude::*;
 
+mod achievements;
 mod assets;
+mod challenges;
 mod components;
+mod cosmetics;
+mod fingerprint;
 mod ggrs_framecount;
 mod gui;
 mod input;
 mod map;
+mod match_history;
 #[cfg(feature = "network_transition_test")]
 mod network_transition_test;
 mod networking;
 mod player;
 mod practice;
+mod profile_code;
+#[cfg(not(target_arch = "wasm32"))]
+mod profile_store;
 mod progression;
+mod rating;
+mod resume;
 mod rollback_audio;
+mod round_clock;
 pub(crate) mod session;
+mod session_log;
 mod toasts;
+mod tutorial;
 
 #[cfg(feature = "debug_render")]
 mod debug_render;
@@ -31,6 +44,7 @@ use assets::procedural::*;
 use assets::sounds::*;
 use assets::textures::*;
 use components::*;
+use cosmetics::*;
 use ggrs_framecount::*;
 use gui::*;
 use input::*;
@@ -39,9 +53,13 @@ use networking::*;
 use player::*;
 use practice::*;
 use progression::*;
+use resume::*;
 use rollback_audio::*;
+use round_clock::*;
 use session::{match_winner, PlayerScore, RoundBootstrap, RoundOutcome};
+use session_log::*;
 use toasts::*;
+use tutorial::*;
 
 use seeded_random::Random;
 use seeded_random::Seed;
@@ -56,6 +74,10 @@ pub enum GameState {
     MainMenu,
     Matchmaking,
     InGame,
+    /// A timed solo target run started from the menu, with no network session.
+    Practice,
+    /// The scripted tutorial in the practice arena, also without a session.
+    Tutorial,
 }
 
 #[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default, Reflect)]
@@ -88,6 +110,9 @@ pub struct Scores(Vec<PlayerScore>);
 pub struct Elimination {
     pub player_id: session::PlayerId,
     pub frame: u32,
+    /// Owner of the bullet that scored it. Traps, the closing void and a
+    /// ghost's own ricochet are uncredited.
+    pub eliminated_by: Option<session::PlayerId>,
 }
 
 #[derive(Resource, Reflect, Default, Debug, Clone, PartialEq, Eq)]
@@ -97,6 +122,9 @@ pub struct RoundProgress {
     pub disconnected: Vec<session::PlayerId>,
     pub resolved: Option<RoundOutcome>,
     pub resolved_frame: Option<u32>,
+    /// Frame `generate_map` started this round on; the round clock counts
+    /// from here. A resumed round backdates it by the frames already played.
+    pub started_frame: u32,
 }
 
 #[derive(Resource, Default)]
@@ -168,7 +196,8 @@ impl Scores {
 pub struct PendingPlayerProfile {
     pub name: String,
     pub palette_id: u8,
-    pub cosmetic_id: u8,
+    pub loadout: Loadout,
+    pub ratings: rating::LobbyRatings,
 }
 
 impl Default for PendingPlayerProfile {
@@ -176,7 +205,8 @@ impl Default for PendingPlayerProfile {
         Self {
             name: "Ghost".into(),
             palette_id: 0,
-            cosmetic_id: 0,
+            loadout: Loadout::default(),
+            ratings: rating::LobbyRatings::default(),
         }
     }
 }
@@ -241,6 +271,7 @@ pub fn run() {
         )
         .add_collection_to_loading_state::<_, ImageAssets>(GameState::AssetLoading)
         .add_collection_to_loading_state::<_, SoundAssets>(GameState::AssetLoading)
+        .add_collection_to_loading_state::<_, CosmeticAssets>(GameState::AssetLoading)
         .add_plugins((
             DefaultPlugins
                 .set(WindowPlugin {
@@ -338,6 +369,7 @@ pub fn run() {
             .register_rollback_resource::<GameSeed>()
             .register_rollback_resource::<SoundIdSeed>()
             .register_rollback_resource::<Map<CellType, MAP_SIZE, MAP_SIZE>>()
+            .register_rollback_resource::<WallDamage>()
             .register_rollback_resource::<RoundProgress>()
             .register_rollback_resource::<MatchFlow>()
             // .rollback_resource_with_copy::<FrameCount>()
@@ -346,11 +378,24 @@ pub fn run() {
             .register_rollback_component::<Transform>()
             .register_rollback_component::<Bullet>()
             .register_rollback_component::<BulletReady>()
+            .register_rollback_component::<Dash>()
             .register_rollback_component::<SpeedPickup>()
             .register_rollback_component::<SpeedBoost>()
             .register_rollback_component::<ShieldPickup>()
             .register_rollback_component::<ShieldCharges>()
+            .register_rollback_component::<HealthPickup>()
+            .register_rollback_component::<Health>()
+            .register_rollback_component::<HitInvulnerability>()
+            .register_rollback_component::<WeaponPickup>()
+            .register_rollback_component::<EquippedWeapon>()
+            .register_rollback_component::<PickupCharging>()
+            .register_rollback_component::<PhasePickup>()
+            .register_rollback_component::<Phasing>()
+            .register_rollback_component::<TeleportCooldown>()
+            .register_rollback_component::<Stocks>()
+            .register_rollback_component::<Respawning>()
             .register_rollback_component::<MoveDir>()
+            .register_rollback_component::<AimDir>()
             .register_rollback_component::<LookTowardsParentMove>()
             .register_rollback_component::<MarkedForDeath>()
             // for rollback audio
@@ -367,11 +412,21 @@ pub fn run() {
             .register_rollback_component::<TextureAtlasSprite>(),
     )
     .insert_resource(ClearColor(Color::BLACK))
+    .add_asset::<CosmeticCatalog>()
+    .init_asset_loader::<CosmeticCatalogLoader>()
     .init_resource::<AudioConfig>()
     .init_resource::<MatchmakingRoom>()
+    .init_resource::<session::MatchRules>()
+    .init_resource::<InputDelaySetting>()
     .init_resource::<EpochRollover>()
+    .init_resource::<ReconnectingPeers>()
+    .init_resource::<ResumeHistory>()
+    .init_resource::<RoundResume>()
+    .init_resource::<SessionLog>()
     .init_resource::<PendingPlayerProfile>()
     .init_resource::<CasualProfile>()
+    .init_resource::<ProfileTransfer>()
+    .init_resource::<TutorialStatusRequest>()
     .init_resource::<toasts::Toasts>()
     .init_resource::<RoundEndTimer>()
     .init_resource::<Scores>()
@@ -379,6 +434,7 @@ pub fn run() {
     .init_resource::<PlaybackStates>()
     .init_resource::<PresentedExplosions>()
     .init_resource::<Map<CellType, MAP_SIZE, MAP_SIZE>>()
+    .init_resource::<WallDamage>()
     .init_resource::<RoundProgress>()
     .init_resource::<ReportedOutcome>()
     .init_resource::<RematchFlow>()
@@ -391,11 +447,16 @@ pub fn run() {
         OnEnter(GameState::MainMenu),
         (reset_menu_to_main, start_main_music, stop_cloudflare_socket).chain(),
     )
+    // The catalog finishes loading with the other assets, before the menu.
+    .add_systems(OnEnter(GameState::MainMenu), refresh_cosmetic_unlocks)
     .add_systems(
         OnEnter(GameState::Matchmaking),
         (reset_practice_view, setup_practice).chain(),
     )
-    .add_systems(OnEnter(GameState::Matchmaking), start_cloudflare_socket)
+    .add_systems(
+        OnEnter(GameState::Matchmaking),
+        (begin_session_log, start_cloudflare_socket),
+    )
     // Queue practice remains active through coordinator assignment and the v3
     // handoff. `wait_for_players` changes state only after the exact v3 lobby
     // and all WebRTC channels are ready, so cleanup occurs at actual readiness.
@@ -403,6 +464,28 @@ pub fn run() {
         OnExit(GameState::Matchmaking),
         (cleanup_practice, apply_deferred, reset_practice_view).chain(),
     )
+    .add_systems(
+        OnEnter(GameState::Practice),
+        (reset_practice_view, setup_practice).chain(),
+    )
+    .add_systems(
+        OnExit(GameState::Practice),
+        (cleanup_practice, apply_deferred, reset_practice_view).chain(),
+    )
+    .add_systems(
+        OnEnter(GameState::Tutorial),
+        (reset_practice_view, setup_tutorial).chain(),
+    )
+    .add_systems(
+        OnExit(GameState::Tutorial),
+        (
+            finish_tutorial,
+            cleanup_practice,
+            apply_deferred,
+            reset_practice_view,
+        )
+            .chain(),
+    )
     .add_systems(
         OnExit(GameState::InGame),
         (
@@ -423,12 +506,18 @@ pub fn run() {
             .before(display_toasts)
             .before(update_main_menu)
             .before(update_settings_ui)
+            .before(update_profile_ui)
+            .before(update_history_ui)
+            .before(update_challenges_ui)
+            .before(update_practice_menu_ui)
+            .before(update_tutorial_prompt_ui)
             .before(update_direct_connect_ui)
             .before(update_in_game_controls_ui)
             .before(update_pause_ui)
             .before(update_match_status_ui)
             .before(update_matchmaking_ui)
             .before(update_practice_ui)
+            .before(update_tutorial_ui)
             .before(update_respawn_ui)
             .before(update_score_ui),
     )
@@ -443,7 +532,22 @@ pub fn run() {
             update_main_menu
                 .run_if(in_state(GameState::MainMenu))
                 .run_if(in_state(MenuState::Main)),
-            update_settings_ui.run_if(in_state(MenuState::Settings)),
+            (
+                update_settings_ui.run_if(in_state(MenuState::Settings)),
+                update_profile_ui
+                    .run_if(in_state(GameState::MainMenu).and_then(in_state(MenuState::Profile))),
+                update_history_ui
+                    .run_if(in_state(GameState::MainMenu).and_then(in_state(MenuState::History))),
+                update_challenges_ui.run_if(
+                    in_state(GameState::MainMenu).and_then(in_state(MenuState::Challenges)),
+                ),
+                update_practice_menu_ui
+                    .run_if(in_state(GameState::MainMenu).and_then(in_state(MenuState::Practice))),
+                update_tutorial_prompt_ui
+                    .after(update_main_menu)
+                    .run_if(in_state(GameState::MainMenu).and_then(in_state(MenuState::Main))),
+                update_tutorial_ui.run_if(in_state(GameState::Tutorial)),
+            ),
             update_direct_connect_ui
                 .run_if(in_state(GameState::MainMenu).and_then(in_state(MenuState::DirectConnect))),
             update_in_game_controls_ui
@@ -453,12 +557,18 @@ pub fn run() {
             update_match_status_ui
                 .run_if(in_state(GameState::InGame).and_then(in_state(MenuState::Main))),
             update_matchmaking_ui.run_if(in_state(GameState::Matchmaking)),
-            update_practice_ui.run_if(in_state(GameState::Matchmaking)),
-            update_respawn_ui
-                .run_if(in_state(GameState::InGame).and_then(in_state(RollbackState::RoundEnd))),
+            update_practice_ui
+                .run_if(in_state(GameState::Matchmaking).or_else(in_state(GameState::Practice))),
+            update_respawn_ui.run_if(in_state(GameState::InGame)),
             // Persist only local casual preferences; reconnect credentials stay
             // in the networking layer's sessionStorage.
-            sync_persistent_preferences,
+            (
+                apply_profile_import,
+                credit_practice_run,
+                save_tutorial_status,
+                sync_persistent_preferences,
+            )
+                .chain(),
             // audio volume update in response to ui
             update_volume,
         ),
@@ -470,14 +580,20 @@ pub fn run() {
     .add_systems(
         Update,
         (
+            tick_practice_run,
             update_practice_player,
             move_practice_targets,
             move_practice_shots,
             resolve_practice_hits,
             respawn_practice_targets,
+            advance_tutorial.run_if(in_state(GameState::Tutorial)),
         )
             .chain()
-            .run_if(in_state(GameState::Matchmaking)),
+            .run_if(
+                in_state(GameState::Matchmaking)
+                    .or_else(in_state(GameState::Practice))
+                    .or_else(in_state(GameState::Tutorial)),
+            ),
     )
     .add_systems(
         First,
@@ -491,13 +607,15 @@ pub fn run() {
                 .after(advance_ggrs_reset_barrier)
                 .run_if(in_state(GameState::Matchmaking)),
             report_confirmed_outcome.run_if(in_state(GameState::InGame)),
+            share_resume_snapshot.run_if(in_state(GameState::InGame)),
             award_confirmed_progression.run_if(in_state(GameState::InGame)),
             update_network_telemetry.run_if(in_state(GameState::InGame)),
             watch_lobby_epoch.run_if(in_state(GameState::InGame)),
             poll_lobby_control
                 .run_if(in_state(GameState::InGame).or_else(in_state(GameState::Matchmaking))),
             repair_presentation_components.run_if(in_state(GameState::InGame)),
-            apply_player_cosmetics
+            (apply_player_cosmetics, apply_bullet_tints)
+                .chain()
                 .after(repair_presentation_components)
                 .run_if(in_state(GameState::InGame)),
             reconcile_player_powerup_presentations
@@ -506,6 +624,19 @@ pub fn run() {
             animate_player_powerup_presentations
                 .after(reconcile_player_powerup_presentations)
                 .run_if(in_state(GameState::InGame)),
+            reconcile_health_bars
+                .after(reconcile_player_powerup_presentations)
+                .run_if(in_state(GameState::InGame)),
+            (
+                reconcile_cracked_walls,
+                reconcile_pickup_charging,
+                reconcile_closing_void,
+                reconcile_respawn_visibility,
+            )
+                .run_if(in_state(GameState::InGame)),
+            reconcile_phase_translucency
+                .after(apply_player_cosmetics)
+                .run_if(in_state(GameState::InGame)),
             player_look
                 .after(apply_player_cosmetics)
                 .run_if(in_state(GameState::InGame)),
@@ -519,6 +650,18 @@ pub fn run() {
                 .run_if(in_state(GameState::InGame)),
         ),
     )
+    // Support session log; entries carry roster handles and counters only.
+    .add_systems(
+        Update,
+        (
+            record_queue_status.run_if(in_state(GameState::Matchmaking)),
+            record_round_assignment,
+            record_epoch_rollover.after(watch_lobby_epoch),
+            record_telemetry_snapshot
+                .after(update_network_telemetry)
+                .run_if(in_state(GameState::InGame)),
+        ),
+    )
     .add_roll_state::<RollbackState>(GgrsSchedule)
     .add_systems(OnEnter(RollbackState::PreRound), (clear_map_sprites,))
     .add_systems(
@@ -537,12 +680,26 @@ pub fn run() {
     .add_systems(
         GgrsSchedule,
         (
-            tick_speed_boost,
+            (
+                tick_speed_boost,
+                tick_hit_invulnerability,
+                tick_weapons,
+                tick_pickup_respawns,
+                tick_phasing,
+                tick_respawns,
+            )
+                .chain(),
             move_players,
             reload_bullet,
-            collect_speed_pickups,
-            collect_shield_pickups,
-            trigger_traps,
+            (
+                collect_speed_pickups,
+                collect_shield_pickups,
+                collect_health_pickups,
+                collect_weapon_pickups,
+                collect_phase_pickups,
+            )
+                .chain(),
+            stock_hazards(),
             fire_bullets,
             move_bullets,
             kill_players,
@@ -550,7 +707,9 @@ pub fn run() {
             remove_finished_explosion_cues,
             apply_deferred,
             process_deaths,
+            expire_round,
             increase_frame_system,
+            record_resume_history,
         )
             .chain()
             .after(apply_state_transition::<RollbackState>)
@@ -691,6 +850,20 @@ fn setup(mut commands: Commands) {
     }
 }
 
+/// Hazards that cost a stock, each flushed before the next runs. Their
+/// `MarkedForDeath` inserts are deferred, so without the flushes a ghost on a
+/// trap that the closing ring also swallows would spend two stocks at once.
+fn stock_hazards() -> impl IntoSystemConfigs<()> {
+    (
+        trigger_traps,
+        apply_deferred,
+        trigger_teleporters,
+        close_arena,
+        apply_deferred,
+    )
+        .chain()
+}
+
 fn reset_menu_to_main(mut menu: ResMut<NextState<MenuState>>) {
     menu.set(MenuState::Main);
 }
@@ -734,12 +907,26 @@ mod tests {
         });
         schedule.add_systems(
             (
-                tick_speed_boost,
+                (
+                    tick_speed_boost,
+                    tick_hit_invulnerability,
+                    tick_weapons,
+                    tick_pickup_respawns,
+                    tick_phasing,
+                    tick_respawns,
+                )
+                    .chain(),
                 move_players,
                 reload_bullet,
-                collect_speed_pickups,
-                collect_shield_pickups,
-                trigger_traps,
+                (
+                    collect_speed_pickups,
+                    collect_shield_pickups,
+                    collect_health_pickups,
+                    collect_weapon_pickups,
+                    collect_phase_pickups,
+                )
+                    .chain(),
+                stock_hazards(),
                 fire_bullets,
                 move_bullets,
                 kill_players,
@@ -747,13 +934,56 @@ mod tests {
                 remove_finished_explosion_cues,
                 apply_deferred,
                 process_deaths,
+                expire_round,
                 increase_frame_system,
+                record_resume_history,
             )
                 .chain(),
         );
 
         assert!(schedule.initialize(&mut World::new()).is_ok());
     }
+
+    #[test]
+    fn a_ghost_spends_one_stock_when_a_trap_and_the_ring_share_a_frame() {
+        let mut map = Map::<CellType, MAP_SIZE, MAP_SIZE>::default();
+        map.cells[0][MAP_SIZE / 2] = CellType::Trap;
+        let mut world = World::new();
+        world.insert_resource(map);
+        world.insert_resource(GGFrameCount {
+            frame: ROUND_FRAMES - OVERTIME_FRAMES,
+        });
+        world.insert_resource(RoundProgress::default());
+        world.insert_resource(SoundIdSeed::new(0, 1));
+        world.insert_resource(SoundAssets {
+            laser_shoot: default(),
+            ray: default(),
+            swoosh_death: default(),
+            menu_music: default(),
+        });
+        let ghost = world
+            .spawn((
+                Player {
+                    handle: 0,
+                    player_id: session::PlayerId(1),
+                },
+                Transform::from_translation(grid_to_world((0, MAP_SIZE as u32 / 2)).extend(0.)),
+                Stocks(3),
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(stock_hazards());
+        schedule.run(&mut world);
+
+        assert_eq!(
+            world.resource::<Map<CellType, MAP_SIZE, MAP_SIZE>>().cells[0][0],
+            CellType::Void
+        );
+        assert_eq!(world.get::<Stocks>(ghost).unwrap().0, 2);
+        assert!(world.get::<Respawning>(ghost).is_some());
+        assert!(world.resource::<RoundProgress>().eliminated.is_empty());
+    }
 }
 
 fn repair_presentation_components(
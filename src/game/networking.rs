@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy_ggrs::{ggrs::PlayerType, *};
 use ggrs::GGRSEvent;
@@ -8,15 +10,26 @@ use crate::{
 };
 
 use super::{
+    cosmetics::Loadout,
+    fingerprint::{simulation_fingerprint, simulation_fingerprint_hex},
+    rating::LobbyRatings,
+    resume::{ResumeHistory, ResumeSnapshot, RoundResume},
     session::{
-        GameMode, MatchId, MatchPreference, PlayerId, PlayerProfile, PlayerScore, RosterEntry,
-        RoundBootstrap, RoundNumber, SessionEpoch,
+        check_simulation_fingerprints, verify_lobby_start, GameMode, MatchId, MatchPreference,
+        MatchRules, PlayerId, PlayerProfile, PlayerScore, RosterEntry, RoundBootstrap, RoundNumber,
+        SessionEpoch,
     },
+    session_log::{ggrs_event_entry, SessionEvent, SessionLog},
     toasts::Toasts,
     GameState, MAP_SIZE,
 };
 
 pub const ROLLBACK_FPS: usize = 60;
+/// Upper bound shared with the lobby service's RTT-derived input delay.
+pub const MAX_INPUT_DELAY: usize = 4;
+/// Matches the browser transport's reconnect window. GGRS keeps a silent peer
+/// predicted this long before reporting it disconnected.
+pub const RECONNECT_WINDOW_MS: u64 = 10_000;
 
 fn final_lobby_can_install(state: &ConnectionState, has_lobby_snapshot: bool) -> bool {
     *state == ConnectionState::Ready && has_lobby_snapshot
@@ -67,6 +80,51 @@ impl EpochRollover {
     }
 }
 
+/// Remote players whose inputs stopped arriving mid-round. The round stays
+/// paused on prediction until they resume or the reconnect window expires.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReconnectingPeers(pub Vec<PlayerId>);
+
+/// Settings override for the lobby-agreed input delay. GGRS delays only local
+/// inputs, so a manual value changes feel, never simulation agreement.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputDelaySetting {
+    #[default]
+    Auto,
+    Fixed(usize),
+}
+
+impl InputDelaySetting {
+    /// `auto` or a frame count, as saved in the profile and session log.
+    pub fn encode(self) -> String {
+        match self {
+            Self::Auto => "auto".into(),
+            Self::Fixed(frames) => frames.to_string(),
+        }
+    }
+
+    /// Anything but a frame count within `MAX_INPUT_DELAY` falls back to
+    /// `Auto`.
+    pub fn decode(value: &str) -> Self {
+        value
+            .parse::<usize>()
+            .ok()
+            .filter(|frames| *frames <= MAX_INPUT_DELAY)
+            .map_or(Self::Auto, Self::Fixed)
+    }
+}
+
+fn effective_input_delay(setting: InputDelaySetting, agreed: usize) -> usize {
+    if cfg!(feature = "no_delay") {
+        return 0;
+    }
+    match setting {
+        InputDelaySetting::Auto => agreed,
+        InputDelaySetting::Fixed(frames) => frames,
+    }
+    .min(MAX_INPUT_DELAY)
+}
+
 #[derive(Resource)]
 pub struct MatchmakingRoom {
     pub private_code: Option<String>,
@@ -75,6 +133,18 @@ pub struct MatchmakingRoom {
     /// Exact private-room mode/capacity; private rooms bypass protocol 4.
     pub private_mode: GameMode,
     pub private_capacity: u8,
+    pub private_rules: MatchRules,
+}
+
+impl MatchmakingRoom {
+    /// Rules for the match this room joins. Only private rooms carry options.
+    pub fn rules(&self) -> MatchRules {
+        if self.private_code.is_some() {
+            self.private_rules
+        } else {
+            MatchRules::default()
+        }
+    }
 }
 
 impl Default for MatchmakingRoom {
@@ -84,6 +154,7 @@ impl Default for MatchmakingRoom {
             preference: MatchPreference::Any,
             private_mode: GameMode::Duel,
             private_capacity: 2,
+            private_rules: MatchRules::default(),
         }
     }
 }
@@ -115,7 +186,7 @@ fn versioned_room_name(private_code: Option<&str>) -> String {
 }
 
 impl ggrs::Config for GgrsConfig {
-    type Input = u8;
+    type Input = u16;
     type State = u8;
     type Address = PlayerId;
 }
@@ -145,7 +216,10 @@ pub fn start_cloudflare_socket(
             room.preference.protocol_name(),
             &profile.name,
             profile.palette_id,
-            profile.cosmetic_id,
+            profile.loadout.legacy_cosmetic_id(),
+            &profile.loadout.encode(),
+            &profile.ratings.encode(),
+            &simulation_fingerprint_hex(),
         );
         return;
     }
@@ -154,14 +228,18 @@ pub fn start_cloudflare_socket(
     // flexible public queue.
     let room_name = versioned_room_name(room.private_code.as_deref());
     let (mode, capacity) = private_lobby_mode_capacity(&room);
+    let rules = room.private_rules.room_suffix();
     socket.connect_lobby(
         SIGNALING_URL,
-        &format!("v3-{room_name}-{mode}-{capacity}"),
+        &format!("v3-{room_name}-{mode}-{capacity}{rules}"),
         mode,
         capacity,
         &profile.name,
         profile.palette_id,
-        profile.cosmetic_id,
+        profile.loadout.legacy_cosmetic_id(),
+        &profile.loadout.encode(),
+        &profile.ratings.encode(),
+        &simulation_fingerprint_hex(),
     );
 }
 
@@ -222,6 +300,9 @@ pub fn cleanup_network_session(
         Or<(
             With<super::components::SpeedPickup>,
             With<super::components::ShieldPickup>,
+            With<super::components::HealthPickup>,
+            With<super::components::WeaponPickup>,
+            With<super::components::PhasePickup>,
         )>,
     >,
 ) {
@@ -291,12 +372,16 @@ fn advance_reset_barrier_state(rollover: &mut EpochRollover, delta_ms: u32) {
 
 pub fn advance_ggrs_reset_barrier(
     time: Res<Time>,
+    socket: Res<CloudflareSocket>,
     session: Option<Res<Session<GgrsConfig>>>,
     mut rollover: ResMut<EpochRollover>,
 ) {
     if !rollover.active() || !rollover.promoted || rollover.install_ready || session.is_some() {
         return;
     }
+    // Same-roster round rollovers keep their open peers through the barrier,
+    // which is the quietest moment to sample RTT for the next start.
+    socket.sample_peer_latency();
     let delta_ms = time.delta().as_millis().min(u32::MAX as u128) as u32;
     advance_reset_barrier_state(&mut rollover, delta_ms);
 }
@@ -305,6 +390,9 @@ pub fn wait_for_players(
     commands: Commands,
     rollover: Option<Res<EpochRollover>>,
     socket: ResMut<CloudflareSocket>,
+    delay: Res<InputDelaySetting>,
+    room: Res<MatchmakingRoom>,
+    mut log: ResMut<SessionLog>,
     mut next_state: ResMut<NextState<GameState>>,
     mut toasts: ResMut<Toasts>,
 ) {
@@ -318,16 +406,21 @@ pub fn wait_for_players(
     // atomically leave Matchmaking/practice.
     let lobby = socket.lobby_match_info();
     if final_lobby_can_install(&state, lobby.is_some()) {
+        // Freshly opened peers are measured once before GGRS owns them.
+        socket.sample_peer_latency();
         return start_lobby_session(
             commands,
             socket,
             next_state,
             toasts,
             lobby.expect("final readiness requires immutable lobby snapshot"),
+            *delay,
+            room.rules(),
         );
     }
     match state {
         ConnectionState::Failed(error) => {
+            log.record(SessionEvent::TransportFailed { stalled: false });
             toasts.error(error.into());
             next_state.set(GameState::MainMenu);
         }
@@ -393,6 +486,34 @@ mod tests {
         assert_eq!(private_lobby_mode_capacity(&duel), (0, 2));
     }
 
+    #[test]
+    fn optional_rules_only_apply_to_private_rooms() {
+        let health = MatchRules {
+            multi_hit_health: true,
+            ..Default::default()
+        };
+        let public = MatchmakingRoom {
+            private_rules: health,
+            ..Default::default()
+        };
+        assert_eq!(public.rules(), MatchRules::default());
+
+        let private = MatchmakingRoom {
+            private_code: Some("ROOM".into()),
+            private_rules: health,
+            ..Default::default()
+        };
+        assert_eq!(private.rules(), health);
+        // Different rules must never share a private lobby.
+        assert_eq!(MatchRules::default().room_suffix(), "");
+        assert_eq!(health.room_suffix(), "-hp");
+        let stocks = MatchRules {
+            stock_lives: true,
+            ..health
+        };
+        assert_eq!(stocks.room_suffix(), "-hp-stock");
+    }
+
     #[test]
     fn two_phase_rollover_state_is_explicit_and_idempotent() {
         let mut rollover = EpochRollover::default();
@@ -443,6 +564,22 @@ mod tests {
         assert!(!watcher.contains("remove_resource::<Session"));
     }
 
+    #[cfg(not(feature = "no_delay"))]
+    #[test]
+    fn input_delay_override_is_local_and_bounded() {
+        assert_eq!(effective_input_delay(InputDelaySetting::Auto, 0), 0);
+        assert_eq!(effective_input_delay(InputDelaySetting::Auto, 3), 3);
+        assert_eq!(
+            effective_input_delay(InputDelaySetting::Auto, 99),
+            MAX_INPUT_DELAY
+        );
+        assert_eq!(effective_input_delay(InputDelaySetting::Fixed(1), 4), 1);
+        assert_eq!(
+            effective_input_delay(InputDelaySetting::Fixed(9), 0),
+            MAX_INPUT_DELAY
+        );
+    }
+
     #[test]
     fn private_room_codes_are_canonical_and_bounded() {
         assert_eq!(sanitize_room_code(" ab-c_12! "), "ABC12");
@@ -461,6 +598,8 @@ fn start_lobby_session(
     mut next_state: ResMut<NextState<GameState>>,
     mut toasts: ResMut<Toasts>,
     info: crate::cloudflare_net::LobbyMatchInfo,
+    delay: InputDelaySetting,
+    rules: MatchRules,
 ) {
     let valid = (info.mode == 0 && info.roster.len() == 2)
         || (info.mode == 1 && (3..=super::session::MAX_LOBBY_PLAYERS).contains(&info.roster.len()));
@@ -476,6 +615,24 @@ fn start_lobby_session(
         next_state.set(GameState::MainMenu);
         return;
     };
+    // Nothing from the JS layer is trusted until the lobby service's signature
+    // over the exact start has been checked against the compiled-in key.
+    if let Err(error) = verify_lobby_start(&info.canonical_start(), &info.signature) {
+        warn!("rejecting lobby assignment: {error:?}");
+        toasts.error("Lobby assignment failed signature verification.".into());
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+    // Mismatched gameplay code would desync on the first divergent frame;
+    // refuse up front with an actionable message instead.
+    if check_simulation_fingerprints(simulation_fingerprint(), &info.simulations).is_err() {
+        socket.leave_lobby(false);
+        toasts.error(
+            "Another player is running a different game version. Please refresh to update.".into(),
+        );
+        next_state.set(GameState::MainMenu);
+        return;
+    }
     let roster: Vec<_> = info
         .roster
         .iter()
@@ -490,7 +647,22 @@ fn start_lobby_session(
             player_id: entry.player_id,
             name: format!("Player {}", entry.handle + 1),
             palette_id: entry.handle as u8,
-            cosmetic_id: 0,
+            // Cosmetics are presentation-only; an unknown or missing loadout
+            // simply shows the classic ghost.
+            loadout: info
+                .cosmetics
+                .iter()
+                .find(|(player_id, _)| *player_id == entry.player_id)
+                .and_then(|(_, loadout)| Loadout::decode(loadout))
+                .unwrap_or_default(),
+            // Ratings are self-reported and only shown or used to move the
+            // local rating; a client that predates them is simply unrated.
+            rating: info
+                .ratings
+                .iter()
+                .find(|(player_id, _)| *player_id == entry.player_id)
+                .and_then(|(_, ratings)| LobbyRatings::decode(ratings))
+                .map(|ratings| ratings.get(mode)),
         })
         .collect();
     if info.scores.len() != roster.len()
@@ -540,14 +712,32 @@ fn start_lobby_session(
         next_state.set(GameState::MainMenu);
         return;
     };
+    let roster_ids: Vec<_> = bootstrap
+        .roster
+        .iter()
+        .map(|entry| entry.player_id)
+        .collect();
+    // Every peer decodes the same start text against the same roster, so an
+    // unusable snapshot falls back to a fresh round everywhere alike.
+    let resume = ResumeSnapshot::decode(&info.resume, &roster_ids, rules, mode);
+    if !info.resume.is_empty() && resume.is_none() {
+        warn!("ignoring invalid resume snapshot; starting a fresh round");
+    }
+    let input_delay = effective_input_delay(delay, info.input_delay);
+    info!(
+        "starting epoch {} round {} with {input_delay} frame(s) of input delay (lobby agreed {})",
+        info.epoch, info.round, info.input_delay
+    );
     let mut builder = ggrs::SessionBuilder::<GgrsConfig>::new()
         .with_fps(ROLLBACK_FPS)
         .unwrap()
         .with_num_players(bootstrap.roster.len())
-        .with_input_delay(if cfg!(feature = "no_delay") { 0 } else { 2 })
+        .with_input_delay(input_delay)
         .with_max_prediction_window(40)
         .with_max_frames_behind(42)
-        .unwrap();
+        .unwrap()
+        .with_disconnect_timeout(Duration::from_millis(RECONNECT_WINDOW_MS))
+        .with_disconnect_notify_delay(Duration::from_millis(500));
     for entry in &bootstrap.roster {
         let player_type = if entry.player_id == info.local_player {
             PlayerType::Local
@@ -580,9 +770,13 @@ fn start_lobby_session(
     commands.insert_resource(super::RoundProgress::default());
     commands.insert_resource(super::ReportedOutcome::default());
     commands.insert_resource(bootstrap);
+    commands.insert_resource(rules);
     commands.insert_resource(Session::P2P(session));
     commands.insert_resource(GameSeed(info.seed));
     commands.insert_resource(EpochRollover::default());
+    commands.insert_resource(ReconnectingPeers::default());
+    commands.insert_resource(ResumeHistory::default());
+    commands.insert_resource(RoundResume(resume));
     next_state.set(GameState::InGame);
 }
 
@@ -696,6 +890,7 @@ pub fn detect_transport_failure(
     socket: Res<CloudflareSocket>,
     rollover: Res<EpochRollover>,
     session: Option<Res<Session<GgrsConfig>>>,
+    mut log: ResMut<SessionLog>,
     mut next_state: ResMut<NextState<GameState>>,
     mut toasts: ResMut<Toasts>,
 ) {
@@ -705,11 +900,13 @@ pub fn detect_transport_failure(
     match socket.state() {
         ConnectionState::Failed(error) => {
             commands.remove_resource::<Session<GgrsConfig>>();
+            log.record(SessionEvent::TransportFailed { stalled: false });
             toasts.error(error.into());
             next_state.set(GameState::MainMenu);
         }
         _ if socket.transport_stalled() => {
             commands.remove_resource::<Session<GgrsConfig>>();
+            log.record(SessionEvent::TransportFailed { stalled: true });
             socket.leave_lobby(false);
             toasts.error("Peer connection stopped receiving packets.".into());
             next_state.set(GameState::MainMenu);
@@ -730,19 +927,23 @@ pub fn log_ggrs_events(
     socket: Res<CloudflareSocket>,
     rollover: Res<EpochRollover>,
     bootstrap: Option<Res<RoundBootstrap>>,
+    mut reconnecting: ResMut<ReconnectingPeers>,
+    mut log: ResMut<SessionLog>,
     mut toasts: ResMut<Toasts>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     if let Session::P2P(session) = session.as_mut() {
         for event in session.events() {
+            log.record(ggrs_event_entry(&event, bootstrap.as_deref()));
             match event {
                 GGRSEvent::Disconnected { addr } => {
                     if rollover.active() {
                         info!("ignoring expected old-round disconnect during epoch rollover: {addr:?}");
                         continue;
                     }
-                    // Outside a deliberate rollover, a disconnect is fatal for
-                    // the whole immutable roster.
+                    // Outside a deliberate rollover, a disconnect means the
+                    // reconnect window expired and is fatal for the whole
+                    // immutable roster.
                     socket.leave_lobby(false);
                     let size = bootstrap.as_ref().map(|b| b.roster.len()).unwrap_or(2);
                     toasts.error(format!("Peer {addr:?} disconnected; returning all {size} roster players to menu.").into());
@@ -753,11 +954,16 @@ pub fn log_ggrs_events(
                         info!("ignoring expected old-round interruption during epoch rollover: {addr:?}");
                         continue;
                     }
-                    socket.leave_lobby(false);
-                    toasts.error(
-                        format!("Peer {addr:?} connection interrupted; returning to menu.").into(),
-                    );
-                    next_state.set(GameState::MainMenu);
+                    // The browser transport renegotiates in the background;
+                    // GGRS resumes the same round if inputs return in time.
+                    info!("peer {addr:?} interrupted; waiting for reconnect");
+                    if !reconnecting.0.contains(&addr) {
+                        reconnecting.0.push(addr);
+                    }
+                }
+                GGRSEvent::NetworkResumed { addr } => {
+                    info!("peer {addr:?} resumed");
+                    reconnecting.0.retain(|player_id| *player_id != addr);
                 }
                 event => info!("GGRS Event: {event:?}"),
             }
@@ -6,12 +6,14 @@ use bevy_ggrs::{AddRollbackCommandExtension, PlayerInputs};
 
 use super::input;
 use super::networking::GgrsConfig;
-use super::session::{round_outcome, PlayerId, RoundBootstrap, RoundOutcome};
+use super::session::{round_outcome, GameMode, MatchRules, PlayerId, RoundBootstrap, RoundOutcome};
 use super::{
-    assets::{sounds::SoundAssets, textures::ImageAssets},
+    assets::{procedural::weapon_pickup_color, sounds::SoundAssets, textures::ImageAssets},
     components::*,
+    cosmetics::{CosmeticAssets, CosmeticCatalog, CosmeticSlot},
     ggrs_framecount::GGFrameCount,
-    map::{splitmix64, CellType, Map},
+    map::{damage_wall, splitmix64, teleporter_partner, CellType, Map, WallDamage},
+    resume::RoundResume,
     rollback_audio::RollbackSound,
     Elimination, GameSeed, RollbackState, RoundProgress, Scores, SoundIdSeed, MAP_SIZE,
 };
@@ -19,25 +21,43 @@ use super::{
 pub fn move_players(
     inputs: Res<PlayerInputs<GgrsConfig>>,
     mut players: Query<
-        (&mut Transform, &mut MoveDir, &Player, Option<&SpeedBoost>),
+        (
+            &mut Transform,
+            &mut MoveDir,
+            &mut AimDir,
+            &mut Dash,
+            &Player,
+            Option<&SpeedBoost>,
+            Option<&Phasing>,
+        ),
         Without<MarkedForDeath>,
     >,
     map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
 ) {
-    for (mut transform, mut move_dir, player, speed_boost) in &mut players {
+    for (mut transform, mut move_dir, mut aim_dir, mut dash, player, speed_boost, phasing) in
+        &mut players
+    {
         let (input, _) = inputs[player.handle];
         let direction = input::direction(input);
+        let dashing = tick_dash(&mut dash, input::dash(input));
+        aim_dir.0 = input::aim(input).unwrap_or(Vec2::ZERO);
 
-        if direction == Vec2::ZERO {
+        if direction != Vec2::ZERO {
+            move_dir.0 = direction;
+        } else if !dashing {
             continue;
         }
 
-        move_dir.0 = direction;
-
-        let move_speed = movement_speed(speed_boost.is_some());
+        // A dash without a held direction bursts the way the ghost is facing.
+        let (direction, move_speed) = if dashing {
+            (move_dir.0, DASH_SPEED)
+        } else {
+            (direction, movement_speed(speed_boost.is_some()))
+        };
         let old_pos = transform.translation.xy();
-        let requested_delta = direction * move_speed;
-        let move_delta = resolve_player_movement(&map_data, old_pos, requested_delta);
+        let requested_delta = direction * move_speed * terrain_speed_scale(&map_data, old_pos);
+        let move_delta =
+            resolve_player_movement(&map_data, old_pos, requested_delta, phasing.is_some());
         let limit = Vec2::splat(map_data.active_size as f32 / 2. - 0.5);
         let new_pos = (old_pos + move_delta).clamp(-limit, limit);
 
@@ -46,9 +66,21 @@ pub fn move_players(
     }
 }
 
-const BASE_MOVE_SPEED: f32 = 0.13;
-const BOOSTED_MOVE_SPEED: f32 = 0.1755;
-const SPEED_BOOST_FRAMES: u16 = 300;
+pub(super) const BASE_MOVE_SPEED: f32 = 0.13;
+pub(super) const BOOSTED_MOVE_SPEED: f32 = 0.1755;
+pub(super) const SPEED_BOOST_FRAMES: u16 = 300;
+
+/// Movement multiplier while standing in ectoplasm. Dashes are slowed too.
+pub(super) const SLOW_ZONE_SCALE: f32 = 0.5;
+
+fn terrain_speed_scale(map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>, position: Vec2) -> f32 {
+    match world_to_grid(position) {
+        Some((x, y)) if map_data.cells[x as usize][y as usize] == CellType::SlowZone => {
+            SLOW_ZONE_SCALE
+        }
+        _ => 1.0,
+    }
+}
 
 fn movement_speed(boosted: bool) -> f32 {
     if boosted {
@@ -65,21 +97,41 @@ fn tick_boost_frames(frames_left: u16) -> Option<u16> {
     }
 }
 
-const PLAYER_COLLIDER_HALF_SIZE: f32 = 0.4;
-const WALL_COLLIDER_HALF_SIZE: f32 = 0.5;
+/// Dash burst speed per frame. It stays below the player/wall collision
+/// distance so a burst can never tunnel through a single wall block.
+pub(super) const DASH_SPEED: f32 = 0.45;
+pub(super) const DASH_FRAMES: u8 = 6;
+/// Counted from the first burst frame, so a held dash repeats every 45 frames.
+pub(super) const DASH_COOLDOWN_FRAMES: u8 = 45;
+
+/// Advances the dash by one frame and reports whether this frame is a burst
+/// frame. Burst frames are also the bullet immunity window.
+fn tick_dash(dash: &mut Dash, requested: bool) -> bool {
+    dash.frames_left = dash.frames_left.saturating_sub(1);
+    dash.cooldown = dash.cooldown.saturating_sub(1);
+    if requested && dash.frames_left == 0 && dash.cooldown == 0 {
+        dash.frames_left = DASH_FRAMES;
+        dash.cooldown = DASH_COOLDOWN_FRAMES;
+    }
+    dash.frames_left > 0
+}
+
+pub(super) const PLAYER_COLLIDER_HALF_SIZE: f32 = 0.4;
+pub(super) const WALL_COLLIDER_HALF_SIZE: f32 = 0.5;
 
 fn resolve_player_movement(
     map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>,
     old_pos: Vec2,
     requested_delta: Vec2,
+    phasing: bool,
 ) -> Vec2 {
-    if !player_hits_wall(map_data, old_pos + requested_delta) {
+    if !player_blocked(map_data, old_pos + requested_delta, phasing) {
         return requested_delta;
     }
     let horizontal = Vec2::new(requested_delta.x, 0.0);
     let vertical = Vec2::new(0.0, requested_delta.y);
-    let horizontal_clear = !player_hits_wall(map_data, old_pos + horizontal);
-    let vertical_clear = !player_hits_wall(map_data, old_pos + vertical);
+    let horizontal_clear = !player_blocked(map_data, old_pos + horizontal, phasing);
+    let vertical_clear = !player_blocked(map_data, old_pos + vertical, phasing);
     match (horizontal_clear, vertical_clear) {
         (true, false) => horizontal,
         (false, true) => vertical,
@@ -95,6 +147,14 @@ fn nearby_cell_range(position: f32) -> std::ops::RangeInclusive<i32> {
 }
 
 fn player_hits_wall(map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>, player_pos: Vec2) -> bool {
+    player_blocked(map_data, player_pos, false)
+}
+
+fn player_blocked(
+    map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>,
+    player_pos: Vec2,
+    phasing: bool,
+) -> bool {
     // A unit wall can overlap the player only in the cell containing its centre
     // or one of the eight adjacent cells. Avoid scanning the whole arena for
     // every movement axis while retaining the exact AABB check at boundaries.
@@ -103,10 +163,8 @@ fn player_hits_wall(map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>, player_pos: Ve
             if x < 0 || y < 0 || x >= MAP_SIZE as i32 || y >= MAP_SIZE as i32 {
                 continue;
             }
-            if matches!(
-                map_data.cells[x as usize][y as usize],
-                CellType::WallBlock | CellType::Void
-            ) && wall_check(player_pos, grid_to_world((x as u32, y as u32)))
+            if map_data.cells[x as usize][y as usize].blocks_ghost(phasing)
+                && wall_check(player_pos, grid_to_world((x as u32, y as u32)))
             {
                 return true;
             }
@@ -262,6 +320,19 @@ pub fn reconcile_player_powerup_presentations(
     }
 }
 
+/// Phasing ghosts are drawn see-through. The body sprite is rollback state,
+/// so this reapplies the alpha after every restore.
+pub fn reconcile_phase_translucency(
+    mut players: Query<(&mut Sprite, Option<&Phasing>), With<Player>>,
+) {
+    for (mut sprite, phasing) in &mut players {
+        let alpha = if phasing.is_some() { 0.4 } else { 1.0 };
+        if sprite.color.a() != alpha {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
+
 pub fn clear_player_powerup_presentations(
     mut commands: Commands,
     visuals: Query<
@@ -270,6 +341,7 @@ pub fn clear_player_powerup_presentations(
             With<ShieldBubble>,
             With<SpeedTrailEmitter>,
             With<SpeedTrailParticle>,
+            With<HealthBar>,
         )>,
     >,
 ) {
@@ -278,11 +350,92 @@ pub fn clear_player_powerup_presentations(
     }
 }
 
+const HEALTH_PIP_SPACING: f32 = 0.26;
+
+fn health_pip_color(filled: bool, in_grace: bool) -> Color {
+    match (filled, in_grace) {
+        (true, false) => Color::rgb(0.35, 0.95, 0.40),
+        (true, true) => Color::rgb(0.85, 1.0, 0.85),
+        (false, _) => Color::rgba(0.12, 0.12, 0.14, 0.8),
+    }
+}
+
+fn spawn_health_bar(commands: &mut Commands, owner: Entity) {
+    let bar = commands
+        .spawn((
+            HealthBar { owner },
+            SpatialBundle::from_transform(Transform::from_translation(Vec3::new(0.0, -0.62, 2.0))),
+            Name::new("presentation: health bar"),
+        ))
+        .with_children(|bar| {
+            for index in 0..MAX_HEALTH {
+                let offset = index as f32 - (MAX_HEALTH - 1) as f32 / 2.0;
+                bar.spawn(SpriteBundle {
+                    transform: Transform::from_xyz(offset * HEALTH_PIP_SPACING, 0.0, 0.0),
+                    sprite: Sprite {
+                        color: health_pip_color(true, false),
+                        custom_size: Some(Vec2::new(0.2, 0.08)),
+                        ..default()
+                    },
+                    ..default()
+                });
+            }
+        })
+        .id();
+    commands.entity(owner).add_child(bar);
+}
+
+/// Hangs a pip row under every living ghost with `Health`. Like the power-up
+/// visuals, bars are rebuilt from rollback state rather than snapshotted.
+pub fn reconcile_health_bars(
+    mut commands: Commands,
+    players: Query<
+        (Entity, &Health, Option<&HitInvulnerability>),
+        (With<Player>, Without<MarkedForDeath>),
+    >,
+    bars: Query<(Entity, &HealthBar, Option<&Children>)>,
+    mut pips: Query<&mut Sprite, Without<Player>>,
+) {
+    let mut existing = HashSet::new();
+    for (entity, bar, children) in &bars {
+        let Ok((_, health, grace)) = players.get(bar.owner) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        if !existing.insert(bar.owner) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        for (index, &pip) in children
+            .into_iter()
+            .flat_map(|children| children.iter())
+            .enumerate()
+        {
+            if let Ok(mut sprite) = pips.get_mut(pip) {
+                sprite.color = health_pip_color(index < health.0 as usize, grace.is_some());
+            }
+        }
+    }
+    for (owner, _, _) in &players {
+        if !existing.contains(&owner) {
+            spawn_health_bar(&mut commands, owner);
+        }
+    }
+}
+
 pub fn animate_player_powerup_presentations(
     mut commands: Commands,
     time: Res<Time>,
     images: Res<ImageAssets>,
-    players: Query<(&Transform, &MoveDir, Option<&SpeedBoost>), With<Player>>,
+    players: Query<
+        (
+            &Transform,
+            &MoveDir,
+            Option<&SpeedBoost>,
+            Option<&CosmeticTints>,
+        ),
+        With<Player>,
+    >,
     mut emitters: Query<&mut SpeedTrailEmitter>,
     mut particles: Query<
         (Entity, &mut SpeedTrailParticle, &mut Transform, &mut Sprite),
@@ -290,7 +443,7 @@ pub fn animate_player_powerup_presentations(
     >,
 ) {
     for mut emitter in &mut emitters {
-        let Ok((transform, direction, boost)) = players.get(emitter.owner) else {
+        let Ok((transform, direction, boost, tints)) = players.get(emitter.owner) else {
             continue;
         };
         if boost.is_none() {
@@ -317,7 +470,10 @@ pub fn animate_player_powerup_presentations(
                     texture: images.ghost.clone(),
                     transform: Transform::from_translation(origin.extend(99.0)),
                     sprite: Sprite {
-                        color: Color::rgba(0.15, 0.90, 1.0, 0.42),
+                        color: tints
+                            .and_then(|tints| tints.trail)
+                            .unwrap_or(Color::rgb(0.15, 0.90, 1.0))
+                            .with_a(0.42),
                         custom_size: Some(Vec2::splat(0.76)),
                         ..default()
                     },
@@ -365,13 +521,14 @@ pub fn animate_player_powerup_presentations(
 }
 
 pub fn player_look(
-    players: Query<(&MoveDir, &Children), With<Player>>,
+    players: Query<(&MoveDir, Option<&AimDir>, &Children), With<Player>>,
     mut eyes_sprite: Query<&mut TextureAtlasSprite, With<LookTowardsParentMove>>,
 ) {
-    for (move_dir, children) in players.iter() {
+    for (move_dir, aim_dir, children) in players.iter() {
+        let look = aim_dir.copied().unwrap_or_default().or(*move_dir);
         for &child in children.iter() {
             if let Ok(eyes) = &mut eyes_sprite.get_mut(child) {
-                eyes.index = get_directional_sprite(move_dir.0);
+                eyes.index = get_directional_sprite(look);
             }
         }
     }
@@ -411,6 +568,10 @@ pub fn spawn_players(
     mut seed: ResMut<GameSeed>,
     map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
     bootstrap: Res<RoundBootstrap>,
+    rules: Res<MatchRules>,
+    resume: Res<RoundResume>,
+    frame: Res<GGFrameCount>,
+    mut progress: ResMut<RoundProgress>,
     players: Query<Entity, With<Player>>,
     bullets: Query<Entity, With<Bullet>>,
 ) {
@@ -448,6 +609,78 @@ pub fn spawn_players(
             .iter()
             .find(|profile| profile.player_id == entry.player_id)
             .expect("validated profile exists");
+        let color = COLORS[(profile.palette_id as usize + entry.handle / 4 * 4) % COLORS.len()];
+        // A resumed round continues from the survivor's confirmed snapshot.
+        // Generated spawns above still advance the seed identically.
+        if let Some(snapshot) = resume.at_round_start(&frame) {
+            let Some(state) = snapshot.player(entry.player_id) else {
+                progress.record_elimination(Elimination {
+                    player_id: entry.player_id,
+                    frame: 0,
+                    eliminated_by: None,
+                });
+                continue;
+            };
+            // A ghost resumed inside a wall keeps phasing out of it; one that
+            // is not phasing is pushed to the nearest empty cell instead.
+            let stuck = state.phase_frames == 0 && player_hits_wall(&map_data, state.position);
+            let position = if stuck {
+                nearest_open_position(&map_data, state.position)
+            } else {
+                state.position
+            };
+            let player = spawn_player(
+                &mut commands,
+                &images,
+                entry.handle,
+                entry.player_id,
+                state.move_dir,
+                position.extend(100.),
+                color,
+                &profile.name,
+            );
+            let mut player = commands.entity(player);
+            player.insert(BulletReady(state.bullet_ready));
+            if rules.multi_hit_health {
+                player.insert(Health(state.health));
+            }
+            if state.invulnerable_frames > 0 {
+                player.insert(HitInvulnerability {
+                    frames_left: state.invulnerable_frames,
+                });
+            }
+            if starting_stocks(*rules, bootstrap.mode).is_some() {
+                player.insert(Stocks(state.stocks));
+            }
+            if state.respawn_frames > 0 {
+                player.insert((
+                    Respawning {
+                        frames_left: state.respawn_frames,
+                    },
+                    MarkedForDeath::at(frame.frame),
+                ));
+            }
+            if state.shield_charges > 0 {
+                player.insert(ShieldCharges(state.shield_charges));
+            }
+            if state.speed_frames > 0 {
+                player.insert(SpeedBoost {
+                    frames_left: state.speed_frames,
+                });
+            }
+            if state.weapon_frames > 0 {
+                player.insert(EquippedWeapon {
+                    weapon: state.weapon,
+                    frames_left: state.weapon_frames,
+                });
+            }
+            if state.phase_frames > 0 {
+                player.insert(Phasing {
+                    frames_left: state.phase_frames,
+                });
+            }
+            continue;
+        }
         let position = positions[entry.handle];
         let world = grid_to_world(position);
         let look = if bootstrap.roster.len() == 2 {
@@ -455,51 +688,81 @@ pub fn spawn_players(
         } else {
             (-world).normalize_or_zero()
         };
-        spawn_player(
+        let player = spawn_player(
             &mut commands,
             &images,
             entry.handle,
             entry.player_id,
             if look == Vec2::ZERO { Vec2::X } else { look },
             world.extend(100.),
-            COLORS[(profile.palette_id as usize + entry.handle / 4 * 4) % COLORS.len()],
-            profile.cosmetic_id,
+            color,
             &profile.name,
         );
+        if rules.multi_hit_health {
+            commands.entity(player).insert(Health(MAX_HEALTH));
+        }
+        if let Some(stocks) = starting_stocks(*rules, bootstrap.mode) {
+            commands.entity(player).insert(Stocks(stocks));
+        }
     }
 }
 
 /// Cosmetic selection is presentation-only. Reapplying it outside the
 /// rollback schedule fixes restored Sprite image handles for both the local
 /// ghost and every remote ghost without changing synchronized gameplay state.
+/// Items missing from this build's catalog fall back to the classic look.
 pub fn apply_player_cosmetics(
+    mut commands: Commands,
     images: Res<ImageAssets>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
     bootstrap: Option<Res<RoundBootstrap>>,
-    mut players: Query<(&Player, &mut Handle<Image>)>,
+    mut players: Query<(Entity, &Player, &mut Handle<Image>, Option<&CosmeticTints>)>,
 ) {
-    let Some(bootstrap) = bootstrap else {
+    let (Some(bootstrap), Some(catalog)) = (bootstrap, cosmetics.get(&catalogs)) else {
         return;
     };
-    for (player, mut image) in &mut players {
-        let cosmetic_id = bootstrap
+    for (entity, player, mut image, tints) in &mut players {
+        let loadout = bootstrap
             .profiles
             .iter()
             .find(|profile| profile.player_id == player.player_id)
-            .map(|profile| profile.cosmetic_id)
-            .unwrap_or(0);
-        let expected = cosmetic_image(&images, cosmetic_id);
+            .map(|profile| profile.loadout)
+            .unwrap_or_default();
+        let expected = catalog
+            .texture(loadout.hat)
+            .unwrap_or_else(|| images.ghost.clone());
         if *image != expected {
             *image = expected;
         }
+        let expected = CosmeticTints {
+            trail: catalog.tint(CosmeticSlot::Trail, loadout.trail),
+            bullet: catalog.tint(CosmeticSlot::BulletTint, loadout.bullet),
+        };
+        if tints != Some(&expected) {
+            commands.entity(entity).insert(expected);
+        }
     }
 }
 
-fn cosmetic_image(images: &ImageAssets, cosmetic_id: u8) -> Handle<Image> {
-    match cosmetic_id {
-        1 => images.ghost_crown.clone(),
-        2 => images.ghost_wizard.clone(),
-        3 => images.ghost_bow.clone(),
-        _ => images.ghost.clone(),
+/// Bullet tints recolor only the default Blaster shot so weapon pickups stay
+/// readable. Bullet sprites are rollback state, so restores are recolored too.
+pub fn apply_bullet_tints(
+    owners: Query<(&Player, &CosmeticTints)>,
+    mut bullets: Query<(&Bullet, &mut Sprite)>,
+) {
+    for (bullet, mut sprite) in &mut bullets {
+        if bullet.weapon != Weapon::Blaster {
+            continue;
+        }
+        let expected = owners
+            .iter()
+            .find(|(player, _)| player.player_id == bullet.owner)
+            .and_then(|(_, tints)| tints.bullet)
+            .unwrap_or_else(|| weapon_pickup_color(Weapon::Blaster));
+        if sprite.color != expected {
+            sprite.color = expected;
+        }
     }
 }
 
@@ -511,16 +774,17 @@ fn spawn_player(
     move_dir: Vec2,
     translation: Vec3,
     color: Color,
-    cosmetic_id: u8,
     display_name: &str,
-) {
+) -> Entity {
     let parent = commands
         .spawn((
             Player { handle, player_id },
             BulletReady(0),
+            Dash::default(),
             MoveDir(move_dir),
+            AimDir::default(),
             SpriteBundle {
-                texture: cosmetic_image(images, cosmetic_id),
+                texture: images.ghost.clone(),
                 transform: Transform::from_translation(translation),
                 sprite: Sprite {
                     color,
@@ -552,6 +816,7 @@ fn spawn_player(
         .id();
 
     commands.entity(parent).push_children(&[child]);
+    parent
 }
 
 // takes in a grid position from 0 to map_size and outputs a world coordinate
@@ -702,7 +967,7 @@ mod spawn_tests {
         assert!(!player_hits_wall(&map, grid_to_world((2, 2))));
 
         let old = wall + Vec2::new(-1.0, 0.2);
-        let resolved = resolve_player_movement(&map, old, Vec2::new(0.2, 0.1));
+        let resolved = resolve_player_movement(&map, old, Vec2::new(0.2, 0.1), false);
         assert_eq!(resolved.x, 0.0);
         assert_eq!(resolved.y, 0.1);
     }
@@ -749,6 +1014,157 @@ mod spawn_tests {
         assert_eq!(replay.1.to_bits(), distance.to_bits());
     }
 
+    #[test]
+    fn held_dash_bursts_six_frames_every_cooldown() {
+        assert!(DASH_SPEED < PLAYER_COLLIDER_HALF_SIZE + WALL_COLLIDER_HALF_SIZE);
+        let mut dash = Dash::default();
+        let burst_frames: Vec<_> = (0..DASH_COOLDOWN_FRAMES as usize * 2)
+            .filter(|_| tick_dash(&mut dash, true))
+            .collect();
+        let first: Vec<_> = (0..DASH_FRAMES as usize).collect();
+        let second: Vec<_> = first
+            .iter()
+            .map(|frame| frame + DASH_COOLDOWN_FRAMES as usize)
+            .collect();
+        assert_eq!(burst_frames, [first, second].concat());
+
+        let mut idle = Dash::default();
+        assert!(!tick_dash(&mut idle, false));
+        assert_eq!(idle.cooldown, 0);
+    }
+
+    #[test]
+    fn dash_slides_along_walls_instead_of_tunnelling() {
+        let wall = grid_to_world((20, 20));
+        let map = map_with_walls(&[(20, 20)]);
+        let old = wall + Vec2::new(-1.0, 0.2);
+        let resolved = resolve_player_movement(&map, old, Vec2::new(DASH_SPEED, 0.1), false);
+        assert_eq!(resolved, Vec2::new(0.0, 0.1));
+        assert!(!player_hits_wall(&map, old + resolved));
+    }
+
+    #[test]
+    fn health_pickups_only_heal_wounded_ghosts() {
+        assert!(PickupEffect::Health.applies_to(Some(MAX_HEALTH - 1)));
+        assert!(!PickupEffect::Health.applies_to(Some(MAX_HEALTH)));
+        // One-shot matches never spawn health, but a stray pickup is inert.
+        assert!(!PickupEffect::Health.applies_to(None));
+        assert!(PickupEffect::Shield.applies_to(None));
+        assert!(PickupEffect::Speed.applies_to(Some(MAX_HEALTH)));
+        assert!(MAX_HEALTH > 1 && HIT_INVULNERABLE_FRAMES > 0);
+    }
+
+    #[test]
+    fn stock_respawns_pick_the_clearest_open_spawn() {
+        let stocks = MatchRules {
+            stock_lives: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            starting_stocks(stocks, GameMode::Deathmatch),
+            Some(STOCK_LIVES)
+        );
+        assert_eq!(starting_stocks(stocks, GameMode::Duel), None);
+        assert_eq!(
+            starting_stocks(MatchRules::default(), GameMode::Deathmatch),
+            None
+        );
+
+        let mut map = empty_map();
+        let pool = [(2, 2), (38, 38), (2, 38)];
+        let near_first = grid_to_world((4, 4));
+        assert_eq!(
+            respawn_position(&map, &pool, &[near_first]),
+            grid_to_world((38, 38))
+        );
+        // Ties go to the earlier pool entry; claimed cells are skipped.
+        assert_eq!(respawn_position(&map, &pool, &[]), grid_to_world((2, 2)));
+        map.cells[38][38] = CellType::Void;
+        assert_eq!(
+            respawn_position(&map, &pool, &[near_first]),
+            grid_to_world((2, 38))
+        );
+    }
+
+    #[test]
+    fn phasing_passes_walls_and_ends_in_the_nearest_open_cell() {
+        let map = map_with_walls(&[(20, 20), (21, 20)]);
+        let old = grid_to_world((19, 20));
+        let step = Vec2::new(0.2, 0.0);
+        assert_eq!(resolve_player_movement(&map, old, step, false), Vec2::ZERO);
+        assert_eq!(resolve_player_movement(&map, old, step, true), step);
+
+        let mut void = empty_map();
+        void.cells[20][20] = CellType::Void;
+        assert_eq!(
+            resolve_player_movement(&void, old + Vec2::new(0.1, 0.0), step, true),
+            Vec2::ZERO
+        );
+
+        // The three open neighbours of (21, 20) are equally close, so the
+        // grid tie-break picks (21, 19).
+        let inside = grid_to_world((21, 20));
+        assert_eq!(nearest_open_position(&map, inside), grid_to_world((21, 19)));
+        assert_eq!(
+            nearest_open_position(&map, grid_to_world((20, 20))),
+            grid_to_world((19, 20))
+        );
+        let mut trapped = map.clone();
+        trapped.cells[21][19] = CellType::Trap;
+        assert_eq!(
+            nearest_open_position(&trapped, inside),
+            grid_to_world((21, 21))
+        );
+    }
+
+    #[test]
+    fn ectoplasm_halves_movement_on_its_cell_only() {
+        let mut map = empty_map();
+        map.cells[10][10] = CellType::SlowZone;
+        assert_eq!(
+            terrain_speed_scale(&map, grid_to_world((10, 10))),
+            SLOW_ZONE_SCALE
+        );
+        assert_eq!(terrain_speed_scale(&map, grid_to_world((11, 10))), 1.0);
+    }
+
+    #[test]
+    fn spread_fans_three_unit_pellets_around_the_aim() {
+        let aim = Vec2::new(0.0, 1.0);
+        assert_eq!(shot_pattern(Weapon::Blaster, aim), vec![aim]);
+        assert_eq!(shot_pattern(Weapon::Heavy, aim), vec![aim]);
+        let pellets = shot_pattern(Weapon::Spread, aim);
+        assert_eq!(pellets.len(), 3);
+        assert!(pellets.len() as u64 <= SHOT_ID_STRIDE);
+        assert_eq!(pellets[1], aim);
+        for pellet in &pellets {
+            assert!((pellet.length() - 1.0).abs() < 1e-5);
+        }
+        assert!((pellets[0].x + pellets[2].x).abs() < 1e-6);
+        assert_eq!(pellets[0].y, pellets[2].y);
+    }
+
+    #[test]
+    fn ricochet_reflects_the_crossed_axis() {
+        let map = map_with_walls(&[(20, 20)]);
+        let wall = grid_to_world((20, 20));
+        // moving right into a vertical wall flips x only
+        let dir = Vec2::new(1.0, 0.5).normalize();
+        let bounced = ricochet_direction(&map, wall + Vec2::new(-1.0, 0.0), dir, (20, 20));
+        assert_eq!(bounced, Vec2::new(-dir.x, dir.y));
+        // a lone corner hit bounces straight back
+        let map = map_with_walls(&[(20, 20)]);
+        let bounced = ricochet_direction(&map, wall + Vec2::new(-1.0, -1.0), dir, (20, 20));
+        assert_eq!(bounced, -dir);
+    }
+
+    #[test]
+    fn own_ricochets_eliminate_without_credit() {
+        let (shooter, target) = (PlayerId(1), PlayerId(2));
+        assert_eq!(elimination_credit(shooter, target), Some(shooter));
+        assert_eq!(elimination_credit(shooter, shooter), None);
+    }
+
     #[test]
     fn spawn_generation_is_deterministic_unique_and_symmetric() {
         let map = empty_map();
@@ -805,6 +1221,43 @@ fn tick_fire_cooldown(frames_left: u8) -> u8 {
     frames_left.saturating_sub(1)
 }
 
+/// Timed weapon duration, ten seconds at 60 Hz.
+pub(super) const WEAPON_FRAMES: u16 = 600;
+pub(super) const HEAVY_BULLET_SPEED: f32 = 0.2;
+pub(super) const HEAVY_BULLET_RADIUS: f32 = 0.1;
+pub(super) const RICOCHET_BOUNCES: u8 = 2;
+/// Pellets of one shot take consecutive ids below this stride, so sorting by
+/// `(owner, id)` still orders bullets by frame and then by pellet.
+pub(super) const SHOT_ID_STRIDE: u64 = 4;
+/// cos/sin of the 15° spread fan, written out so every peer rotates alike.
+pub(super) const SPREAD_ROTATION: Vec2 = Vec2::new(0.9659258, 0.25881904);
+
+fn bullet_speed(weapon: Weapon) -> f32 {
+    match weapon {
+        Weapon::Heavy => HEAVY_BULLET_SPEED,
+        Weapon::Blaster | Weapon::Spread | Weapon::Ricochet => BULLET_SPEED,
+    }
+}
+
+fn bullet_radius(weapon: Weapon) -> f32 {
+    match weapon {
+        Weapon::Heavy => HEAVY_BULLET_RADIUS,
+        Weapon::Blaster | Weapon::Spread | Weapon::Ricochet => BULLET_RADIUS,
+    }
+}
+
+/// Direction of every pellet in one shot, in id order.
+fn shot_pattern(weapon: Weapon, aim: Vec2) -> Vec<Vec2> {
+    match weapon {
+        Weapon::Spread => vec![
+            aim.rotate(SPREAD_ROTATION * Vec2::new(1.0, -1.0)),
+            aim,
+            aim.rotate(SPREAD_ROTATION),
+        ],
+        Weapon::Blaster | Weapon::Ricochet | Weapon::Heavy => vec![aim],
+    }
+}
+
 pub fn fire_bullets(
     mut commands: Commands,
     frame: Res<GGFrameCount>,
@@ -813,49 +1266,74 @@ pub fn fire_bullets(
     sounds: Res<SoundAssets>,
     mut sound_id: ResMut<SoundIdSeed>,
     mut players: Query<
-        (Entity, &Transform, &Player, &mut BulletReady, &MoveDir),
-        Without<MarkedForDeath>,
+        (
+            Entity,
+            &Transform,
+            &Player,
+            &mut BulletReady,
+            &MoveDir,
+            &AimDir,
+            Option<&EquippedWeapon>,
+        ),
+        (Without<MarkedForDeath>, Without<Phasing>),
     >,
 ) {
     let mut firing: Vec<_> = players
         .iter()
-        .filter_map(|(entity, transform, player, ready, direction)| {
-            let (input, _) = inputs[player.handle];
-            (input::fire(input) && ready.0 == 0).then_some((
-                player.player_id,
-                player.handle,
-                entity,
-                *transform,
-                *direction,
-            ))
-        })
+        .filter_map(
+            |(entity, transform, player, ready, direction, aim, weapon)| {
+                let (input, _) = inputs[player.handle];
+                (input::fire(input) && ready.0 == 0).then_some((
+                    player.player_id,
+                    player.handle,
+                    entity,
+                    *transform,
+                    MoveDir(aim.or(*direction)),
+                    weapon.map(|weapon| weapon.weapon).unwrap_or_default(),
+                ))
+            },
+        )
         .collect();
     firing.sort_by_key(|entry| entry.0);
-    for (player_id, handle, entity, transform, move_dir) in firing {
+    for (player_id, handle, entity, transform, move_dir, weapon) in firing {
         let player_pos = transform.translation.xy();
-        let pos = player_pos + move_dir.0 * (PLAYER_RADIUS + BULLET_RADIUS);
-        // spawn bullet entity
-        commands
-            .spawn((
-                Bullet {
-                    id: frame.frame as u64,
-                    owner: player_id,
-                    owner_handle: handle,
-                    active: true,
-                },
-                move_dir,
-                SpriteBundle {
-                    transform: Transform::from_translation(pos.extend(200.))
-                        .with_rotation(Quat::from_rotation_arc_2d(Vec2::X, move_dir.0)),
-                    texture: images.bullet.clone(),
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::new(0.3, 0.1)),
+        let radius = bullet_radius(weapon);
+        for (pellet, direction) in shot_pattern(weapon, move_dir.0).into_iter().enumerate() {
+            let pos = player_pos + direction * (PLAYER_RADIUS + radius);
+            // spawn bullet entity
+            commands
+                .spawn((
+                    Bullet {
+                        id: frame.frame as u64 * SHOT_ID_STRIDE + pellet as u64,
+                        owner: player_id,
+                        owner_handle: handle,
+                        active: true,
+                        weapon,
+                        bounces_left: if weapon == Weapon::Ricochet {
+                            RICOCHET_BOUNCES
+                        } else {
+                            0
+                        },
+                    },
+                    MoveDir(direction),
+                    SpriteBundle {
+                        transform: Transform::from_translation(pos.extend(200.))
+                            .with_rotation(Quat::from_rotation_arc_2d(Vec2::X, direction)),
+                        texture: images.bullet.clone(),
+                        sprite: Sprite {
+                            color: weapon_pickup_color(weapon),
+                            custom_size: Some(if weapon == Weapon::Heavy {
+                                Vec2::new(0.36, 0.24)
+                            } else {
+                                Vec2::new(0.3, 0.1)
+                            }),
+                            ..default()
+                        },
                         ..default()
                     },
-                    ..default()
-                },
-            ))
-            .add_rollback();
+                ))
+                .add_rollback();
+        }
 
         let snd = sound_id.next(handle);
         debug!("firing bullet snd {snd:#00x}");
@@ -874,7 +1352,7 @@ pub fn fire_bullets(
             },))
             .add_rollback();
 
-        if let Ok((_, _, _, mut ready, _)) = players.get_mut(entity) {
+        if let Ok((_, _, _, mut ready, _, _, _)) = players.get_mut(entity) {
             ready.0 = FIRE_COOLDOWN_FRAMES;
         }
     }
@@ -896,10 +1374,242 @@ pub fn tick_speed_boost(mut commands: Commands, mut players: Query<(Entity, &mut
     }
 }
 
+/// Frames a collected pickup takes to respawn on its cell, 15 seconds at 60 Hz.
+pub(super) const PICKUP_RESPAWN_FRAMES: u16 = 900;
+
+pub fn tick_pickup_respawns(
+    mut commands: Commands,
+    mut pickups: Query<(Entity, &mut PickupCharging)>,
+) {
+    for (entity, mut charging) in &mut pickups {
+        if let Some(frames) = tick_boost_frames(charging.frames_left) {
+            charging.frames_left = frames;
+        } else {
+            commands.entity(entity).remove::<PickupCharging>();
+        }
+    }
+}
+
+/// Two seconds of phasing at 60 Hz.
+pub(super) const PHASE_FRAMES: u16 = 120;
+
+/// Where a ghost still inside a wall lands when its phase ends: the centre of
+/// the closest empty cell, ties broken by grid coordinates. Traps, teleporters
+/// and pickups are never landing spots, matching `respawn_position`.
+fn nearest_open_position(map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>, position: Vec2) -> Vec2 {
+    let Some((from_x, from_y)) = world_to_grid(position) else {
+        return position;
+    };
+    (0..MAP_SIZE as u32)
+        .flat_map(|x| (0..MAP_SIZE as u32).map(move |y| (x, y)))
+        .filter(|&(x, y)| map_data.cells[x as usize][y as usize] == CellType::Empty)
+        .filter(|&cell| !player_hits_wall(map_data, grid_to_world(cell)))
+        .min_by_key(|&(x, y)| {
+            let dx = x.abs_diff(from_x);
+            let dy = y.abs_diff(from_y);
+            (dx * dx + dy * dy, x, y)
+        })
+        .map(grid_to_world)
+        .unwrap_or(position)
+}
+
+pub fn tick_phasing(
+    mut commands: Commands,
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    mut players: Query<(Entity, &mut Phasing, &mut Transform)>,
+) {
+    for (entity, mut phasing, mut transform) in &mut players {
+        if let Some(frames) = tick_boost_frames(phasing.frames_left) {
+            phasing.frames_left = frames;
+            continue;
+        }
+        commands.entity(entity).remove::<Phasing>();
+        let position = transform.translation.xy();
+        if player_hits_wall(&map_data, position) {
+            let landing = nearest_open_position(&map_data, position);
+            transform.translation.x = landing.x;
+            transform.translation.y = landing.y;
+        }
+    }
+}
+
+pub fn tick_weapons(mut commands: Commands, mut players: Query<(Entity, &mut EquippedWeapon)>) {
+    for (entity, mut weapon) in &mut players {
+        if let Some(frames) = tick_boost_frames(weapon.frames_left) {
+            weapon.frames_left = frames;
+        } else {
+            commands.entity(entity).remove::<EquippedWeapon>();
+        }
+    }
+}
+
+/// Hit points per ghost under the multi-hit health rule.
+pub(super) const MAX_HEALTH: u8 = 3;
+/// Shield pickups refill a single charge rather than stacking.
+pub(super) const MAX_SHIELD_CHARGES: u8 = 1;
+/// Half a second of grace after a hit, so one burst cannot chain eliminate.
+pub(super) const HIT_INVULNERABLE_FRAMES: u8 = 30;
+
+pub fn tick_hit_invulnerability(
+    mut commands: Commands,
+    mut players: Query<(Entity, &mut HitInvulnerability)>,
+) {
+    for (entity, mut grace) in &mut players {
+        grace.frames_left = grace.frames_left.saturating_sub(1);
+        if grace.frames_left == 0 {
+            commands.entity(entity).remove::<HitInvulnerability>();
+        }
+    }
+}
+
+/// Lives per ghost under the stock rule.
+pub(super) const STOCK_LIVES: u8 = 3;
+/// Three seconds out of play between losing a stock and respawning.
+pub(super) const RESPAWN_DELAY_FRAMES: u16 = 180;
+/// Bullets pass through a freshly respawned ghost for a second and a half.
+pub(super) const RESPAWN_GRACE_FRAMES: u8 = 90;
+
+/// Stocks each ghost starts a round with. Duels already score every
+/// elimination, so only Last Ghost Standing plays the stock rule.
+pub fn starting_stocks(rules: MatchRules, mode: GameMode) -> Option<u8> {
+    (rules.stock_lives && mode == GameMode::Deathmatch).then_some(STOCK_LIVES)
+}
+
+/// Spends one stock of a ghost that was just hit. Returns true when the ghost
+/// is out of the round; otherwise it starts respawning instead.
+pub(super) fn spend_stock(
+    commands: &mut Commands,
+    entity: Entity,
+    stocks: &mut Query<&mut Stocks>,
+) -> bool {
+    let Ok(mut stocks) = stocks.get_mut(entity) else {
+        return true;
+    };
+    stocks.0 = stocks.0.saturating_sub(1);
+    if stocks.0 == 0 {
+        return true;
+    }
+    commands.entity(entity).insert(Respawning {
+        frames_left: RESPAWN_DELAY_FRAMES,
+    });
+    false
+}
+
+/// The spawn in this round's pool farthest from every live ghost, ties going
+/// to the earlier entry. Cells the map has since claimed are skipped.
+fn respawn_position(
+    map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>,
+    pool: &[(u32, u32)],
+    live: &[Vec2],
+) -> Vec2 {
+    let mut best: Option<(Vec2, f32)> = None;
+    for &(x, y) in pool {
+        if map_data.cells[x as usize][y as usize] != CellType::Empty {
+            continue;
+        }
+        let position = grid_to_world((x, y));
+        let clearance = live
+            .iter()
+            .map(|other| position.distance_squared(*other))
+            .fold(f32::INFINITY, f32::min);
+        if best.map_or(true, |(_, most)| clearance > most) {
+            best = Some((position, clearance));
+        }
+    }
+    best.map(|(position, _)| position)
+        .unwrap_or_else(|| nearest_open_position(map_data, Vec2::ZERO))
+}
+
+/// Brings respawning ghosts back in `PlayerId` order, each one placed clear
+/// of the ghosts already in play. Timed power-ups do not survive a lost stock.
+pub fn tick_respawns(
+    mut commands: Commands,
+    seed: Res<GameSeed>,
+    rules: Res<MatchRules>,
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    live: Query<&Transform, (With<Player>, Without<MarkedForDeath>)>,
+    mut respawning: Query<(Entity, &Player, &mut Respawning, &mut Transform), With<MarkedForDeath>>,
+) {
+    let mut ready = Vec::new();
+    for (entity, player, mut respawn, _) in &mut respawning {
+        respawn.frames_left = respawn.frames_left.saturating_sub(1);
+        if respawn.frames_left == 0 {
+            ready.push((player.player_id, entity));
+        }
+    }
+    if ready.is_empty() {
+        return;
+    }
+    ready.sort_by_key(|entry| entry.0);
+    let pool = generate_spawn_positions(seed.0, &map_data, 8);
+    let mut occupied: Vec<_> = live
+        .iter()
+        .map(|transform| transform.translation.xy())
+        .collect();
+    for (_, entity) in ready {
+        let Ok((_, _, _, mut transform)) = respawning.get_mut(entity) else {
+            continue;
+        };
+        let position = respawn_position(&map_data, &pool, &occupied);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        occupied.push(position);
+        let mut ghost = commands.entity(entity);
+        ghost
+            .remove::<(
+                Respawning,
+                MarkedForDeath,
+                SpeedBoost,
+                ShieldCharges,
+                EquippedWeapon,
+                Phasing,
+                TeleportCooldown,
+            )>()
+            .insert((
+                Dash::default(),
+                HitInvulnerability {
+                    frames_left: RESPAWN_GRACE_FRAMES,
+                },
+            ));
+        if rules.multi_hit_health {
+            ghost.insert(Health(MAX_HEALTH));
+        }
+    }
+}
+
+/// Respawning ghosts are hidden, eyes included, until they are back in play.
+pub fn reconcile_respawn_visibility(
+    mut players: Query<(&mut Visibility, Option<&Respawning>), With<Player>>,
+) {
+    for (mut visibility, respawning) in &mut players {
+        let expected = if respawning.is_some() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        if *visibility != expected {
+            *visibility = expected;
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 enum PickupEffect {
     Speed,
     Shield,
+    Health,
+    Weapon(Weapon),
+    Phase,
+}
+
+impl PickupEffect {
+    /// Health pickups stay on the floor until a wounded ghost reaches them.
+    fn applies_to(self, health: Option<u8>) -> bool {
+        match self {
+            Self::Health => health.is_some_and(|health| health < MAX_HEALTH),
+            Self::Speed | Self::Shield | Self::Weapon(_) | Self::Phase => true,
+        }
+    }
 }
 
 fn collect_pickups<P: Component>(
@@ -907,30 +1617,39 @@ fn collect_pickups<P: Component>(
     frame: u32,
     sounds: &SoundAssets,
     sound_id: &mut SoundIdSeed,
-    players: &Query<(Entity, &Player, &Transform), Without<MarkedForDeath>>,
-    pickups: &Query<(Entity, &P)>,
+    players: &Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: &Query<(Entity, &P), Without<PickupCharging>>,
     pickup_cell: impl Fn(&P) -> (u16, u16),
-    effect: PickupEffect,
+    effect: impl Fn(&P) -> PickupEffect,
 ) {
     let mut players: Vec<_> = players
         .iter()
-        .filter_map(|(entity, player, transform)| {
-            world_to_grid(transform.translation.xy())
-                .map(|cell| (player.handle, entity, cell, transform.translation))
+        .filter_map(|(entity, player, transform, health)| {
+            world_to_grid(transform.translation.xy()).map(|cell| {
+                (
+                    player.handle,
+                    entity,
+                    cell,
+                    transform.translation,
+                    health.map(|health| health.0),
+                )
+            })
         })
         .collect();
     players.sort_by_key(|player| player.0);
 
     let mut pickups: Vec<_> = pickups
         .iter()
-        .map(|(entity, pickup)| (entity, pickup_cell(pickup)))
+        .map(|(entity, pickup)| (entity, pickup_cell(pickup), effect(pickup)))
         .collect();
-    pickups.sort_by_key(|(_, cell)| *cell);
+    pickups.sort_by_key(|(_, cell, _)| *cell);
 
-    for (pickup_entity, cell) in pickups {
+    for (pickup_entity, cell, effect) in pickups {
         let cell = (cell.0 as u32, cell.1 as u32);
-        let Some((handle, player_entity, _, position)) =
-            players.iter().find(|player| player.2 == cell).copied()
+        let Some((handle, player_entity, _, position, health)) = players
+            .iter()
+            .find(|player| player.2 == cell && effect.applies_to(player.4))
+            .copied()
         else {
             continue;
         };
@@ -939,9 +1658,23 @@ fn collect_pickups<P: Component>(
             PickupEffect::Speed => commands.entity(player_entity).insert(SpeedBoost {
                 frames_left: SPEED_BOOST_FRAMES,
             }),
-            PickupEffect::Shield => commands.entity(player_entity).insert(ShieldCharges(1)),
+            PickupEffect::Shield => commands
+                .entity(player_entity)
+                .insert(ShieldCharges(MAX_SHIELD_CHARGES)),
+            PickupEffect::Health => commands.entity(player_entity).insert(Health(
+                health.unwrap_or(0).saturating_add(1).min(MAX_HEALTH),
+            )),
+            PickupEffect::Weapon(weapon) => commands.entity(player_entity).insert(EquippedWeapon {
+                weapon,
+                frames_left: WEAPON_FRAMES,
+            }),
+            PickupEffect::Phase => commands.entity(player_entity).insert(Phasing {
+                frames_left: PHASE_FRAMES,
+            }),
         };
-        commands.entity(pickup_entity).despawn_recursive();
+        commands.entity(pickup_entity).insert(PickupCharging {
+            frames_left: PICKUP_RESPAWN_FRAMES,
+        });
         commands
             .spawn((RollbackSoundBundle {
                 sound: RollbackSound {
@@ -962,8 +1695,8 @@ pub fn collect_speed_pickups(
     frame: Res<GGFrameCount>,
     sounds: Res<SoundAssets>,
     mut sound_id: ResMut<SoundIdSeed>,
-    players: Query<(Entity, &Player, &Transform), Without<MarkedForDeath>>,
-    pickups: Query<(Entity, &SpeedPickup)>,
+    players: Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: Query<(Entity, &SpeedPickup), Without<PickupCharging>>,
 ) {
     collect_pickups(
         &mut commands,
@@ -973,7 +1706,7 @@ pub fn collect_speed_pickups(
         &players,
         &pickups,
         |pickup| pickup.cell,
-        PickupEffect::Speed,
+        |_| PickupEffect::Speed,
     );
 }
 
@@ -982,8 +1715,68 @@ pub fn collect_shield_pickups(
     frame: Res<GGFrameCount>,
     sounds: Res<SoundAssets>,
     mut sound_id: ResMut<SoundIdSeed>,
-    players: Query<(Entity, &Player, &Transform), Without<MarkedForDeath>>,
-    pickups: Query<(Entity, &ShieldPickup)>,
+    players: Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: Query<(Entity, &ShieldPickup), Without<PickupCharging>>,
+) {
+    collect_pickups(
+        &mut commands,
+        frame.frame,
+        &sounds,
+        &mut sound_id,
+        &players,
+        &pickups,
+        |pickup| pickup.cell,
+        |_| PickupEffect::Shield,
+    );
+}
+
+pub fn collect_health_pickups(
+    mut commands: Commands,
+    frame: Res<GGFrameCount>,
+    sounds: Res<SoundAssets>,
+    mut sound_id: ResMut<SoundIdSeed>,
+    players: Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: Query<(Entity, &HealthPickup), Without<PickupCharging>>,
+) {
+    collect_pickups(
+        &mut commands,
+        frame.frame,
+        &sounds,
+        &mut sound_id,
+        &players,
+        &pickups,
+        |pickup| pickup.cell,
+        |_| PickupEffect::Health,
+    );
+}
+
+pub fn collect_weapon_pickups(
+    mut commands: Commands,
+    frame: Res<GGFrameCount>,
+    sounds: Res<SoundAssets>,
+    mut sound_id: ResMut<SoundIdSeed>,
+    players: Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: Query<(Entity, &WeaponPickup), Without<PickupCharging>>,
+) {
+    collect_pickups(
+        &mut commands,
+        frame.frame,
+        &sounds,
+        &mut sound_id,
+        &players,
+        &pickups,
+        |pickup| pickup.cell,
+        |pickup| PickupEffect::Weapon(pickup.weapon),
+    );
+}
+
+pub fn collect_phase_pickups(
+    mut commands: Commands,
+    frame: Res<GGFrameCount>,
+    sounds: Res<SoundAssets>,
+    mut sound_id: ResMut<SoundIdSeed>,
+    players: Query<(Entity, &Player, &Transform, Option<&Health>), Without<MarkedForDeath>>,
+    pickups: Query<(Entity, &PhasePickup), Without<PickupCharging>>,
 ) {
     collect_pickups(
         &mut commands,
@@ -993,7 +1786,7 @@ pub fn collect_shield_pickups(
         &players,
         &pickups,
         |pickup| pickup.cell,
-        PickupEffect::Shield,
+        |_| PickupEffect::Phase,
     );
 }
 
@@ -1005,6 +1798,7 @@ pub fn trigger_traps(
     mut progress: ResMut<RoundProgress>,
     map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
     players: Query<(Entity, &Player, &Transform), Without<MarkedForDeath>>,
+    mut stocks: Query<&mut Stocks>,
 ) {
     let mut trapped: Vec<_> = players
         .iter()
@@ -1020,10 +1814,13 @@ pub fn trigger_traps(
         .collect();
     trapped.sort_by_key(|entry| entry.0);
     for (player_id, handle, entity, position) in trapped {
-        progress.record_elimination(Elimination {
-            player_id,
-            frame: frame.frame,
-        });
+        if spend_stock(&mut commands, entity, &mut stocks) {
+            progress.record_elimination(Elimination {
+                player_id,
+                frame: frame.frame,
+                eliminated_by: None,
+            });
+        }
         commands
             .entity(entity)
             .insert(MarkedForDeath::at(frame.frame));
@@ -1042,13 +1839,78 @@ pub fn trigger_traps(
     }
 }
 
-pub fn move_bullets(
+/// Frames after leaving the pads before a ghost may teleport again.
+pub(super) const TELEPORT_COOLDOWN_FRAMES: u8 = 30;
+
+pub fn trigger_teleporters(
     mut commands: Commands,
     frame: Res<GGFrameCount>,
     sounds: Res<SoundAssets>,
     mut sound_id: ResMut<SoundIdSeed>,
     map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
-    mut bullets: Query<(Entity, &mut Bullet, &mut Transform, &MoveDir)>,
+    mut players: Query<
+        (
+            Entity,
+            &Player,
+            &mut Transform,
+            Option<&mut TeleportCooldown>,
+        ),
+        Without<MarkedForDeath>,
+    >,
+) {
+    let mut teleporting = Vec::new();
+    for (entity, player, transform, cooldown) in &mut players {
+        let cell = world_to_grid(transform.translation.xy())
+            .filter(|&(x, y)| map_data.cells[x as usize][y as usize] == CellType::Teleporter);
+        match (cell, cooldown) {
+            (Some(_), Some(_)) => {}
+            (None, Some(mut cooldown)) => {
+                cooldown.frames_left = cooldown.frames_left.saturating_sub(1);
+                if cooldown.frames_left == 0 {
+                    commands.entity(entity).remove::<TeleportCooldown>();
+                }
+            }
+            (Some(cell), None) => teleporting.push((player.player_id, player.handle, entity, cell)),
+            (None, None) => {}
+        }
+    }
+    teleporting.sort_by_key(|entry| entry.0);
+    for (_, handle, entity, cell) in teleporting {
+        let Ok((_, _, mut transform, _)) = players.get_mut(entity) else {
+            continue;
+        };
+        let destination = grid_to_world(teleporter_partner(cell));
+        transform.translation.x = destination.x;
+        transform.translation.y = destination.y;
+        commands.entity(entity).insert(TeleportCooldown {
+            frames_left: TELEPORT_COOLDOWN_FRAMES,
+        });
+        commands
+            .spawn((RollbackSoundBundle {
+                sound: RollbackSound {
+                    clip: sounds.ray.clone(),
+                    start_frame: frame.frame,
+                    sub_key: sound_id.next(handle),
+                    volume: 0.5,
+                },
+                transform: Transform::from_translation(transform.translation),
+                ..default()
+            },))
+            .add_rollback();
+    }
+}
+
+/// Distance a bullet travels per rollback frame.
+pub(super) const BULLET_SPEED: f32 = 0.35;
+
+pub fn move_bullets(
+    mut commands: Commands,
+    frame: Res<GGFrameCount>,
+    sounds: Res<SoundAssets>,
+    mut sound_id: ResMut<SoundIdSeed>,
+    mut map_data: ResMut<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    mut wall_damage: ResMut<WallDamage>,
+    mut bullets: Query<(Entity, &mut Bullet, &mut Transform, &mut MoveDir)>,
 ) {
     let limit = Vec2::splat(MAP_SIZE as f32 / 2.);
     let mut order: Vec<_> = bullets
@@ -1057,10 +1919,11 @@ pub fn move_bullets(
         .collect();
     order.sort_by_key(|entry| (entry.0, entry.1));
     for (_, _, entity) in order {
-        let Ok((_, mut bullet, mut transform, dir)) = bullets.get_mut(entity) else {
+        let Ok((_, mut bullet, mut transform, mut dir)) = bullets.get_mut(entity) else {
             continue;
         };
-        let delta = (dir.0 * 0.35).extend(0.);
+        let previous = transform.translation;
+        let delta = (dir.0 * bullet_speed(bullet.weapon)).extend(0.);
         transform.translation += delta;
 
         // check if bullet is out of map bounds
@@ -1075,10 +1938,20 @@ pub fn move_bullets(
         if let Some((x, y)) = world_to_grid(transform.translation.xy()) {
             // if coords are inside of a wall then its a hit
             match map_data.cells[x as usize][y as usize] {
-                CellType::WallBlock | CellType::Void => {
-                    // bullet in a block, despawn it
-                    bullet.active = false;
-                    commands.entity(entity).despawn_recursive();
+                CellType::WallBlock | CellType::CrackedWall | CellType::Void => {
+                    let sound_position = transform.translation;
+                    if bullet.bounces_left > 0 {
+                        // ricochet: step back out of the wall and reflect
+                        bullet.bounces_left -= 1;
+                        dir.0 = ricochet_direction(&map_data, previous.xy(), dir.0, (x, y));
+                        transform.translation = previous;
+                        transform.rotation = Quat::from_rotation_arc_2d(Vec2::X, dir.0);
+                    } else {
+                        // bullet in a block, despawn it
+                        bullet.active = false;
+                        commands.entity(entity).despawn_recursive();
+                    }
+                    damage_wall(&mut map_data, &mut wall_damage, (x as usize, y as usize));
                     commands
                         .spawn((RollbackSoundBundle {
                             sound: RollbackSound {
@@ -1087,7 +1960,7 @@ pub fn move_bullets(
                                 sub_key: sound_id.next(bullet.owner_handle),
                                 ..default()
                             },
-                            transform: Transform::from_translation(transform.translation),
+                            transform: Transform::from_translation(sound_position),
                             ..default()
                         },))
                         .add_rollback();
@@ -1098,21 +1971,66 @@ pub fn move_bullets(
     }
 }
 
-const PLAYER_RADIUS: f32 = 0.5;
-const BULLET_RADIUS: f32 = 0.025;
+/// Reflects a bullet that moved from `previous` into `wall`. Only the axes
+/// whose cell boundary was crossed into a blocked cell are flipped; a head-on
+/// corner hit bounces straight back.
+fn ricochet_direction(
+    map_data: &Map<CellType, MAP_SIZE, MAP_SIZE>,
+    previous: Vec2,
+    dir: Vec2,
+    wall: (u32, u32),
+) -> Vec2 {
+    let Some(from) = world_to_grid(previous) else {
+        return -dir;
+    };
+    let blocked = |x: u32, y: u32| map_data.cells[x as usize][y as usize].is_solid();
+    let flip_x = from.0 != wall.0 && blocked(wall.0, from.1);
+    let flip_y = from.1 != wall.1 && blocked(from.0, wall.1);
+    match (flip_x, flip_y) {
+        (true, false) => Vec2::new(-dir.x, dir.y),
+        (false, true) => Vec2::new(dir.x, -dir.y),
+        _ => -dir,
+    }
+}
+
+pub(super) const PLAYER_RADIUS: f32 = 0.5;
+pub(super) const BULLET_RADIUS: f32 = 0.025;
+
+/// Who scores an elimination by `owner`'s bullet. A ghost caught by its own
+/// ricochet eliminated itself, which credits nobody.
+fn elimination_credit(owner: PlayerId, eliminated: PlayerId) -> Option<PlayerId> {
+    (owner != eliminated).then_some(owner)
+}
+
 pub fn kill_players(
     sounds: Res<SoundAssets>,
     frame: Res<GGFrameCount>,
     mut sound_id: ResMut<SoundIdSeed>,
     mut progress: ResMut<RoundProgress>,
     mut commands: Commands,
-    players: Query<(Entity, &Player, &Transform), (Without<Bullet>, Without<MarkedForDeath>)>,
+    players: Query<
+        (
+            Entity,
+            &Player,
+            &Transform,
+            Option<&Dash>,
+            Option<&HitInvulnerability>,
+        ),
+        (Without<Bullet>, Without<MarkedForDeath>),
+    >,
     bullets: Query<(Entity, &Bullet, &Transform)>,
     mut shields: Query<&mut ShieldCharges>,
+    mut healths: Query<&mut Health>,
+    mut stocks: Query<&mut Stocks>,
 ) {
+    // Dashing and freshly hit ghosts are intangible: bullets pass through
+    // without being spent.
     let mut players: Vec<_> = players
         .iter()
-        .map(|(entity, player, transform)| {
+        .filter(|(_, _, _, dash, grace)| {
+            !dash.is_some_and(|dash| dash.frames_left > 0) && grace.is_none()
+        })
+        .map(|(entity, player, transform, _, _)| {
             (
                 player.handle,
                 player.player_id,
@@ -1125,7 +2043,15 @@ pub fn kill_players(
     let mut bullets: Vec<_> = bullets
         .iter()
         .filter(|(_, bullet, _)| bullet.active)
-        .map(|(entity, bullet, transform)| (bullet.owner, bullet.id, entity, transform.translation))
+        .map(|(entity, bullet, transform)| {
+            (
+                bullet.owner,
+                bullet.id,
+                entity,
+                transform.translation,
+                bullet.weapon,
+            )
+        })
         .collect();
     bullets.sort_by_key(|bullet| (bullet.0, bullet.1));
     let mut consumed = HashSet::new();
@@ -1136,10 +2062,10 @@ pub fn kill_players(
             .map(|shield| shield.0 > 0)
             .unwrap_or(false);
 
-        for (_, _, bullet_entity, bullet_position) in bullets.iter().copied() {
+        for (owner, _, bullet_entity, bullet_position, weapon) in bullets.iter().copied() {
             if consumed.contains(&bullet_entity)
                 || Vec2::distance(player_position.xy(), bullet_position.xy())
-                    >= PLAYER_RADIUS + BULLET_RADIUS
+                    >= PLAYER_RADIUS + bullet_radius(weapon)
             {
                 continue;
             }
@@ -1166,7 +2092,34 @@ pub fn kill_players(
                         ..default()
                     },))
                     .add_rollback();
-                continue;
+                // heavy shots break the shield and still land
+                if weapon != Weapon::Heavy {
+                    continue;
+                }
+            }
+
+            // Under the health rule a non-lethal hit only starts the grace
+            // period; the remaining bullets this frame pass through.
+            if let Ok(mut health) = healths.get_mut(player_entity) {
+                health.0 = health.0.saturating_sub(1);
+                if health.0 > 0 {
+                    commands.entity(player_entity).insert(HitInvulnerability {
+                        frames_left: HIT_INVULNERABLE_FRAMES,
+                    });
+                    commands
+                        .spawn((RollbackSoundBundle {
+                            sound: RollbackSound {
+                                clip: sounds.swoosh_death.clone(),
+                                start_frame: frame.frame,
+                                sub_key: sound_id.next(handle),
+                                volume: 0.4,
+                            },
+                            transform: Transform::from_translation(bullet_position),
+                            ..default()
+                        },))
+                        .add_rollback();
+                    break;
+                }
             }
 
             commands
@@ -1191,10 +2144,13 @@ pub fn kill_players(
                     ..default()
                 },))
                 .add_rollback();
-            progress.record_elimination(Elimination {
-                player_id,
-                frame: frame.frame,
-            });
+            if spend_stock(&mut commands, player_entity, &mut stocks) {
+                progress.record_elimination(Elimination {
+                    player_id,
+                    frame: frame.frame,
+                    eliminated_by: elimination_credit(owner, player_id),
+                });
+            }
             commands
                 .entity(player_entity)
                 .insert(MarkedForDeath::at(frame.frame));
@@ -1,42 +1,84 @@
-//! Local target practice shown while the network matchmaking flow continues.
+//! Local target practice shown while the network matchmaking flow continues,
+//! as timed solo runs from the menu, and as the tutorial's arena.
 //!
 //! Nothing in this module is rollback-authoritative. Practice owns its entities
-//! and resources, runs only in `GameState::Matchmaking`, and is removed before
-//! the game session is allowed to present its arena.
+//! and resources, runs only in `GameState::Matchmaking`, `GameState::Practice`
+//! and `GameState::Tutorial`, and is removed before the game session is
+//! allowed to present its arena.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use bevy::{input::touch::Touches, prelude::*, window::PrimaryWindow};
 use bevy_kira_audio::prelude::AudioReceiver;
 
-use super::assets::textures::ImageAssets;
+use super::{
+    assets::textures::ImageAssets,
+    map::splitmix64,
+    player::{BASE_MOVE_SPEED, BOOSTED_MOVE_SPEED},
+};
 
-const ARENA_MIN: Vec2 = Vec2::new(-4.25, -3.25);
-const ARENA_MAX: Vec2 = Vec2::new(4.25, 3.25);
-const PLAYER_RADIUS: f32 = 0.45;
+pub(super) const ARENA_MIN: Vec2 = Vec2::new(-4.25, -3.25);
+pub(super) const ARENA_MAX: Vec2 = Vec2::new(4.25, 3.25);
+pub(super) const PLAYER_RADIUS: f32 = 0.45;
+pub(super) const PLAYER_COLOR: Color = Color::rgb(0.25, 0.82, 0.95);
 const TARGET_RADIUS: f32 = 0.42;
 const SHOT_RADIUS: f32 = 0.12;
 const PLAYER_SPEED: f32 = 4.8;
 const SHOT_SPEED: f32 = 9.5;
 const SHOT_LIFETIME: f32 = 1.35;
-const FIRE_COOLDOWN: f32 = 0.18;
+pub(super) const FIRE_COOLDOWN: f32 = 0.18;
 const TARGET_RESPAWN_DELAY: f32 = 0.28;
 const TOUCH_DEADZONE: f32 = 20.0;
+/// Length of every timed drill.
+pub const DRILL_SECONDS: f32 = 30.0;
+/// Shots allowed in the accuracy drill.
+pub const ACCURACY_SHOTS: u32 = 20;
+/// A reflex target shrinks from full size to nothing in this long.
+const REFLEX_SHRINK_SECONDS: f32 = 2.4;
+/// Below this a reflex target has escaped.
+const REFLEX_MIN_RADIUS: f32 = 0.08;
+const MAX_PERSONAL_BESTS: usize = 32;
 
 #[derive(Component, Debug, Clone, Copy)]
 pub struct PracticePlayer {
     facing: Vec2,
+    /// Seconds of boosted movement left from a tutorial speed pickup.
+    speed_boost: f32,
 }
 
 impl Default for PracticePlayer {
     fn default() -> Self {
-        Self { facing: Vec2::X }
+        Self {
+            facing: Vec2::X,
+            speed_boost: 0.0,
+        }
+    }
+}
+
+impl PracticePlayer {
+    pub fn speed_boost(&self) -> f32 {
+        self.speed_boost
+    }
+
+    pub(super) fn boost(&mut self, seconds: f32) {
+        self.speed_boost = self.speed_boost.max(seconds);
+    }
+
+    fn speed(&self) -> f32 {
+        if self.speed_boost > 0.0 {
+            PLAYER_SPEED * BOOSTED_MOVE_SPEED / BASE_MOVE_SPEED
+        } else {
+            PLAYER_SPEED
+        }
     }
 }
 
 #[derive(Component, Debug, Clone, Copy)]
 pub struct Target {
     velocity: Vec2,
+    radius: f32,
+    /// Radius lost per second; only reflex targets shrink.
+    shrink_rate: f32,
 }
 
 #[derive(Component, Debug, Clone, Copy)]
@@ -55,6 +97,7 @@ pub struct PracticeScore {
     pub score: u32,
     pub streak: u32,
     pub best_streak: u32,
+    pub hits: u32,
 }
 
 #[derive(Resource, Debug, Default, Clone, Copy)]
@@ -62,11 +105,218 @@ pub struct PracticeCooldown {
     pub remaining: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drill {
+    /// Score as much as possible before time runs out.
+    ScoreAttack,
+    /// Hit as many moving targets as possible with a limited number of shots.
+    Accuracy,
+    /// Targets shrink away unless they are hit quickly.
+    Reflex,
+}
+
+impl Drill {
+    pub const ALL: [Drill; 3] = [Self::ScoreAttack, Self::Accuracy, Self::Reflex];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ScoreAttack => "Score Attack",
+            Self::Accuracy => "Accuracy",
+            Self::Reflex => "Reflex",
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::ScoreAttack => format!(
+                "Score as much as you can in {} seconds; streaks pay extra.",
+                DRILL_SECONDS as u32
+            ),
+            Self::Accuracy => {
+                format!("Hit as many moving targets as you can with {ACCURACY_SHOTS} shots.")
+            }
+            Self::Reflex => "Targets shrink away; an escaped target breaks your streak.".into(),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::ScoreAttack => "score-attack",
+            Self::Accuracy => "accuracy",
+            Self::Reflex => "reflex",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+        }
+    }
+
+    fn speed_scale(self) -> f32 {
+        match self {
+            Self::Easy => 0.7,
+            Self::Normal => 1.0,
+            Self::Hard => 1.45,
+        }
+    }
+
+    fn target_radius(self) -> f32 {
+        TARGET_RADIUS
+            * match self {
+                Self::Easy => 1.3,
+                Self::Normal => 1.0,
+                Self::Hard => 0.72,
+            }
+    }
+}
+
+/// A timed solo drill. Inserted before entering `GameState::Practice`; the
+/// waiting-room practice has none and never ends.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PracticeRun {
+    /// Targets follow this seed instead of the fixed waiting-room pattern,
+    /// so everyone playing the same seed gets the same run.
+    pub seed: u64,
+    pub drill: Drill,
+    pub difficulty: Difficulty,
+    pub remaining: f32,
+    pub shots_fired: u32,
+    /// The challenge this run counts toward.
+    pub challenge: Option<String>,
+    /// Set once the finished run's result has been saved.
+    pub recorded: bool,
+}
+
+impl PracticeRun {
+    pub fn new(drill: Drill, difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            seed,
+            drill,
+            difficulty,
+            remaining: DRILL_SECONDS,
+            shots_fired: 0,
+            challenge: None,
+            recorded: false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    /// Shots left in the accuracy drill; other drills are unlimited.
+    pub fn shots_left(&self) -> Option<u32> {
+        (self.drill == Drill::Accuracy).then(|| ACCURACY_SHOTS.saturating_sub(self.shots_fired))
+    }
+
+    /// What a personal best compares: hits for accuracy, points otherwise.
+    pub fn result(&self, score: &PracticeScore) -> u32 {
+        match self.drill {
+            Drill::Accuracy => score.hits,
+            Drill::ScoreAttack | Drill::Reflex => score.score,
+        }
+    }
+}
+
+/// Best result per drill and difficulty, kept in the casual profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersonalBests(BTreeMap<String, u32>);
+
+impl PersonalBests {
+    pub fn get(&self, drill: Drill, difficulty: Difficulty) -> u32 {
+        self.0
+            .get(&best_key(drill, difficulty))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns whether `result` beat the previous best.
+    pub fn record(&mut self, drill: Drill, difficulty: Difficulty, result: u32) -> bool {
+        if result <= self.get(drill, difficulty) {
+            return false;
+        }
+        self.0.insert(best_key(drill, difficulty), result);
+        true
+    }
+
+    pub fn merge(&mut self, other: PersonalBests) {
+        for (key, best) in other.0 {
+            let entry = self.0.entry(key).or_default();
+            *entry = (*entry).max(best);
+        }
+        self.truncate();
+    }
+
+    pub fn encode(&self) -> String {
+        self.0
+            .iter()
+            .map(|(key, best)| format!("{key}:{best}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Keys this build does not know are kept for a newer build's drills.
+    pub fn decode(value: &str) -> Self {
+        let mut bests = Self(
+            value
+                .split(',')
+                .filter_map(|entry| entry.split_once(':'))
+                .filter(|(key, _)| {
+                    !key.is_empty()
+                        && key.len() <= 32
+                        && key
+                            .bytes()
+                            .all(|byte| byte.is_ascii_lowercase() || byte == b'-')
+                })
+                .filter_map(|(key, best)| Some((key.to_owned(), best.parse().ok()?)))
+                .collect(),
+        );
+        bests.truncate();
+        bests
+    }
+
+    fn truncate(&mut self) {
+        while self.0.len() > MAX_PERSONAL_BESTS {
+            self.0.pop_last();
+        }
+    }
+}
+
+fn best_key(drill: Drill, difficulty: Difficulty) -> String {
+    format!("{}-{}", drill.key(), difficulty.key())
+}
+
 #[derive(Resource, Debug, Default, Clone, Copy)]
 pub struct PracticeSpawn {
     sequence: u32,
     pending: u8,
     until_next: f32,
+    seed: Option<u64>,
+    difficulty: Difficulty,
+    shrinking: bool,
 }
 
 #[derive(Resource, Debug, Default, Clone, Copy)]
@@ -133,9 +383,9 @@ fn tick_cooldown(remaining: f32, delta_seconds: f32) -> f32 {
     (remaining - delta_seconds.max(0.0)).max(0.0)
 }
 
-fn bounce_target(position: Vec2, velocity: Vec2, delta_seconds: f32) -> (Vec2, Vec2) {
-    let lower = ARENA_MIN + Vec2::splat(TARGET_RADIUS);
-    let upper = ARENA_MAX - Vec2::splat(TARGET_RADIUS);
+fn bounce_target(position: Vec2, velocity: Vec2, radius: f32, delta_seconds: f32) -> (Vec2, Vec2) {
+    let lower = ARENA_MIN + Vec2::splat(radius);
+    let upper = ARENA_MAX - Vec2::splat(radius);
     let mut next = position + velocity * delta_seconds.max(0.0);
     let mut bounced = velocity;
 
@@ -156,11 +406,18 @@ fn bounce_target(position: Vec2, velocity: Vec2, delta_seconds: f32) -> (Vec2, V
     (next, bounced)
 }
 
-fn shot_hits_target(shot: Vec2, target: Vec2) -> bool {
-    shot.distance_squared(target) <= (SHOT_RADIUS + TARGET_RADIUS).powi(2)
+fn shot_hits_target(shot: Vec2, target: Vec2, radius: f32) -> bool {
+    shot.distance_squared(target) <= (SHOT_RADIUS + radius).powi(2)
+}
+
+/// The radius a reflex target shrinks to, or `None` once it has escaped.
+fn shrink_target(radius: f32, shrink_rate: f32, delta_seconds: f32) -> Option<f32> {
+    let radius = radius - shrink_rate * delta_seconds.max(0.0);
+    (radius > REFLEX_MIN_RADIUS).then_some(radius)
 }
 
 fn register_hit(score: &mut PracticeScore) {
+    score.hits = score.hits.saturating_add(1);
     score.streak = score.streak.saturating_add(1);
     score.best_streak = score.best_streak.max(score.streak);
     score.score = score
@@ -176,11 +433,49 @@ fn shot_should_cleanup(position: Vec2, lifetime: f32) -> bool {
         || position.y > ARENA_MAX.y + 0.5
 }
 
+/// Spawn position and velocity of the `sequence`th target of a seeded run.
+fn seeded_target(seed: u64, sequence: u32, radius: f32) -> (Vec2, Vec2) {
+    let mut bits = seed ^ u64::from(sequence).wrapping_mul(0x2545_f491_4f6c_dd1d);
+    let mut unit = || {
+        bits = splitmix64(bits);
+        (bits >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let lower = ARENA_MIN + Vec2::splat(radius);
+    let upper = ARENA_MAX - Vec2::splat(radius);
+    let position = lower + (upper - lower) * Vec2::new(unit(), unit());
+    let velocity = Vec2::from_angle(unit() * std::f32::consts::TAU) * (0.9 + unit() * 0.6);
+    (position, velocity)
+}
+
 fn cleanup_plan(entities: impl IntoIterator<Item = Entity>) -> Vec<Entity> {
     entities.into_iter().collect()
 }
 
-pub fn setup_practice(mut commands: Commands, images: Res<ImageAssets>) {
+pub fn setup_practice(
+    mut commands: Commands,
+    images: Res<ImageAssets>,
+    run: Option<Res<PracticeRun>>,
+) {
+    spawn_practice_arena(&mut commands, &images, Vec2::new(-2.7, -1.7));
+    let mut spawn = PracticeSpawn {
+        seed: run.as_ref().map(|run| run.seed),
+        difficulty: run.as_ref().map(|run| run.difficulty).unwrap_or_default(),
+        shrinking: run.is_some_and(|run| run.drill == Drill::Reflex),
+        ..default()
+    };
+    for _ in 0..3 {
+        spawn_target(&mut commands, &images, &mut spawn);
+    }
+    commands.insert_resource(spawn);
+}
+
+/// Resets the practice resources and spawns the bounded pad and the player
+/// at `player_start`, without any targets.
+pub(super) fn spawn_practice_arena(
+    commands: &mut Commands,
+    images: &ImageAssets,
+    player_start: Vec2,
+) {
     commands.insert_resource(PracticeScore::default());
     commands.insert_resource(PracticeCooldown::default());
     commands.insert_resource(PracticeTouch::default());
@@ -237,9 +532,9 @@ pub fn setup_practice(mut commands: Commands, images: Res<ImageAssets>) {
         PracticePlayer::default(),
         SpriteBundle {
             texture: images.ghost.clone(),
-            transform: Transform::from_xyz(-2.7, -1.7, 30.0),
+            transform: Transform::from_translation(player_start.extend(30.0)),
             sprite: Sprite {
-                color: Color::rgb(0.25, 0.82, 0.95),
+                color: PLAYER_COLOR,
                 custom_size: Some(Vec2::splat(0.9)),
                 ..default()
             },
@@ -247,12 +542,6 @@ pub fn setup_practice(mut commands: Commands, images: Res<ImageAssets>) {
         },
         Name::new("practice: player"),
     ));
-
-    let mut spawn = PracticeSpawn::default();
-    for _ in 0..3 {
-        spawn_target(&mut commands, &images, &mut spawn);
-    }
-    commands.insert_resource(spawn);
 }
 
 fn spawn_target(commands: &mut Commands, images: &ImageAssets, spawn: &mut PracticeSpawn) {
@@ -276,19 +565,32 @@ fn spawn_target(commands: &mut Commands, images: &ImageAssets, spawn: &mut Pract
         Vec2::new(0.72, -1.22),
         Vec2::new(-0.94, 1.02),
     ];
-    let index = spawn.sequence as usize % POSITIONS.len();
+    let radius = spawn.difficulty.target_radius();
+    let (position, velocity) = match spawn.seed {
+        Some(seed) => seeded_target(seed, spawn.sequence, radius),
+        None => {
+            let index = spawn.sequence as usize % POSITIONS.len();
+            (POSITIONS[index], VELOCITIES[index])
+        }
+    };
     spawn.sequence = spawn.sequence.wrapping_add(1);
     commands.spawn((
         PracticeOwned,
         Target {
-            velocity: VELOCITIES[index],
+            velocity: velocity * spawn.difficulty.speed_scale(),
+            radius,
+            shrink_rate: if spawn.shrinking {
+                radius / REFLEX_SHRINK_SECONDS
+            } else {
+                0.0
+            },
         },
         SpriteBundle {
             texture: images.ghost.clone(),
-            transform: Transform::from_translation(POSITIONS[index].extend(28.0)),
+            transform: Transform::from_translation(position.extend(28.0)),
             sprite: Sprite {
                 color: Color::rgb(1.0, 0.38, 0.28),
-                custom_size: Some(Vec2::splat(TARGET_RADIUS * 2.0)),
+                custom_size: Some(Vec2::splat(radius * 2.0)),
                 ..default()
             },
             ..default()
@@ -323,10 +625,14 @@ pub fn update_practice_player(
     images: Res<ImageAssets>,
     mut touch_state: ResMut<PracticeTouch>,
     mut cooldown: ResMut<PracticeCooldown>,
+    mut run: Option<ResMut<PracticeRun>>,
     mut players: Query<(&mut Transform, &mut PracticePlayer)>,
 ) {
     let delta_seconds = time.delta_seconds().min(0.1);
     cooldown.remaining = tick_cooldown(cooldown.remaining, delta_seconds);
+    if run.as_ref().is_some_and(|run| run.is_finished()) {
+        return;
+    }
 
     let mut movement = Vec2::ZERO;
     if keys.any_pressed([KeyCode::W, KeyCode::Up]) {
@@ -362,17 +668,25 @@ pub fn update_practice_player(
     let Ok((mut transform, mut facing)) = players.get_single_mut() else {
         return;
     };
+    facing.speed_boost = tick_cooldown(facing.speed_boost, delta_seconds);
     if movement != Vec2::ZERO {
         facing.facing = movement;
         let next = clamp_movement(
             transform.translation.truncate(),
-            movement * PLAYER_SPEED * delta_seconds,
+            movement * facing.speed() * delta_seconds,
         );
         transform.translation.x = next.x;
         transform.translation.y = next.y;
     }
 
-    if fire && cooldown.remaining <= 0.0 {
+    let out_of_shots = run
+        .as_ref()
+        .and_then(|run| run.shots_left())
+        .is_some_and(|left| left == 0);
+    if fire && cooldown.remaining <= 0.0 && !out_of_shots {
+        if let Some(run) = run.as_mut() {
+            run.shots_fired = run.shots_fired.saturating_add(1);
+        }
         let direction = if facing.facing == Vec2::ZERO {
             Vec2::X
         } else {
@@ -402,12 +716,32 @@ pub fn update_practice_player(
     }
 }
 
-pub fn move_practice_targets(time: Res<Time>, mut targets: Query<(&mut Transform, &mut Target)>) {
+pub fn move_practice_targets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut score: ResMut<PracticeScore>,
+    mut spawn: ResMut<PracticeSpawn>,
+    run: Option<Res<PracticeRun>>,
+    mut targets: Query<(Entity, &mut Transform, &mut Target, &mut Sprite)>,
+) {
     let delta_seconds = time.delta_seconds().min(0.1);
-    for (mut transform, mut target) in &mut targets {
+    let finished = run.is_some_and(|run| run.is_finished());
+    for (entity, mut transform, mut target, mut sprite) in &mut targets {
+        if target.shrink_rate > 0.0 && !finished {
+            let Some(radius) = shrink_target(target.radius, target.shrink_rate, delta_seconds)
+            else {
+                commands.entity(entity).despawn_recursive();
+                score.streak = 0;
+                queue_respawn(&mut spawn);
+                continue;
+            };
+            target.radius = radius;
+            sprite.custom_size = Some(Vec2::splat(radius * 2.0));
+        }
         let (position, velocity) = bounce_target(
             transform.translation.truncate(),
             target.velocity,
+            target.radius,
             delta_seconds,
         );
         transform.translation.x = position.x;
@@ -436,30 +770,44 @@ pub fn move_practice_shots(
 pub fn resolve_practice_hits(
     mut commands: Commands,
     shots: Query<(Entity, &Transform), With<Shot>>,
-    targets: Query<(Entity, &Transform), With<Target>>,
+    targets: Query<(Entity, &Transform, &Target)>,
     mut score: ResMut<PracticeScore>,
     mut spawn: ResMut<PracticeSpawn>,
+    run: Option<Res<PracticeRun>>,
 ) {
+    // Shots still in flight when a run ends do not count.
+    if run.is_some_and(|run| run.is_finished()) {
+        return;
+    }
     let mut consumed_targets = HashSet::new();
     for (shot_entity, shot_transform) in &shots {
-        let Some((target_entity, _)) = targets.iter().find(|(target_entity, target_transform)| {
-            !consumed_targets.contains(target_entity)
-                && shot_hits_target(
-                    shot_transform.translation.truncate(),
-                    target_transform.translation.truncate(),
-                )
-        }) else {
+        let Some((target_entity, _, _)) =
+            targets
+                .iter()
+                .find(|(target_entity, target_transform, target)| {
+                    !consumed_targets.contains(target_entity)
+                        && shot_hits_target(
+                            shot_transform.translation.truncate(),
+                            target_transform.translation.truncate(),
+                            target.radius,
+                        )
+                })
+        else {
             continue;
         };
         consumed_targets.insert(target_entity);
         commands.entity(shot_entity).despawn_recursive();
         commands.entity(target_entity).despawn_recursive();
         register_hit(&mut score);
-        if spawn.pending == 0 {
-            spawn.until_next = TARGET_RESPAWN_DELAY;
-        }
-        spawn.pending = spawn.pending.saturating_add(1);
+        queue_respawn(&mut spawn);
+    }
+}
+
+pub(super) fn queue_respawn(spawn: &mut PracticeSpawn) {
+    if spawn.pending == 0 {
+        spawn.until_next = TARGET_RESPAWN_DELAY;
     }
+    spawn.pending = spawn.pending.saturating_add(1);
 }
 
 pub fn respawn_practice_targets(
@@ -481,6 +829,21 @@ pub fn respawn_practice_targets(
     }
 }
 
+pub fn tick_practice_run(
+    time: Res<Time>,
+    run: Option<ResMut<PracticeRun>>,
+    shots: Query<(), With<Shot>>,
+) {
+    let Some(mut run) = run else {
+        return;
+    };
+    run.remaining = tick_cooldown(run.remaining, time.delta_seconds().min(0.1));
+    // The accuracy drill ends early once the last shot has landed or missed.
+    if run.shots_left() == Some(0) && shots.is_empty() {
+        run.remaining = 0.0;
+    }
+}
+
 pub fn cleanup_practice(mut commands: Commands, entities: Query<Entity, With<PracticeOwned>>) {
     for entity in cleanup_plan(entities.iter()) {
         commands.entity(entity).despawn_recursive();
@@ -489,6 +852,7 @@ pub fn cleanup_practice(mut commands: Commands, entities: Query<Entity, With<Pra
     commands.remove_resource::<PracticeCooldown>();
     commands.remove_resource::<PracticeSpawn>();
     commands.remove_resource::<PracticeTouch>();
+    commands.remove_resource::<PracticeRun>();
 }
 
 #[cfg(test)]
@@ -518,23 +882,101 @@ mod tests {
     #[test]
     fn targets_bounce_and_remain_readable_inside_bounds() {
         let start = ARENA_MAX - Vec2::splat(TARGET_RADIUS + 0.01);
-        let (position, velocity) = bounce_target(start, Vec2::splat(2.0), 1.0);
+        let (position, velocity) = bounce_target(start, Vec2::splat(2.0), TARGET_RADIUS, 1.0);
         assert_eq!(position, ARENA_MAX - Vec2::splat(TARGET_RADIUS));
         assert!(velocity.x < 0.0 && velocity.y < 0.0);
     }
 
+    #[test]
+    fn seeded_targets_repeat_per_seed_and_spawn_inside_bounds() {
+        let lower = ARENA_MIN + Vec2::splat(TARGET_RADIUS);
+        let upper = ARENA_MAX - Vec2::splat(TARGET_RADIUS);
+        for sequence in 0..64 {
+            let (position, velocity) = seeded_target(42, sequence, TARGET_RADIUS);
+            assert_eq!(
+                (position, velocity),
+                seeded_target(42, sequence, TARGET_RADIUS)
+            );
+            assert!(position.cmpge(lower).all() && position.cmple(upper).all());
+            assert!((0.89..1.51).contains(&velocity.length()));
+        }
+        assert_ne!(
+            seeded_target(42, 0, TARGET_RADIUS),
+            seeded_target(43, 0, TARGET_RADIUS)
+        );
+        assert_ne!(
+            seeded_target(42, 0, TARGET_RADIUS),
+            seeded_target(42, 1, TARGET_RADIUS)
+        );
+    }
+
     #[test]
     fn hit_radius_includes_edges_and_rejects_misses() {
         assert!(shot_hits_target(
             Vec2::ZERO,
-            Vec2::X * (SHOT_RADIUS + TARGET_RADIUS)
+            Vec2::X * (SHOT_RADIUS + TARGET_RADIUS),
+            TARGET_RADIUS
         ));
         assert!(!shot_hits_target(
             Vec2::ZERO,
-            Vec2::X * (SHOT_RADIUS + TARGET_RADIUS + 0.01)
+            Vec2::X * (SHOT_RADIUS + TARGET_RADIUS + 0.01),
+            TARGET_RADIUS
         ));
     }
 
+    #[test]
+    fn difficulty_scales_targets_and_reflex_targets_escape() {
+        assert!(Difficulty::Hard.target_radius() < Difficulty::Normal.target_radius());
+        assert!(Difficulty::Easy.target_radius() > Difficulty::Normal.target_radius());
+        assert!(Difficulty::Hard.speed_scale() > Difficulty::Easy.speed_scale());
+
+        let rate = TARGET_RADIUS / REFLEX_SHRINK_SECONDS;
+        let radius = shrink_target(TARGET_RADIUS, rate, 1.0).unwrap();
+        assert!(radius < TARGET_RADIUS);
+        assert_eq!(shrink_target(radius, rate, REFLEX_SHRINK_SECONDS), None);
+        assert_eq!(shrink_target(radius, 0.0, 1.0), Some(radius));
+    }
+
+    #[test]
+    fn accuracy_runs_count_shots_and_score_hits() {
+        let mut run = PracticeRun::new(Drill::Accuracy, Difficulty::Hard, 7);
+        assert_eq!(run.shots_left(), Some(ACCURACY_SHOTS));
+        run.shots_fired = ACCURACY_SHOTS + 3;
+        assert_eq!(run.shots_left(), Some(0));
+
+        let mut score = PracticeScore::default();
+        register_hit(&mut score);
+        register_hit(&mut score);
+        assert_eq!(run.result(&score), 2);
+        let attack = PracticeRun::new(Drill::ScoreAttack, Difficulty::Hard, 7);
+        assert_eq!(attack.shots_left(), None);
+        assert_eq!(attack.result(&score), score.score);
+    }
+
+    #[test]
+    fn personal_bests_keep_the_highest_result_per_drill_and_difficulty() {
+        let mut bests = PersonalBests::default();
+        assert!(bests.record(Drill::Reflex, Difficulty::Hard, 40));
+        assert!(!bests.record(Drill::Reflex, Difficulty::Hard, 30));
+        assert!(bests.record(Drill::Accuracy, Difficulty::Easy, 12));
+        assert_eq!(bests.get(Drill::Reflex, Difficulty::Hard), 40);
+        assert_eq!(bests.get(Drill::Reflex, Difficulty::Easy), 0);
+
+        let decoded = PersonalBests::decode(&bests.encode());
+        assert_eq!(decoded, bests);
+        assert_eq!(
+            PersonalBests::decode("reflex-hard:9,BAD:1,accuracy-easy:x,spin-hard:3").encode(),
+            "reflex-hard:9,spin-hard:3"
+        );
+
+        let mut other = PersonalBests::default();
+        other.record(Drill::Reflex, Difficulty::Hard, 55);
+        other.record(Drill::Reflex, Difficulty::Hard, 10);
+        bests.merge(other);
+        assert_eq!(bests.get(Drill::Reflex, Difficulty::Hard), 55);
+        assert_eq!(bests.get(Drill::Accuracy, Difficulty::Easy), 12);
+    }
+
     #[test]
     fn scoring_rewards_and_tracks_a_streak() {
         let mut score = PracticeScore::default();
@@ -0,0 +1,201 @@
+//! Portable profile codes for moving a casual profile between browsers and
+//! devices.
+//!
+//! A code is `CasualProfile::encode` in unpadded URL-safe base64 behind a
+//! format prefix, followed by a checksum that catches truncated or mistyped
+//! pastes. It is not a signature, so a code can claim anything: the profile
+//! stays client-side and non-authoritative, `CasualProfile::merge_import`
+//! keeps the larger of each counter and lifetime points as claimed, and it
+//! never takes achievements or unlocks from a code.
+
+use super::{fingerprint::fnv1a, progression::CasualProfile};
+
+const CODE_PREFIX: &str = "GHOST1-";
+const CHECKSUM_DIGITS: usize = 8;
+const MAX_CODE_BYTES: usize = 64 * 1024;
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileCodeError {
+    UnknownFormat,
+    Malformed,
+    ChecksumMismatch,
+    NotAProfile,
+}
+
+impl ProfileCodeError {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::UnknownFormat => "That is not a Ghost Battle profile code.",
+            Self::Malformed => "The profile code is damaged or incomplete.",
+            Self::ChecksumMismatch => {
+                "The profile code failed its checksum; check for a typo or a missing part."
+            }
+            Self::NotAProfile => "The profile code does not contain a profile.",
+        }
+    }
+}
+
+pub fn export_code(profile: &CasualProfile) -> String {
+    let encoded = profile.encode();
+    format!(
+        "{CODE_PREFIX}{}-{:08x}",
+        encode_base64(encoded.as_bytes()),
+        checksum(encoded.as_bytes())
+    )
+}
+
+/// Reads a pasted code. Whitespace is ignored, so a code that wrapped across
+/// lines in a chat or note still imports.
+pub fn decode_code(code: &str) -> Result<CasualProfile, ProfileCodeError> {
+    if code.len() > MAX_CODE_BYTES {
+        return Err(ProfileCodeError::Malformed);
+    }
+    let code: String = code
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect();
+    let body = code
+        .strip_prefix(CODE_PREFIX)
+        .ok_or(ProfileCodeError::UnknownFormat)?;
+    // The base64 alphabet includes '-', but the hex checksum never does.
+    let (payload, expected) = body
+        .rsplit_once('-')
+        .filter(|(_, expected)| expected.len() == CHECKSUM_DIGITS)
+        .ok_or(ProfileCodeError::Malformed)?;
+    let expected = u32::from_str_radix(expected, 16).map_err(|_| ProfileCodeError::Malformed)?;
+    let bytes = decode_base64(payload).ok_or(ProfileCodeError::Malformed)?;
+    if checksum(&bytes) != expected {
+        return Err(ProfileCodeError::ChecksumMismatch);
+    }
+    let text = String::from_utf8(bytes).map_err(|_| ProfileCodeError::Malformed)?;
+    if !CasualProfile::is_envelope(&text) {
+        return Err(ProfileCodeError::NotAProfile);
+    }
+    Ok(CasualProfile::decode(&text))
+}
+
+/// Writes an exported code to the working directory, like session logs.
+/// Browser builds rely on the clipboard and the on-screen code instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_profile_file(code: &str) -> Result<String, String> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let filename = format!("ghost-battle-profile-{stamp}.txt");
+    std::fs::write(&filename, code)
+        .map_err(|error| format!("Could not write {filename}: {error}"))?;
+    Ok(filename)
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    fnv1a(bytes) as u32
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() * 4 / 3 + 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (index, byte)| {
+            word | (u32::from(*byte) << (16 - 8 * index))
+        });
+        for index in 0..=chunk.len() {
+            text.push(BASE64_ALPHABET[((word >> (18 - 6 * index)) & 63) as usize] as char);
+        }
+    }
+    text
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let values = text
+        .bytes()
+        .map(|byte| {
+            BASE64_ALPHABET
+                .iter()
+                .position(|candidate| *candidate == byte)
+                .map(|value| value as u32)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(values.len() / 4 * 3 + 2);
+    for chunk in values.chunks(4) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (index, value)| {
+            word | (value << (18 - 6 * index))
+        });
+        for index in 0..chunk.len() - 1 {
+            bytes.push((word >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_round_trips_every_tail_length() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg");
+        assert_eq!(encode_base64(b"fo"), "Zm8");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(&[0xfb, 0xff]), "-_8");
+        for length in 0..8 {
+            let bytes: Vec<u8> = (0..length)
+                .map(|byte: u8| byte.wrapping_mul(37).wrapping_add(200))
+                .collect();
+            assert_eq!(decode_base64(&encode_base64(&bytes)), Some(bytes));
+        }
+        assert_eq!(decode_base64("Zm9vY"), None);
+        assert_eq!(decode_base64("Zm9v+"), None);
+    }
+
+    #[test]
+    fn codes_round_trip_and_reject_damage() {
+        let mut profile = CasualProfile::default();
+        profile.name = "Code Ghost".into();
+        assert!(profile.award_confirmed_outcome("0a:0b", true, true));
+        let code = export_code(&profile);
+        assert!(code.starts_with(CODE_PREFIX));
+        assert_eq!(decode_code(&code), Ok(profile.clone()));
+
+        // Line wrapping and surrounding whitespace are harmless.
+        let wrapped = format!(
+            "  {}\n{}\n",
+            &code[..code.len() / 2],
+            &code[code.len() / 2..]
+        );
+        assert_eq!(decode_code(&wrapped), Ok(profile.clone()));
+
+        assert_eq!(decode_code("hello"), Err(ProfileCodeError::UnknownFormat));
+        assert_eq!(
+            decode_code(&code[..code.len() - 3]),
+            Err(ProfileCodeError::Malformed)
+        );
+        let flipped = code.replacen(CODE_PREFIX, &format!("{CODE_PREFIX}A"), 1);
+        assert!(matches!(
+            decode_code(&flipped),
+            Err(ProfileCodeError::Malformed | ProfileCodeError::ChecksumMismatch)
+        ));
+        // Editing a field without recomputing the checksum is caught.
+        let forged = profile.encode().replace("\tpoints=5\t", "\tpoints=9\t");
+        let tampered = format!(
+            "{CODE_PREFIX}{}-{}",
+            encode_base64(forged.as_bytes()),
+            &code[code.len() - CHECKSUM_DIGITS..]
+        );
+        assert_eq!(
+            decode_code(&tampered),
+            Err(ProfileCodeError::ChecksumMismatch)
+        );
+        let foreign = format!(
+            "{CODE_PREFIX}{}-{:08x}",
+            encode_base64(b"not a profile"),
+            checksum(b"not a profile")
+        );
+        assert_eq!(decode_code(&foreign), Err(ProfileCodeError::NotAProfile));
+    }
+}
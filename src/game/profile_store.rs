@@ -0,0 +1,395 @@
+//! Desktop storage for the casual profile.
+//!
+//! Browser builds keep the profile in `localStorage`; native builds keep the
+//! same encoded text in a file under the platform config directory. Writes go
+//! to a temporary file that is renamed over the profile, so a crash leaves
+//! either the old or the new profile and never half of one. The previous good
+//! copy is kept as a backup for the rare case the profile itself is damaged.
+//! Each save first folds in whatever another running instance saved, so two
+//! games sharing a profile do not overwrite each other's progress.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use bevy::prelude::*;
+
+use super::progression::CasualProfile;
+
+const APP_DIRECTORY: &str = "ghost-battle";
+const PROFILE_FILE: &str = "profile.txt";
+/// Overrides the config directory, for portable installs and tests.
+const PROFILE_DIR_VARIABLE: &str = "GHOST_BATTLE_PROFILE_DIR";
+/// A save takes milliseconds, so a lock this old was left by a crashed
+/// instance rather than one that is still writing.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10);
+/// Another instance's save is over within a few retries, so waiting for it
+/// costs at most a few hundred milliseconds before this save is put off.
+const LOCK_ATTEMPTS: u32 = 6;
+const FIRST_LOCK_RETRY: Duration = Duration::from_millis(10);
+
+pub struct ProfileStore {
+    directory: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// The per-user store, or `None` when the platform exposes no home or
+    /// config directory and the profile should stay in memory.
+    pub fn platform() -> Option<Self> {
+        if let Some(directory) = std::env::var_os(PROFILE_DIR_VARIABLE) {
+            return Some(Self::new(directory));
+        }
+        platform_config_dir().map(|directory| Self::new(directory.join(APP_DIRECTORY)))
+    }
+
+    fn profile_path(&self) -> PathBuf {
+        self.directory.join(PROFILE_FILE)
+    }
+
+    fn backup_path(&self) -> PathBuf {
+        self.directory.join(format!("{PROFILE_FILE}.bak"))
+    }
+
+    fn temporary_path(&self) -> PathBuf {
+        self.directory
+            .join(format!("{PROFILE_FILE}.{}.tmp", std::process::id()))
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.directory.join(format!("{PROFILE_FILE}.lock"))
+    }
+
+    /// Returns the stored profile text, the backup when the profile is
+    /// unreadable, or an empty string so decoding starts from defaults.
+    pub fn load(&self) -> String {
+        if let Some(text) = read_profile(&self.profile_path()) {
+            return text;
+        }
+        let profile = self.profile_path();
+        if profile.exists() {
+            // Keep the damaged file for inspection instead of overwriting it
+            // with the recovered profile on the next save.
+            let quarantine = self
+                .directory
+                .join(format!("{PROFILE_FILE}.corrupt-{}", unix_seconds()));
+            warn!(
+                "casual profile at {} is unreadable; moved it to {}",
+                profile.display(),
+                quarantine.display()
+            );
+            let _ = fs::rename(&profile, quarantine);
+        }
+        read_profile(&self.backup_path()).unwrap_or_default()
+    }
+
+    /// Merges the stored profile into `profile` under the lock, then writes
+    /// the result. The merge keeps this instance's preferences, adds its
+    /// counter progress since the last load or save to the stored counters
+    /// and takes the union of processed rounds.
+    pub fn save(&self, profile: &mut CasualProfile) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let _lock = ProfileLock::acquire(self.lock_path())?;
+        if let Some(saved) = read_profile(&self.profile_path()) {
+            profile.merge_saved(CasualProfile::decode(&saved));
+        }
+        let temporary = self.temporary_path();
+        let written = write_synced(&temporary, &profile.encode()).and_then(|()| {
+            let profile = self.profile_path();
+            if read_profile(&profile).is_some() {
+                fs::copy(&profile, self.backup_path())?;
+            }
+            fs::rename(&temporary, &profile)
+        });
+        match written {
+            Ok(()) => profile.record_counter_baseline(),
+            Err(_) => {
+                let _ = fs::remove_file(&temporary);
+            }
+        }
+        written
+    }
+}
+
+/// Held while a save is in progress so two running games cannot interleave
+/// their writes. The file holds a token unique to this lock, and drop removes
+/// it only while it still does.
+struct ProfileLock {
+    path: PathBuf,
+    token: String,
+}
+
+impl ProfileLock {
+    /// Takes the lock, retrying with a doubling delay while another instance
+    /// holds it and reclaiming it once it has gone stale.
+    fn acquire(path: PathBuf) -> io::Result<Self> {
+        static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+        let token = format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut delay = FIRST_LOCK_RETRY;
+        for attempt in 1..=LOCK_ATTEMPTS {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    if let Err(error) = file.write_all(token.as_bytes()) {
+                        let _ = fs::remove_file(&path);
+                        return Err(error);
+                    }
+                    return Ok(Self { path, token });
+                }
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        reclaim_stale(&path);
+                    } else if attempt < LOCK_ATTEMPTS {
+                        std::thread::sleep(delay);
+                        delay *= 2;
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "another game instance is saving the profile",
+        ))
+    }
+}
+
+impl Drop for ProfileLock {
+    fn drop(&mut self) {
+        // A save slow enough for its lock to be reclaimed as stale must not
+        // delete the lock the reclaiming instance took since.
+        if fs::read_to_string(&self.path).is_ok_and(|held| held == self.token) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Moves a stale lock aside before deleting it. The rename is atomic, so of
+/// two instances reclaiming the same lock only one moves it. A lock that is
+/// fresh once moved was taken after the staleness check; it goes back for its
+/// owner, which a plain delete of the path would have discarded.
+fn reclaim_stale(path: &Path) {
+    let claimed = path.with_extension(format!("lock.{}.stale", std::process::id()));
+    if fs::rename(path, &claimed).is_err() {
+        return;
+    }
+    if !is_stale(&claimed) {
+        let _ = fs::hard_link(&claimed, path);
+    }
+    let _ = fs::remove_file(&claimed);
+}
+
+fn is_stale(path: &Path) -> bool {
+    let age = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    !matches!(age, Some(age) if age <= STALE_LOCK_AGE)
+}
+
+fn read_profile(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .filter(|text| CasualProfile::is_envelope(text))
+}
+
+fn write_synced(path: &Path, value: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(value.as_bytes())?;
+    file.sync_all()
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn platform_config_dir() -> Option<PathBuf> {
+    let from_env = |name| std::env::var_os(name).map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        from_env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        from_env("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        from_env("XDG_CONFIG_HOME")
+            .filter(|directory| directory.is_absolute())
+            .or_else(|| from_env("HOME").map(|home| home.join(".config")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tutorial::TutorialStatus;
+    use super::*;
+
+    fn scratch_store(name: &str) -> ProfileStore {
+        let directory =
+            std::env::temp_dir().join(format!("ghost-battle-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        ProfileStore::new(directory)
+    }
+
+    #[test]
+    fn saves_round_trip_and_recover_from_a_damaged_profile() {
+        let store = scratch_store("store");
+        assert_eq!(store.load(), "");
+
+        let mut first = CasualProfile::default();
+        first.name = "First".into();
+        store.save(&mut first).unwrap();
+        assert_eq!(store.load(), first.encode());
+        assert!(!store.temporary_path().exists());
+        assert!(!store.lock_path().exists());
+
+        let mut second = first.clone();
+        second.name = "Second".into();
+        store.save(&mut second).unwrap();
+        assert_eq!(store.load(), second.encode());
+
+        // A torn or garbled profile falls back to the last good copy and is
+        // set aside rather than silently overwritten.
+        fs::write(store.profile_path(), "GHOSTIES_PRO").unwrap();
+        assert_eq!(store.load(), first.encode());
+        assert!(!store.profile_path().exists());
+        let quarantined = fs::read_dir(&store.directory)
+            .unwrap()
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"));
+        assert!(quarantined);
+
+        // With no usable copy at all, decoding starts from defaults.
+        fs::write(store.backup_path(), [0xff, 0xfe]).unwrap();
+        assert_eq!(store.load(), "");
+        assert_eq!(
+            CasualProfile::decode(&store.load()),
+            CasualProfile::default()
+        );
+        let _ = fs::remove_dir_all(&store.directory);
+    }
+
+    #[test]
+    fn a_held_lock_blocks_saves_until_it_goes_stale() {
+        let store = scratch_store("lock");
+        fs::create_dir_all(&store.directory).unwrap();
+        let held = ProfileLock::acquire(store.lock_path()).unwrap();
+        let error = store.save(&mut CasualProfile::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(store.load(), "");
+        drop(held);
+
+        // A lock left behind by a crash is reclaimed once it is old enough.
+        let stale = fs::File::create(store.lock_path()).unwrap();
+        stale
+            .set_modified(SystemTime::now() - STALE_LOCK_AGE * 2)
+            .unwrap();
+        drop(stale);
+        store.save(&mut CasualProfile::default()).unwrap();
+        assert_eq!(store.load(), CasualProfile::default().encode());
+
+        // A lock taken between the staleness check and the reclaim is put
+        // back rather than deleted.
+        let fresh = ProfileLock::acquire(store.lock_path()).unwrap();
+        reclaim_stale(&store.lock_path());
+        assert!(store.lock_path().exists());
+        let leftovers = fs::read_dir(&store.directory)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".stale"))
+            .count();
+        assert_eq!(leftovers, 0);
+        drop(fresh);
+        assert!(!store.lock_path().exists());
+        let _ = fs::remove_dir_all(&store.directory);
+    }
+
+    #[test]
+    fn saves_keep_what_another_instance_saved_meanwhile() {
+        let store = scratch_store("instances");
+        let mut first = CasualProfile::default();
+        let mut second = CasualProfile::default();
+        first.rounds_played = 5;
+        first.tutorial = TutorialStatus::Completed;
+        store.save(&mut first).unwrap();
+
+        second.name = "Second".into();
+        second.lifetime_points = 30;
+        store.save(&mut second).unwrap();
+        assert_eq!(second.name, "Second");
+        assert_eq!(second.rounds_played, 5);
+        assert_eq!(second.tutorial, TutorialStatus::Completed);
+
+        // Points earned on both sides add up rather than the larger winning.
+        first.lifetime_points = 10;
+        store.save(&mut first).unwrap();
+        let saved = CasualProfile::decode(&store.load());
+        assert_eq!(saved.name, first.name);
+        assert_eq!(saved.lifetime_points, 40);
+        assert_eq!(saved.rounds_played, 5);
+        let _ = fs::remove_dir_all(&store.directory);
+    }
+
+    #[test]
+    fn progress_from_both_instances_adds_up() {
+        let store = scratch_store("progress");
+        let load = || {
+            let mut profile = CasualProfile::decode(&store.load());
+            profile.record_counter_baseline();
+            profile
+        };
+        let mut first = load();
+        let mut second = load();
+        first.lifetime_points = 10;
+        store.save(&mut first).unwrap();
+        second.lifetime_points = 5;
+        store.save(&mut second).unwrap();
+        assert_eq!(CasualProfile::decode(&store.load()).lifetime_points, 15);
+
+        // Saving again with nothing new counts nothing twice.
+        store.save(&mut first).unwrap();
+        store.save(&mut second).unwrap();
+        assert_eq!(CasualProfile::decode(&store.load()).lifetime_points, 15);
+        let _ = fs::remove_dir_all(&store.directory);
+    }
+
+    #[test]
+    fn saves_wait_out_a_brief_lock_and_only_release_their_own() {
+        let store = scratch_store("wait");
+        fs::create_dir_all(&store.directory).unwrap();
+        let held = ProfileLock::acquire(store.lock_path()).unwrap();
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(FIRST_LOCK_RETRY * 2);
+            drop(held);
+        });
+        store.save(&mut CasualProfile::default()).unwrap();
+        release.join().unwrap();
+
+        // A lock reclaimed from a slow instance and taken by another stays
+        // when the slow one finally lets go.
+        let slow = ProfileLock::acquire(store.lock_path()).unwrap();
+        fs::remove_file(store.lock_path()).unwrap();
+        let current = ProfileLock::acquire(store.lock_path()).unwrap();
+        drop(slow);
+        assert!(store.lock_path().exists());
+        drop(current);
+        assert!(!store.lock_path().exists());
+        let _ = fs::remove_dir_all(&store.directory);
+    }
+}
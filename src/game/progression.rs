@@ -4,83 +4,74 @@
 //! `sessionStorage`. This module only owns the durable, non-authoritative
 //! preferences and casual rewards stored in `localStorage`.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use bevy::prelude::*;
 use bevy_ggrs::Session;
 
+#[cfg(not(target_arch = "wasm32"))]
+use super::profile_store::ProfileStore;
 use super::{
+    achievements::{newly_earned, valid_achievement_key, AchievementDefinition, RoundStats},
     assets::sounds::AudioConfig,
-    networking::{GgrsConfig, LocalPlayerHandle},
-    session::{match_winner, MatchId, PlayerProfile, RoundBootstrap, RoundNumber, SessionEpoch},
+    challenges::{active_challenges, Challenge, ChallengeProgress},
+    components::{MarkedForDeath, Player, ShieldCharges},
+    cosmetics::{CosmeticAssets, CosmeticCatalog, CosmeticSlot, Loadout, UnlockSet},
+    match_history::{MatchHistory, MatchParticipant, MatchRecord},
+    networking::{GgrsConfig, InputDelaySetting, LocalPlayerHandle},
+    practice::{PersonalBests, PracticeRun, PracticeScore},
+    profile_code::decode_code,
+    rating::{round_placements, SkillRating, SkillRatings, INITIAL_RATING},
+    session::{
+        match_winner, GameMode, MatchId, PlayerProfile, RoundBootstrap, RoundNumber, SessionEpoch,
+    },
+    toasts::Toasts,
+    tutorial::{TutorialStatus, TutorialStatusRequest},
     PendingPlayerProfile, RollbackState, RoundProgress, Scores,
 };
 
-pub const PROFILE_SCHEMA_VERSION: u8 = 1;
+/// v2 appended the earned achievement keys; v3 appended the Duel and Last
+/// Ghost Standing skill ratings; v4 replaced positional fields with named
+/// `key=value` fields.
+pub const PROFILE_SCHEMA_VERSION: u8 = 4;
+/// Schemas up to v3 stored their fields by position.
+const LAST_POSITIONAL_SCHEMA: u8 = 3;
 pub const PROFILE_STORAGE_KEY: &str = "ghosties.casual-profile.v1";
 const PROFILE_MAGIC: &str = "GHOSTIES_PROFILE";
 const MAX_COUNTER: u64 = 999_999_999;
 const MAX_EVENT_ID_BYTES: usize = 96;
+const MAX_STORED_ACHIEVEMENTS: usize = 64;
+const MAX_PROFILE_FIELDS: usize = 64;
+const MAX_FIELD_KEY_BYTES: usize = 32;
 const DEFAULT_NAME: &str = "Ghost";
 
 pub const ROUND_PARTICIPATION_POINTS: u64 = 2;
 pub const ROUND_WIN_BONUS_POINTS: u64 = 3;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct CosmeticDefinition {
-    pub id: u8,
-    pub name: &'static str,
-    pub required_points: u64,
-}
+type ProfileFields = BTreeMap<String, String>;
 
-/// Deliberately small thresholds make the first rewards available in a short
-/// casual session. Classic is always available.
-pub const COSMETICS: [CosmeticDefinition; 4] = [
-    CosmeticDefinition {
-        id: 0,
-        name: "Classic",
-        required_points: 0,
-    },
-    CosmeticDefinition {
-        id: 1,
-        name: "Crown",
-        required_points: 5,
-    },
-    CosmeticDefinition {
-        id: 2,
-        name: "Wizard",
-        required_points: 12,
-    },
-    CosmeticDefinition {
-        id: 3,
-        name: "Bow",
-        required_points: 25,
-    },
+/// Field names of the positional layouts in storage order. v1 stored the
+/// first ten, v2 the first eleven and v3 all of them.
+const POSITIONAL_FIELDS: [&str; 13] = [
+    "name",
+    "music",
+    "effects",
+    "palette",
+    "points",
+    "matches",
+    "rounds",
+    "unlocks",
+    "loadout",
+    "events",
+    "achievements",
+    "duel-rating",
+    "lgs-rating",
 ];
 
-pub fn cosmetic(id: u8) -> Option<&'static CosmeticDefinition> {
-    COSMETICS
-        .get(id as usize)
-        .filter(|definition| definition.id == id)
-}
-
-pub fn unlocked_mask(lifetime_points: u64) -> u8 {
-    COSMETICS.iter().fold(0, |mask, definition| {
-        if lifetime_points >= definition.required_points {
-            mask | (1 << definition.id)
-        } else {
-            mask
-        }
-    })
-}
-
-pub fn equipped_or_default(equipped: u8, unlocked: u8) -> u8 {
-    if cosmetic(equipped).is_some() && unlocked & (1 << equipped) != 0 {
-        equipped
-    } else {
-        0
-    }
-}
+/// `MIGRATIONS[n - 1]` upgrades named fields from schema `n` to `n + 1`.
+/// Bumping `PROFILE_SCHEMA_VERSION` does not compile without a new step.
+const MIGRATIONS: [fn(&mut ProfileFields); PROFILE_SCHEMA_VERSION as usize - 1] =
+    [migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
 
 #[derive(Resource, Debug, Clone, PartialEq)]
 pub struct CasualProfile {
@@ -88,13 +79,27 @@ pub struct CasualProfile {
     pub name: String,
     pub music_volume: f64,
     pub effects_volume: f64,
+    pub input_delay: InputDelaySetting,
     pub palette_id: u8,
     pub lifetime_points: u64,
     pub matches_played: u64,
     pub rounds_played: u64,
-    pub unlocked_cosmetics: u8,
-    pub equipped_cosmetic: u8,
+    pub unlocked_cosmetics: UnlockSet,
+    pub equipped_cosmetics: Loadout,
+    pub ratings: SkillRatings,
+    pub match_history: MatchHistory,
+    pub challenges: ChallengeProgress,
+    pub practice_bests: PersonalBests,
+    pub tutorial: TutorialStatus,
     processed_outcomes: BTreeSet<String>,
+    achievements: BTreeSet<String>,
+    /// Fields a newer build wrote, kept so a round trip through this build
+    /// does not drop them.
+    unknown_fields: ProfileFields,
+    /// Points, matches and rounds as this instance last loaded or saved
+    /// them. Not stored: a save adds only the progress made since then onto
+    /// what another instance saved meanwhile.
+    counter_baseline: [u64; 3],
 }
 
 impl Default for CasualProfile {
@@ -104,91 +109,265 @@ impl Default for CasualProfile {
             name: DEFAULT_NAME.into(),
             music_volume: 55.0,
             effects_volume: 100.0,
+            input_delay: InputDelaySetting::Auto,
             palette_id: 0,
             lifetime_points: 0,
             matches_played: 0,
             rounds_played: 0,
-            unlocked_cosmetics: 1,
-            equipped_cosmetic: 0,
+            unlocked_cosmetics: UnlockSet::default(),
+            equipped_cosmetics: Loadout::default(),
+            ratings: SkillRatings::default(),
+            match_history: MatchHistory::default(),
+            challenges: ChallengeProgress::default(),
+            practice_bests: PersonalBests::default(),
+            tutorial: TutorialStatus::default(),
             processed_outcomes: BTreeSet::new(),
+            achievements: BTreeSet::new(),
+            unknown_fields: ProfileFields::new(),
+            counter_baseline: [0; 3],
         }
     }
 }
 
 impl CasualProfile {
-    /// Decode the intentionally simple, versioned storage schema. Every field
-    /// is validated independently; an unknown schema or malformed envelope
-    /// returns a complete safe default.
+    /// Decode any stored schema. Positional v1–v3 profiles are lifted into
+    /// named fields, then every migration from the stored version onward runs
+    /// before the fields are validated independently, so an upgrade keeps
+    /// every valid value. A profile from a newer build is read best-effort
+    /// and its unknown fields are kept for the next save. Only a missing
+    /// envelope or a positional layout with the wrong field count returns a
+    /// complete safe default.
     pub fn decode(value: &str) -> Self {
         let fields: Vec<_> = value.split('\t').collect();
-        if fields.len() != 12
-            || fields[0] != PROFILE_MAGIC
-            || fields[1].parse::<u8>().ok() != Some(PROFILE_SCHEMA_VERSION)
-        {
+        let version = fields.get(1).and_then(|version| version.parse::<u8>().ok());
+        let named = match version {
+            _ if fields[0] != PROFILE_MAGIC => None,
+            Some(version @ 1..=LAST_POSITIONAL_SCHEMA) => legacy_fields(version, &fields[2..]),
+            Some(version) if version > LAST_POSITIONAL_SCHEMA => Some(named_fields(&fields[2..])),
+            _ => None,
+        };
+        let (Some(version), Some(mut named)) = (version, named) else {
             return Self::default();
+        };
+        for migrate in MIGRATIONS.iter().skip(usize::from(version) - 1) {
+            migrate(&mut named);
         }
+        Self::from_fields(named)
+    }
 
+    /// Whether `value` carries the profile envelope with a known-shaped
+    /// version, as opposed to text `decode` would only replace with defaults.
+    pub fn is_envelope(value: &str) -> bool {
+        let mut fields = value.split('\t');
+        fields.next() == Some(PROFILE_MAGIC)
+            && fields
+                .next()
+                .and_then(|version| version.parse::<u8>().ok())
+                .is_some_and(|version| version >= 1)
+    }
+
+    fn from_fields(mut fields: ProfileFields) -> Self {
+        let mut take = |key: &str| fields.remove(key).unwrap_or_default();
         let defaults = Self::default();
         let mut profile = Self {
             schema_version: PROFILE_SCHEMA_VERSION,
-            name: canonical_name(fields[2]),
-            music_volume: volume_or_default(fields[3], defaults.music_volume),
-            effects_volume: volume_or_default(fields[4], defaults.effects_volume),
-            palette_id: fields[5]
+            name: canonical_name(&take("name")),
+            music_volume: volume_or_default(&take("music"), defaults.music_volume),
+            effects_volume: volume_or_default(&take("effects"), defaults.effects_volume),
+            input_delay: InputDelaySetting::decode(&take("input-delay")),
+            palette_id: take("palette")
                 .parse::<u8>()
                 .ok()
                 .filter(|id| (*id as usize) < super::session::MAX_LOBBY_PLAYERS)
                 .unwrap_or(defaults.palette_id),
-            lifetime_points: bounded_counter(fields[6]),
-            matches_played: bounded_counter(fields[7]),
-            rounds_played: bounded_counter(fields[8]),
-            unlocked_cosmetics: fields[9].parse::<u8>().unwrap_or(0),
-            equipped_cosmetic: fields[10].parse::<u8>().unwrap_or(0),
-            processed_outcomes: fields[11]
+            lifetime_points: bounded_counter(&take("points")),
+            matches_played: bounded_counter(&take("matches")),
+            rounds_played: bounded_counter(&take("rounds")),
+            unlocked_cosmetics: UnlockSet::decode(&take("unlocks")),
+            equipped_cosmetics: Loadout::decode(&take("loadout")).unwrap_or_default(),
+            ratings: SkillRatings {
+                duel: SkillRating::decode(&take("duel-rating")),
+                deathmatch: SkillRating::decode(&take("lgs-rating")),
+            },
+            match_history: MatchHistory::decode(&take("history")),
+            challenges: ChallengeProgress::decode(&take("challenges")),
+            practice_bests: PersonalBests::decode(&take("practice-bests")),
+            tutorial: TutorialStatus::decode(&take("tutorial")),
+            processed_outcomes: take("events")
                 .split(',')
                 .filter(|id| valid_event_id(id))
                 .map(str::to_owned)
                 .collect(),
+            achievements: take("achievements")
+                .split(',')
+                .filter(|key| valid_achievement_key(key))
+                .take(MAX_STORED_ACHIEVEMENTS)
+                .map(str::to_owned)
+                .collect(),
+            unknown_fields: ProfileFields::new(),
+            counter_baseline: [0; 3],
         };
+        profile.unknown_fields = fields;
         profile.normalize();
         profile
     }
 
+    /// Encode the current schema as named `key=value` fields. Unknown fields
+    /// from a newer build follow the known ones unchanged.
     pub fn encode(&self) -> String {
         let mut profile = self.clone();
         profile.normalize();
-        let events = profile
-            .processed_outcomes
-            .iter()
-            .cloned()
-            .collect::<Vec<_>>()
-            .join(",");
-        format!(
-            "{PROFILE_MAGIC}\t{PROFILE_SCHEMA_VERSION}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
-            profile.name,
-            profile.music_volume,
-            profile.effects_volume,
-            profile.palette_id,
-            profile.lifetime_points,
-            profile.matches_played,
-            profile.rounds_played,
-            profile.unlocked_cosmetics,
-            profile.equipped_cosmetic,
-            events,
-        )
-    }
-
-    pub fn is_unlocked(&self, cosmetic_id: u8) -> bool {
-        cosmetic(cosmetic_id).is_some() && self.unlocked_cosmetics & (1 << cosmetic_id) != 0
+        let join = |values: &BTreeSet<String>| values.iter().cloned().collect::<Vec<_>>().join(",");
+        let known = [
+            ("name", profile.name.clone()),
+            ("music", profile.music_volume.to_string()),
+            ("effects", profile.effects_volume.to_string()),
+            ("input-delay", profile.input_delay.encode()),
+            ("palette", profile.palette_id.to_string()),
+            ("points", profile.lifetime_points.to_string()),
+            ("matches", profile.matches_played.to_string()),
+            ("rounds", profile.rounds_played.to_string()),
+            ("unlocks", profile.unlocked_cosmetics.encode()),
+            ("loadout", profile.equipped_cosmetics.encode()),
+            ("events", join(&profile.processed_outcomes)),
+            ("achievements", join(&profile.achievements)),
+            ("duel-rating", profile.ratings.duel.encode()),
+            ("lgs-rating", profile.ratings.deathmatch.encode()),
+            ("history", profile.match_history.encode()),
+            ("challenges", profile.challenges.encode()),
+            ("practice-bests", profile.practice_bests.encode()),
+            ("tutorial", profile.tutorial.encode().to_owned()),
+        ];
+        let mut encoded = format!("{PROFILE_MAGIC}\t{PROFILE_SCHEMA_VERSION}");
+        for (key, value) in known {
+            encoded.push_str(&format!("\t{key}={value}"));
+        }
+        for (key, value) in &profile.unknown_fields {
+            encoded.push_str(&format!("\t{key}={value}"));
+        }
+        encoded
+    }
+
+    /// Folds an imported profile into this one. Preferences come from the
+    /// import, except the input delay, which suits this device's connection
+    /// rather than the player. Counters keep the larger value, because summing would count
+    /// rounds both profiles already share twice, and processed event IDs are
+    /// merged so neither side's rounds can pay out again. Achievements and
+    /// unlocks are never taken from the import, since a code is unsigned and
+    /// achievements gate cosmetics: unlocks are re-derived from the merged
+    /// points and this profile's own achievements, or left as they were
+    /// until the catalog has loaded.
+    pub fn merge_import(&mut self, imported: CasualProfile, catalog: Option<&CosmeticCatalog>) {
+        self.name = imported.name;
+        self.music_volume = imported.music_volume;
+        self.effects_volume = imported.effects_volume;
+        self.palette_id = imported.palette_id;
+        self.equipped_cosmetics = imported.equipped_cosmetics;
+        self.lifetime_points = self.lifetime_points.max(imported.lifetime_points);
+        self.matches_played = self.matches_played.max(imported.matches_played);
+        self.rounds_played = self.rounds_played.max(imported.rounds_played);
+        for mode in [GameMode::Duel, GameMode::Deathmatch] {
+            let rating = imported.ratings.get(mode);
+            if rating.rated_rounds > self.ratings.get(mode).rated_rounds {
+                *self.ratings.get_mut(mode) = rating.clone();
+            }
+        }
+        self.processed_outcomes.extend(imported.processed_outcomes);
+        self.match_history.merge(imported.match_history);
+        self.challenges.merge(imported.challenges);
+        self.practice_bests.merge(imported.practice_bests);
+        self.tutorial = self.tutorial.max(imported.tutorial);
+        for (key, value) in imported.unknown_fields {
+            self.unknown_fields.entry(key).or_insert(value);
+        }
+        match catalog {
+            Some(catalog) => {
+                self.refresh_unlocks(catalog);
+            }
+            None => self.normalize(),
+        }
+    }
+
+    /// Folds in the profile another running instance saved since this one
+    /// last wrote. Everything merges as for an import except preferences,
+    /// which stay this instance's own, achievements, which this device
+    /// earned itself and so are kept from both sides, and the counters: each
+    /// adds this instance's progress since its baseline to the saved value,
+    /// where the larger of the two would drop one instance's rounds.
+    pub fn merge_saved(&mut self, saved: CasualProfile) {
+        let name = std::mem::take(&mut self.name);
+        let (music, effects) = (self.music_volume, self.effects_volume);
+        let (palette, equipped) = (self.palette_id, self.equipped_cosmetics);
+        let mut achievements = std::mem::take(&mut self.achievements);
+        achievements.extend(saved.achievements.iter().cloned());
+        let mut progress = self.counters();
+        for (counter, baseline) in progress.iter_mut().zip(self.counter_baseline) {
+            *counter = counter.saturating_sub(baseline);
+        }
+        let stored = saved.counters();
+        self.merge_import(saved, None);
+        self.name = name;
+        self.music_volume = music;
+        self.effects_volume = effects;
+        self.palette_id = palette;
+        self.equipped_cosmetics = equipped;
+        self.achievements = achievements
+            .into_iter()
+            .take(MAX_STORED_ACHIEVEMENTS)
+            .collect();
+        let [points, matches, rounds] = [0, 1, 2].map(|index| {
+            stored[index]
+                .saturating_add(progress[index])
+                .min(MAX_COUNTER)
+        });
+        self.lifetime_points = points;
+        self.matches_played = matches;
+        self.rounds_played = rounds;
+        // Until this profile is written, the stored counters are the ones
+        // the next merge must not count again.
+        self.counter_baseline = stored;
+    }
+
+    /// Marks the current counters as stored, after loading or saving.
+    pub fn record_counter_baseline(&mut self) {
+        self.counter_baseline = self.counters();
+    }
+
+    fn counters(&self) -> [u64; 3] {
+        [
+            self.lifetime_points,
+            self.matches_played,
+            self.rounds_played,
+        ]
+    }
+
+    pub fn has_achievement(&self, key: &str) -> bool {
+        self.achievements.contains(key)
+    }
+
+    pub fn is_unlocked(&self, cosmetic_id: u16) -> bool {
+        self.unlocked_cosmetics.contains(cosmetic_id)
     }
 
     #[cfg(test)]
-    pub fn equip(&mut self, cosmetic_id: u8) -> bool {
+    pub fn equip(&mut self, slot: CosmeticSlot, cosmetic_id: u16) -> bool {
         if !self.is_unlocked(cosmetic_id) {
             return false;
         }
-        let changed = self.equipped_cosmetic != cosmetic_id;
-        self.equipped_cosmetic = cosmetic_id;
+        let changed = self.equipped_cosmetics.get(slot) != cosmetic_id;
+        self.equipped_cosmetics.set(slot, cosmetic_id);
+        changed
+    }
+
+    /// Unlocks are earned, not trusted input: re-derive them from lifetime
+    /// points against the loaded catalog and unequip anything no longer
+    /// available in its slot. Returns whether the profile changed.
+    pub fn refresh_unlocks(&mut self, catalog: &CosmeticCatalog) -> bool {
+        let unlocked = catalog.unlocked_by(self.lifetime_points, &self.achievements);
+        let equipped = catalog.sanitize(self.equipped_cosmetics, &unlocked);
+        let changed = self.unlocked_cosmetics != unlocked || self.equipped_cosmetics != equipped;
+        self.unlocked_cosmetics = unlocked;
+        self.equipped_cosmetics = equipped;
         changed
     }
 
@@ -210,7 +389,7 @@ impl CasualProfile {
             } else {
                 0
             };
-        self.lifetime_points = self.lifetime_points.saturating_add(reward).min(MAX_COUNTER);
+        self.add_points(reward);
         self.rounds_played = self.rounds_played.saturating_add(1).min(MAX_COUNTER);
         if match_completed {
             self.matches_played = self.matches_played.saturating_add(1).min(MAX_COUNTER);
@@ -219,6 +398,74 @@ impl CasualProfile {
         true
     }
 
+    /// Applies one confirmed round and returns the achievements it earned,
+    /// or `None` when the event was already processed. Achievements and the
+    /// mode's rating share the round's event ID, so they are exactly as
+    /// idempotent as its points. `opponents` holds each other ghost's
+    /// `(rating, placement)` beside the local `placement`.
+    pub fn award_confirmed_round(
+        &mut self,
+        event_id: &str,
+        stats: &RoundStats,
+        placement: u32,
+        opponents: &[(u16, u32)],
+    ) -> Option<Vec<&'static AchievementDefinition>> {
+        if !self.award_confirmed_outcome(event_id, stats.won_round, stats.match_completed) {
+            return None;
+        }
+        self.ratings.get_mut(stats.mode).apply(placement, opponents);
+        let earned = newly_earned(stats, self.rounds_played, |key| {
+            self.achievements.contains(key)
+        });
+        self.achievements
+            .extend(earned.iter().map(|definition| definition.key.to_owned()));
+        Some(earned)
+    }
+
+    /// Advances the day's and week's round challenges with a round that
+    /// `award_confirmed_round` accepted, pays the reward of each one it
+    /// completed and returns them.
+    pub fn advance_round_challenges(
+        &mut self,
+        stats: &RoundStats,
+        unix_seconds: u64,
+    ) -> Vec<Challenge> {
+        let completed = self
+            .challenges
+            .advance_round(&active_challenges(unix_seconds), stats);
+        for challenge in &completed {
+            self.add_points(challenge.reward);
+        }
+        completed
+    }
+
+    /// Records a finished target run for `challenge_id` and returns the
+    /// challenge if the run completed it. A challenge from an earlier day
+    /// no longer counts.
+    pub fn record_target_run(
+        &mut self,
+        challenge_id: &str,
+        score: u32,
+        unix_seconds: u64,
+    ) -> Option<Challenge> {
+        let active = active_challenges(unix_seconds);
+        if !self
+            .challenges
+            .record_target_run(&active, challenge_id, score)
+        {
+            return None;
+        }
+        let challenge = active
+            .into_iter()
+            .find(|challenge| challenge.id == challenge_id)?;
+        self.add_points(challenge.reward);
+        Some(challenge)
+    }
+
+    fn add_points(&mut self, points: u64) {
+        self.lifetime_points = self.lifetime_points.saturating_add(points).min(MAX_COUNTER);
+    }
+
     fn normalize(&mut self) {
         self.schema_version = PROFILE_SCHEMA_VERSION;
         self.name = canonical_name(&self.name);
@@ -231,12 +478,13 @@ impl CasualProfile {
         self.lifetime_points = self.lifetime_points.min(MAX_COUNTER);
         self.matches_played = self.matches_played.min(MAX_COUNTER);
         self.rounds_played = self.rounds_played.min(MAX_COUNTER);
-        // Unlocks are earned, not trusted input. Keeping the field in the
-        // schema makes unlock state explicit while thresholds remain canonical.
-        self.unlocked_cosmetics = unlocked_mask(self.lifetime_points);
-        self.equipped_cosmetic =
-            equipped_or_default(self.equipped_cosmetic, self.unlocked_cosmetics);
+        // Thresholds live in the catalog asset, so stored unlocks stand in
+        // until `refresh_unlocks` re-derives them once it has loaded.
+        self.equipped_cosmetics = self
+            .equipped_cosmetics
+            .retain_unlocked(&self.unlocked_cosmetics);
         self.processed_outcomes.retain(|id| valid_event_id(id));
+        self.achievements.retain(|key| valid_achievement_key(key));
     }
 }
 
@@ -269,7 +517,16 @@ fn finite_clamped_volume(value: f64, default: f64) -> f64 {
     }
 }
 
-fn valid_event_id(value: &str) -> bool {
+/// Wall-clock seconds, for display dates and the challenge calendar only;
+/// nothing rollback-relevant reads the clock.
+pub fn unix_now() -> u64 {
+    instant::SystemTime::now()
+        .duration_since(instant::SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+pub(super) fn valid_event_id(value: &str) -> bool {
     !value.is_empty()
         && value.len() <= MAX_EVENT_ID_BYTES
         && value
@@ -277,6 +534,65 @@ fn valid_event_id(value: &str) -> bool {
             .all(|byte| byte.is_ascii_hexdigit() || matches!(byte, b':' | b'-'))
 }
 
+/// v2 added earned achievements; a v1 profile has none yet.
+fn migrate_v1_to_v2(fields: &mut ProfileFields) {
+    fields.entry("achievements".into()).or_default();
+}
+
+/// v3 added the per-mode skill ratings; an older profile starts unrated.
+fn migrate_v2_to_v3(fields: &mut ProfileFields) {
+    for key in ["duel-rating", "lgs-rating"] {
+        fields
+            .entry(key.into())
+            .or_insert_with(|| SkillRating::default().encode());
+    }
+}
+
+/// v4 only changed the encoding to named fields.
+fn migrate_v3_to_v4(_fields: &mut ProfileFields) {}
+
+/// Names the fields of a positional v1–v3 profile, or `None` when the field
+/// count does not match the stated schema.
+fn legacy_fields(version: u8, values: &[&str]) -> Option<ProfileFields> {
+    let expected = match version {
+        1 => 10,
+        2 => 11,
+        _ => POSITIONAL_FIELDS.len(),
+    };
+    (values.len() == expected).then(|| {
+        POSITIONAL_FIELDS
+            .iter()
+            .zip(values)
+            .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+            .collect()
+    })
+}
+
+/// Reads `key=value` fields. A malformed field is skipped and the first
+/// occurrence of a key wins, so one damaged field never costs the others.
+fn named_fields(values: &[&str]) -> ProfileFields {
+    let mut fields = ProfileFields::new();
+    for (key, value) in values.iter().filter_map(|field| field.split_once('=')) {
+        if fields.len() == MAX_PROFILE_FIELDS {
+            break;
+        }
+        if valid_field_key(key) {
+            fields
+                .entry(key.to_owned())
+                .or_insert_with(|| value.to_owned());
+        }
+    }
+    fields
+}
+
+fn valid_field_key(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_FIELD_KEY_BYTES
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-')
+}
+
 pub fn outcome_event_id(
     match_id: MatchId,
     epoch: SessionEpoch,
@@ -293,24 +609,41 @@ pub fn load_persistent_profile(
     mut stored: ResMut<CasualProfile>,
     mut pending: ResMut<PendingPlayerProfile>,
     mut audio: ResMut<AudioConfig>,
+    mut input_delay: ResMut<InputDelaySetting>,
 ) {
-    let loaded = CasualProfile::decode(&storage_load());
-    pending.name = loaded.name.clone();
-    pending.palette_id = loaded.palette_id;
-    pending.cosmetic_id = loaded.equipped_cosmetic;
-    audio.music_volume = loaded.music_volume;
-    audio.sfx_volume = loaded.effects_volume;
-    // Materialize a canonical v1 profile on first run and repair any partially
-    // invalid v1 values that were safely defaulted during decoding.
-    storage_save(&loaded.encode());
+    let mut loaded = CasualProfile::decode(&storage_load());
+    loaded.record_counter_baseline();
+    publish_preferences(&loaded, &mut pending, &mut audio);
+    *input_delay = loaded.input_delay;
+    // Materialize a canonical profile on first run, save an older schema in
+    // its migrated form, and repair any partially invalid values that were
+    // safely defaulted during decoding.
+    storage_save(&mut loaded);
     *stored = loaded;
 }
 
+/// Hands the durable profile's preferences to the systems and egui screens
+/// that edit or send them.
+fn publish_preferences(
+    profile: &CasualProfile,
+    pending: &mut PendingPlayerProfile,
+    audio: &mut AudioConfig,
+) {
+    pending.name = profile.name.clone();
+    pending.palette_id = profile.palette_id;
+    pending.loadout = profile.equipped_cosmetics;
+    pending.ratings = profile.ratings.lobby();
+    audio.music_volume = profile.music_volume;
+    audio.sfx_volume = profile.effects_volume;
+}
+
 /// Pull preference edits made by egui into the durable profile. Progression
-/// counters remain exclusively owned by `award_confirmed_progression`.
+/// counters remain owned by `award_confirmed_progression` and
+/// `apply_profile_import`.
 pub fn sync_persistent_preferences(
     pending: Res<PendingPlayerProfile>,
     audio: Res<AudioConfig>,
+    input_delay: Res<InputDelaySetting>,
     mut stored: ResMut<CasualProfile>,
 ) {
     let name = canonical_name(&pending.name);
@@ -319,33 +652,93 @@ pub fn sync_persistent_preferences(
     } else {
         0
     };
-    let equipped = equipped_or_default(pending.cosmetic_id, stored.unlocked_cosmetics);
+    let equipped = pending.loadout.retain_unlocked(&stored.unlocked_cosmetics);
     let music = finite_clamped_volume(audio.music_volume, stored.music_volume);
     let effects = finite_clamped_volume(audio.sfx_volume, stored.effects_volume);
     if stored.name == name
         && stored.palette_id == palette
-        && stored.equipped_cosmetic == equipped
+        && stored.equipped_cosmetics == equipped
         && stored.music_volume == music
         && stored.effects_volume == effects
+        && stored.input_delay == *input_delay
     {
         return;
     }
     stored.name = name;
     stored.palette_id = palette;
-    stored.equipped_cosmetic = equipped;
+    stored.equipped_cosmetics = equipped;
     stored.music_volume = music;
     stored.effects_volume = effects;
+    stored.input_delay = *input_delay;
     stored.normalize();
-    storage_save(&stored.encode());
+    storage_save(&mut stored);
+}
+
+/// Export and import requests from the settings screen.
+#[derive(Resource, Debug, Default)]
+pub struct ProfileTransfer {
+    /// Code from the most recent export, shown for copying by hand.
+    pub exported: String,
+    /// Code pasted by the player for import.
+    pub import_code: String,
+    pub import_requested: bool,
+}
+
+/// Validates a requested import and merges it into the durable profile.
+pub fn apply_profile_import(
+    mut transfer: ResMut<ProfileTransfer>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
+    mut pending: ResMut<PendingPlayerProfile>,
+    mut audio: ResMut<AudioConfig>,
+    mut toasts: ResMut<Toasts>,
+    mut stored: ResMut<CasualProfile>,
+) {
+    if !transfer.import_requested {
+        return;
+    }
+    transfer.import_requested = false;
+    match decode_code(&transfer.import_code) {
+        Ok(imported) => {
+            stored.merge_import(imported, cosmetics.get(&catalogs));
+            publish_preferences(&stored, &mut pending, &mut audio);
+            storage_save(&mut stored);
+            transfer.import_code.clear();
+            toasts.success("Profile imported.".into());
+        }
+        Err(error) => toasts.error(error.message().into()),
+    }
+}
+
+/// Re-derives unlocks once the cosmetics catalog has loaded, so a changed
+/// threshold or a hand-edited profile is corrected before the first match.
+pub fn refresh_cosmetic_unlocks(
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
+    mut pending: ResMut<PendingPlayerProfile>,
+    mut stored: ResMut<CasualProfile>,
+) {
+    let Some(catalog) = cosmetics.get(&catalogs) else {
+        return;
+    };
+    if stored.refresh_unlocks(catalog) {
+        pending.loadout = stored.equipped_cosmetics;
+        storage_save(&mut stored);
+    }
 }
 
 pub fn award_confirmed_progression(
     session: Res<Session<GgrsConfig>>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
     bootstrap: Option<Res<RoundBootstrap>>,
     local: Option<Res<LocalPlayerHandle>>,
     progress: Res<RoundProgress>,
     scores: Res<Scores>,
     rollback_state: Res<State<RollbackState>>,
+    players: Query<(&Player, Option<&ShieldCharges>), Without<MarkedForDeath>>,
+    mut toasts: ResMut<Toasts>,
+    mut pending: ResMut<PendingPlayerProfile>,
     mut stored: ResMut<CasualProfile>,
 ) {
     // OnExit(InRound) applies the outcome to Scores. Waiting for RoundEnd
@@ -377,11 +770,191 @@ pub fn award_confirmed_progression(
         return;
     };
 
-    let local_won = outcome.point_winners().contains(&local_id);
-    let match_completed = match_winner(scores.entries()).is_some();
+    let winner = match_winner(scores.entries());
+    let stats = RoundStats {
+        mode: bootstrap.mode,
+        won_round: outcome.point_winners().contains(&local_id),
+        eliminations: progress
+            .eliminated
+            .iter()
+            .filter(|entry| entry.eliminated_by == Some(local_id))
+            .count() as u32,
+        // RoundEnd freezes the simulation, so survivors still hold the
+        // charges they had on the resolving frame.
+        shield_left: players.iter().any(|(player, shield)| {
+            player.player_id == local_id && shield.is_some_and(|shield| shield.0 > 0)
+        }),
+        match_completed: winner.is_some(),
+        won_match: winner.is_some_and(|winner| winner.player_id == local_id),
+        best_opponent_score: scores
+            .entries()
+            .iter()
+            .filter(|entry| entry.player_id != local_id)
+            .map(|entry| entry.score)
+            .max()
+            .unwrap_or(0),
+    };
+    let roster: Vec<_> = bootstrap
+        .roster
+        .iter()
+        .map(|entry| entry.player_id)
+        .collect();
+    let eliminated: Vec<_> = progress
+        .eliminated
+        .iter()
+        .map(|entry| (entry.player_id, entry.frame))
+        .collect();
+    let placements = round_placements(
+        &roster,
+        outcome.point_winners(),
+        &eliminated,
+        &progress.disconnected,
+    );
+    let placement = placements
+        .iter()
+        .find(|(player_id, _)| *player_id == local_id)
+        .map_or(0, |(_, placement)| *placement);
+    // Lobby members' ratings are self-reported; an unrated or pre-rating
+    // client counts as a newcomer.
+    let opponents: Vec<_> = placements
+        .iter()
+        .filter(|(player_id, _)| *player_id != local_id)
+        .map(|(player_id, placement)| {
+            let rating = bootstrap
+                .profiles
+                .iter()
+                .find(|profile| profile.player_id == *player_id)
+                .and_then(|profile| profile.rating)
+                .unwrap_or(INITIAL_RATING);
+            (rating, *placement)
+        })
+        .collect();
     let event_id = outcome_event_id(bootstrap.match_id, bootstrap.epoch, bootstrap.round, frame);
-    if stored.award_confirmed_outcome(&event_id, local_won, match_completed) {
-        storage_save(&stored.encode());
+    let Some(earned) = stored.award_confirmed_round(&event_id, &stats, placement, &opponents)
+    else {
+        return;
+    };
+    pending.ratings = stored.ratings.lobby();
+    if winner.is_some() {
+        stored.match_history.record(MatchRecord {
+            event_id,
+            finished_at: unix_now(),
+            mode: bootstrap.mode,
+            local_player: local_id,
+            participants: scores
+                .entries()
+                .iter()
+                .map(|entry| {
+                    let profile = bootstrap
+                        .profiles
+                        .iter()
+                        .find(|profile| profile.player_id == entry.player_id);
+                    MatchParticipant {
+                        player_id: entry.player_id,
+                        name: profile
+                            .map_or_else(|| DEFAULT_NAME.into(), |profile| profile.name.clone()),
+                        palette_id: profile.map_or(0, |profile| profile.palette_id),
+                        score: entry.score,
+                    }
+                })
+                .collect(),
+        });
+    }
+    for definition in earned {
+        toasts.success(format!("🏆 {} — {}", definition.name, definition.description).into());
+    }
+    for challenge in stored.advance_round_challenges(&stats, unix_now()) {
+        toasts.success(challenge_toast(&challenge).into());
+    }
+    announce_new_unlocks(&mut stored, cosmetics.get(&catalogs), &mut toasts);
+    storage_save(&mut stored);
+}
+
+/// Records a finished practice drill once: its personal best and, for a
+/// challenge target run, the challenge. Leaving a run early records nothing.
+pub fn credit_practice_run(
+    run: Option<ResMut<PracticeRun>>,
+    score: Option<Res<PracticeScore>>,
+    cosmetics: Res<CosmeticAssets>,
+    catalogs: Res<Assets<CosmeticCatalog>>,
+    mut toasts: ResMut<Toasts>,
+    mut stored: ResMut<CasualProfile>,
+) {
+    let (Some(mut run), Some(score)) = (run, score) else {
+        return;
+    };
+    if !run.is_finished() || run.recorded {
+        return;
+    }
+    run.recorded = true;
+    let result = run.result(&score);
+    if stored
+        .practice_bests
+        .record(run.drill, run.difficulty, result)
+    {
+        toasts.success(
+            format!(
+                "⭐ New {} ({}) best: {result}",
+                run.drill.label(),
+                run.difficulty.label()
+            )
+            .into(),
+        );
+    }
+    if let Some(challenge_id) = &run.challenge {
+        if let Some(challenge) = stored.record_target_run(challenge_id, score.score, unix_now()) {
+            toasts.success(challenge_toast(&challenge).into());
+            announce_new_unlocks(&mut stored, cosmetics.get(&catalogs), &mut toasts);
+        }
+    }
+    storage_save(&mut stored);
+}
+
+/// Saves a tutorial status from the first-run prompt or a tutorial that just
+/// ended. A status never moves backwards, so skipping after finishing keeps
+/// the tutorial done.
+pub fn save_tutorial_status(
+    mut request: ResMut<TutorialStatusRequest>,
+    mut stored: ResMut<CasualProfile>,
+) {
+    let Some(status) = request.0.take() else {
+        return;
+    };
+    if status <= stored.tutorial {
+        return;
+    }
+    stored.tutorial = status;
+    storage_save(&mut stored);
+}
+
+fn challenge_toast(challenge: &Challenge) -> String {
+    format!(
+        "✔ {} challenge: {} (+{} points)",
+        challenge.period.label(),
+        challenge.objective.description(),
+        challenge.reward
+    )
+}
+
+/// Re-derives unlocks after points or achievements changed and toasts each
+/// newly unlocked cosmetic.
+fn announce_new_unlocks(
+    stored: &mut CasualProfile,
+    catalog: Option<&CosmeticCatalog>,
+    toasts: &mut Toasts,
+) {
+    let Some(catalog) = catalog else {
+        return;
+    };
+    let previous = stored.unlocked_cosmetics.clone();
+    stored.refresh_unlocks(catalog);
+    for slot in CosmeticSlot::ALL {
+        for item in catalog.in_slot(slot) {
+            if stored.is_unlocked(item.id) && !previous.contains(item.id) {
+                toasts
+                    .success(format!("New {}: {}", slot.label().to_lowercase(), item.name).into());
+            }
+        }
     }
 }
 
@@ -392,16 +965,26 @@ fn storage_load() -> String {
 
 #[cfg(not(target_arch = "wasm32"))]
 fn storage_load() -> String {
-    String::new()
+    ProfileStore::platform()
+        .map(|store| store.load())
+        .unwrap_or_default()
 }
 
 #[cfg(target_arch = "wasm32")]
-fn storage_save(value: &str) {
-    profile_storage_save(PROFILE_STORAGE_KEY, value);
+fn storage_save(profile: &mut CasualProfile) {
+    profile_storage_save(PROFILE_STORAGE_KEY, &profile.encode());
 }
 
+/// Saving can merge in progress another running instance stored, which is
+/// why it takes the live profile rather than its encoded text.
 #[cfg(not(target_arch = "wasm32"))]
-fn storage_save(_value: &str) {}
+fn storage_save(profile: &mut CasualProfile) {
+    // Like the browser build, a failed save leaves the game playable and is
+    // retried by the next change.
+    if let Some(Err(error)) = ProfileStore::platform().map(|store| store.save(profile)) {
+        warn!("could not save the casual profile: {error}");
+    }
+}
 
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
@@ -421,6 +1004,13 @@ extern "C" {
 
 #[cfg(test)]
 mod tests {
+    use super::super::{
+        achievements::ACHIEVEMENTS,
+        cosmetics::shipped_catalog,
+        practice::{Difficulty, Drill},
+        profile_code::export_code,
+        session::PlayerId,
+    };
     use super::*;
 
     #[test]
@@ -447,10 +1037,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_input_delay_override_survives_a_reload() {
+        let mut profile = CasualProfile::default();
+        profile.input_delay = InputDelaySetting::Fixed(3);
+        let encoded = profile.encode();
+        assert!(encoded.contains("\tinput-delay=3"));
+        assert_eq!(
+            CasualProfile::decode(&encoded).input_delay,
+            InputDelaySetting::Fixed(3)
+        );
+        let out_of_range = encoded.replace("input-delay=3", "input-delay=99");
+        assert_eq!(
+            CasualProfile::decode(&out_of_range).input_delay,
+            InputDelaySetting::Auto
+        );
+        // Another device's import leaves this device's override alone.
+        let mut imported = CasualProfile::default();
+        imported.input_delay = InputDelaySetting::Fixed(0);
+        profile.merge_import(imported, None);
+        assert_eq!(profile.input_delay, InputDelaySetting::Fixed(3));
+    }
+
     #[test]
     fn invalid_values_are_defaulted_clamped_and_sanitized() {
         let value = "GHOSTIES_PROFILE\t1\t  \n Bad\tNaN\t500\t90\t18446744073709551615\tbad\t4\t255\t99\tbad event,abc:123";
-        let profile = CasualProfile::decode(value);
+        let mut profile = CasualProfile::decode(value);
         assert_eq!(profile.name, "Bad");
         assert_eq!(profile.music_volume, 55.0);
         assert_eq!(profile.effects_volume, 100.0);
@@ -458,8 +1070,11 @@ mod tests {
         assert_eq!(profile.lifetime_points, MAX_COUNTER);
         assert_eq!(profile.matches_played, 0);
         assert_eq!(profile.rounds_played, 4);
-        assert_eq!(profile.unlocked_cosmetics, 0b1111);
-        assert_eq!(profile.equipped_cosmetic, 0);
+        // A v1 mask reads as the first bitset word; id 99 is not in it.
+        assert!((1..=3).all(|id| profile.is_unlocked(id)));
+        assert_eq!(profile.equipped_cosmetics, Loadout::default());
+        profile.refresh_unlocks(&shipped_catalog());
+        assert!(profile.is_unlocked(129));
         assert_eq!(
             profile.processed_outcomes,
             BTreeSet::from(["abc:123".into()])
@@ -467,17 +1082,27 @@ mod tests {
     }
 
     #[test]
-    fn thresholds_are_small_monotonic_and_exact() {
-        assert_eq!(unlocked_mask(0), 0b0001);
-        assert_eq!(unlocked_mask(4), 0b0001);
-        assert_eq!(unlocked_mask(5), 0b0011);
-        assert_eq!(unlocked_mask(11), 0b0011);
-        assert_eq!(unlocked_mask(12), 0b0111);
-        assert_eq!(unlocked_mask(24), 0b0111);
-        assert_eq!(unlocked_mask(25), 0b1111);
-        assert!(COSMETICS
-            .windows(2)
-            .all(|pair| pair[0].required_points < pair[1].required_points));
+    fn unlocks_are_rederived_from_points_against_the_catalog() {
+        let catalog = shipped_catalog();
+        let mut profile = CasualProfile::default();
+        for (points, unlocked) in [(0, false), (4, false), (5, true)] {
+            profile.lifetime_points = points;
+            profile.refresh_unlocks(&catalog);
+            assert_eq!(profile.is_unlocked(1), unlocked);
+        }
+        // Ids past the first bitset word unlock like any other.
+        profile.lifetime_points = 60;
+        assert!(profile.refresh_unlocks(&catalog));
+        assert!(profile.is_unlocked(129));
+        assert!(profile.equip(CosmeticSlot::BulletTint, 129));
+        assert!(!profile.refresh_unlocks(&catalog));
+
+        // A hand-edited unlock set is replaced and the loadout follows it.
+        let mut edited = CasualProfile::decode(&profile.encode());
+        edited.lifetime_points = 8;
+        assert!(edited.refresh_unlocks(&catalog));
+        assert!(!edited.is_unlocked(129));
+        assert_eq!(edited.equipped_cosmetics, Loadout::default());
     }
 
     #[test]
@@ -498,12 +1123,443 @@ mod tests {
         );
     }
 
+    #[test]
+    fn achievements_are_awarded_once_and_unlock_their_cosmetics() {
+        let sweep = RoundStats {
+            mode: GameMode::Duel,
+            won_round: true,
+            eliminations: 1,
+            shield_left: true,
+            match_completed: true,
+            won_match: true,
+            best_opponent_score: 0,
+        };
+        let opponents = [(INITIAL_RATING, 1)];
+        let mut profile = CasualProfile::default();
+        let id = outcome_event_id(MatchId(7), SessionEpoch(0), RoundNumber(2), 300);
+        let earned = profile
+            .award_confirmed_round(&id, &sweep, 0, &opponents)
+            .unwrap();
+        assert_eq!(earned.len(), 4);
+        assert_eq!(
+            profile.award_confirmed_round(&id, &sweep, 0, &opponents),
+            None
+        );
+
+        // A later round only reports what is new.
+        let next = outcome_event_id(MatchId(8), SessionEpoch(0), RoundNumber(0), 90);
+        assert_eq!(
+            profile.award_confirmed_round(&next, &sweep, 0, &opponents),
+            Some(Vec::new())
+        );
+
+        let mut reloaded = CasualProfile::decode(&profile.encode());
+        assert!(reloaded.has_achievement("clean-sweep"));
+        assert_eq!(
+            reloaded.award_confirmed_round(&id, &sweep, 0, &opponents),
+            None
+        );
+        reloaded.refresh_unlocks(&shipped_catalog());
+        assert!(reloaded.is_unlocked(66) && reloaded.is_unlocked(130));
+    }
+
+    #[test]
+    fn v1_profiles_read_as_current_without_achievements_or_ratings() {
+        let v1 = "GHOSTIES_PROFILE\t1\tGhost\t55\t100\t0\t30\t0\t0\t15\t3\tabc:1";
+        let profile = CasualProfile::decode(v1);
+        assert_eq!(profile.lifetime_points, 30);
+        assert!(profile.processed_outcomes.contains("abc:1"));
+        assert!(profile.achievements.is_empty());
+        assert_eq!(profile.ratings, SkillRatings::default());
+        assert!(profile
+            .encode()
+            .starts_with(&format!("GHOSTIES_PROFILE\t{PROFILE_SCHEMA_VERSION}\t")));
+
+        // Field counts must match the stated schema.
+        assert_eq!(
+            CasualProfile::decode(&format!("{v1}\tregular")),
+            CasualProfile::default()
+        );
+        let current = profile.encode().replace(
+            "\tachievements=\t",
+            "\tachievements=regular,Bad Key,future-one\t",
+        );
+        let decoded = CasualProfile::decode(&current);
+        assert!(decoded.has_achievement("regular") && decoded.has_achievement("future-one"));
+        assert_eq!(decoded.achievements.len(), 2);
+    }
+
+    #[test]
+    fn ratings_move_once_per_confirmed_round_and_survive_reload() {
+        let stats = RoundStats {
+            mode: GameMode::Deathmatch,
+            won_round: false,
+            eliminations: 0,
+            shield_left: false,
+            match_completed: false,
+            won_match: false,
+            best_opponent_score: 0,
+        };
+        let mut profile = CasualProfile::default();
+        let id = outcome_event_id(MatchId(9), SessionEpoch(0), RoundNumber(0), 120);
+        let opponents = [(INITIAL_RATING, 0), (INITIAL_RATING, 2)];
+        assert!(profile
+            .award_confirmed_round(&id, &stats, 1, &opponents)
+            .is_some());
+        assert!(profile
+            .award_confirmed_round(&id, &stats, 1, &[(INITIAL_RATING, 2)])
+            .is_none());
+        assert_eq!(profile.ratings.deathmatch.rating, INITIAL_RATING);
+        assert_eq!(profile.ratings.deathmatch.rated_rounds, 1);
+        assert_eq!(profile.ratings.duel, SkillRating::default());
+
+        let next = outcome_event_id(MatchId(9), SessionEpoch(0), RoundNumber(1), 240);
+        profile.award_confirmed_round(&next, &stats, 0, &opponents);
+        assert_eq!(profile.ratings.deathmatch.rating, INITIAL_RATING + 24);
+        let reloaded = CasualProfile::decode(&profile.encode());
+        assert_eq!(reloaded.ratings, profile.ratings);
+        assert_eq!(
+            reloaded.ratings.deathmatch.history(),
+            [INITIAL_RATING, INITIAL_RATING + 24]
+        );
+
+        // A v2 profile has no ratings yet; a malformed rating resets alone.
+        let v2 = "GHOSTIES_PROFILE\t2\tGhost\t55\t100\t0\t30\t0\t0\t15\t3\tabc:1\tregular";
+        assert_eq!(CasualProfile::decode(v2).ratings, SkillRatings::default());
+        let current = profile.encode().replace(
+            &format!("lgs-rating={}", profile.ratings.deathmatch.encode()),
+            "lgs-rating=bad",
+        );
+        let decoded = CasualProfile::decode(&current);
+        assert_eq!(decoded.lifetime_points, profile.lifetime_points);
+        assert_eq!(decoded.ratings.deathmatch, SkillRating::default());
+    }
+
     #[test]
     fn equipped_cosmetic_must_exist_and_be_unlocked() {
-        assert_eq!(equipped_or_default(1, 0b0001), 0);
-        assert_eq!(equipped_or_default(9, 0b1111), 0);
-        assert_eq!(equipped_or_default(2, 0b0111), 2);
-        let locked = "GHOSTIES_PROFILE\t1\tGhost\t55\t100\t0\t4\t0\t0\t15\t3\t";
-        assert_eq!(CasualProfile::decode(locked).equipped_cosmetic, 0);
+        let v1 = "GHOSTIES_PROFILE\t1\tGhost\t55\t100\t0\t30\t0\t0\t15\t3\t";
+        let mut profile = CasualProfile::decode(v1);
+        assert_eq!(profile.equipped_cosmetics.hat, 3);
+        assert!(!profile.equip(CosmeticSlot::Trail, 64));
+
+        let catalog = shipped_catalog();
+        profile.refresh_unlocks(&catalog);
+        assert!(profile.equip(CosmeticSlot::Trail, 64));
+        // A hat in the trail slot or an unknown id never survives a refresh.
+        profile.equipped_cosmetics.set(CosmeticSlot::BulletTint, 2);
+        profile.equipped_cosmetics.set(CosmeticSlot::Hat, 9);
+        profile.refresh_unlocks(&catalog);
+        assert_eq!(
+            profile.equipped_cosmetics,
+            Loadout {
+                hat: 0,
+                trail: 64,
+                bullet: 0,
+            }
+        );
+        assert_eq!(
+            CasualProfile::decode(&profile.encode()).equipped_cosmetics,
+            profile.equipped_cosmetics
+        );
+
+        let locked = "GHOSTIES_PROFILE\t1\tGhost\t55\t100\t0\t4\t0\t0\t1\t3\t";
+        assert_eq!(CasualProfile::decode(locked).equipped_cosmetics.hat, 0);
+    }
+
+    #[test]
+    fn every_positional_schema_migrates_without_losing_fields() {
+        let v1 = "GHOSTIES_PROFILE\t1\tCasper\t40\t80\t2\t120\t4\t30\t15\t3\tabc:1";
+        let v2 = format!("{}\tregular", v1.replacen("\t1\t", "\t2\t", 1));
+        let v3 = format!(
+            "{}\t1016:2:1000,1016\t984:1:984",
+            v2.replacen("\t2\t", "\t3\t", 1)
+        );
+        for (version, value) in [(1, v1.to_owned()), (2, v2), (3, v3)] {
+            let profile = CasualProfile::decode(&value);
+            assert_eq!(profile.name, "Casper", "v{version}");
+            assert_eq!(profile.palette_id, 2, "v{version}");
+            assert_eq!(
+                (
+                    profile.lifetime_points,
+                    profile.matches_played,
+                    profile.rounds_played
+                ),
+                (120, 4, 30),
+                "v{version}"
+            );
+            assert!(profile.processed_outcomes.contains("abc:1"), "v{version}");
+            assert_eq!(profile.has_achievement("regular"), version >= 2);
+            assert_eq!(profile.ratings.duel.rating, [1000, 1000, 1016][version - 1]);
+            assert_eq!(profile.ratings.deathmatch.history().len(), version / 3);
+            let encoded = profile.encode();
+            assert!(encoded.contains("\tpoints=120\t"));
+            assert_eq!(CasualProfile::decode(&encoded), profile);
+        }
+    }
+
+    #[test]
+    fn named_fields_tolerate_damage_and_keep_unknown_fields() {
+        let mut profile = CasualProfile::default();
+        profile.lifetime_points = 77;
+        assert!(profile.award_confirmed_outcome("ab:01", true, false));
+        let encoded = profile.encode();
+
+        // Order does not matter, the first duplicate wins, and a damaged
+        // field only costs itself.
+        let mut fields: Vec<_> = encoded.split('\t').collect();
+        fields[2..].reverse();
+        let shuffled = fields.join("\t");
+        assert_eq!(CasualProfile::decode(&shuffled), profile);
+        let damaged = encoded
+            .replace("\tmusic=55\t", "\tmusic\t")
+            .replace("\tpoints=", "\tpoints=90\tpoints=");
+        let decoded = CasualProfile::decode(&damaged);
+        assert_eq!(decoded.lifetime_points, 90);
+        assert_eq!(decoded.music_volume, 55.0);
+        assert!(decoded.processed_outcomes.contains("ab:01"));
+
+        // A newer build's profile reads best-effort and keeps its extras.
+        let future = format!(
+            "{}\tseason-pass=gold\tBad Key=1",
+            encoded.replacen("\t4\t", "\t9\t", 1)
+        );
+        let decoded = CasualProfile::decode(&future);
+        assert_eq!(decoded.lifetime_points, 82);
+        assert_eq!(
+            decoded.unknown_fields,
+            ProfileFields::from([("season-pass".into(), "gold".into())])
+        );
+        let saved = decoded.encode();
+        assert!(saved.starts_with(&format!("GHOSTIES_PROFILE\t{PROFILE_SCHEMA_VERSION}\t")));
+        assert!(saved.ends_with("\tseason-pass=gold"));
+        assert_eq!(CasualProfile::decode(&saved), decoded);
+    }
+
+    #[test]
+    fn challenges_pay_their_reward_once_and_survive_reload() {
+        // 2023-11-14, a Tuesday.
+        let now = 1_700_000_000;
+        let active = active_challenges(now);
+        let mut stats = RoundStats {
+            mode: GameMode::Deathmatch,
+            won_round: true,
+            eliminations: 5,
+            shield_left: false,
+            match_completed: true,
+            won_match: true,
+            best_opponent_score: 0,
+        };
+        let mut profile = CasualProfile::default();
+        let mut rewarded = 0;
+        for mode in [GameMode::Duel, GameMode::Deathmatch].repeat(4) {
+            stats.mode = mode;
+            for challenge in profile.advance_round_challenges(&stats, now) {
+                rewarded += challenge.reward;
+            }
+        }
+        // Four winning rounds in each mode meet any daily round objective.
+        assert!(profile.challenges.is_complete(&active[0]));
+        assert_eq!(profile.lifetime_points, rewarded);
+
+        let run = &active[1];
+        let goal = run.objective.goal();
+        assert!(profile.record_target_run(&run.id, goal - 1, now).is_none());
+        assert_eq!(
+            profile.record_target_run(&run.id, goal, now).as_ref(),
+            Some(run)
+        );
+        assert!(profile.record_target_run(&run.id, goal + 9, now).is_none());
+        assert_eq!(profile.lifetime_points, rewarded + run.reward);
+
+        let reloaded = CasualProfile::decode(&profile.encode());
+        assert_eq!(reloaded.challenges, profile.challenges);
+        // Tomorrow the run is a different challenge and yesterday's no longer pays.
+        let tomorrow = now + 86_400;
+        let mut later = reloaded.clone();
+        assert!(later.record_target_run(&run.id, goal, tomorrow).is_none());
+        assert!(later.advance_round_challenges(&stats, tomorrow).len() <= 1);
+        assert_eq!(later.challenges.get(&active[0].id), 0);
+    }
+
+    #[test]
+    fn imports_merge_without_double_counting_or_granting_unlocks() {
+        let catalog = shipped_catalog();
+        let shared = outcome_event_id(MatchId(1), SessionEpoch(0), RoundNumber(0), 60);
+        let mut local = CasualProfile::default();
+        local.lifetime_points = 20;
+        local.rounds_played = 4;
+        assert!(local.award_confirmed_outcome(&shared, true, false));
+        local.refresh_unlocks(&catalog);
+        local
+            .practice_bests
+            .record(Drill::Reflex, Difficulty::Hard, 80);
+
+        let mut imported = CasualProfile::default();
+        imported.name = "Traveller".into();
+        imported.palette_id = 3;
+        imported.lifetime_points = 9;
+        assert!(imported.award_confirmed_outcome(&shared, true, false));
+        imported.achievements.insert("clean-sweep".into());
+        imported.ratings.duel.apply(0, &[(INITIAL_RATING, 1)]);
+        // A hand-edited code may claim every unlock; none of them count.
+        imported.unlocked_cosmetics = UnlockSet::decode("18446744073709551615.3");
+        imported
+            .equipped_cosmetics
+            .set(CosmeticSlot::BulletTint, 129);
+        imported.match_history.record(MatchRecord {
+            event_id: shared.clone(),
+            finished_at: 1_700_000_000,
+            mode: GameMode::Duel,
+            local_player: PlayerId(1),
+            participants: vec![
+                MatchParticipant {
+                    player_id: PlayerId(1),
+                    name: "Traveller".into(),
+                    palette_id: 3,
+                    score: 3,
+                },
+                MatchParticipant {
+                    player_id: PlayerId(2),
+                    name: "Rival, Esq.".into(),
+                    palette_id: 0,
+                    score: 1,
+                },
+            ],
+        });
+        imported
+            .practice_bests
+            .record(Drill::Reflex, Difficulty::Hard, 60);
+        imported
+            .practice_bests
+            .record(Drill::Accuracy, Difficulty::Easy, 14);
+        imported.tutorial = TutorialStatus::Completed;
+
+        local.merge_import(imported, Some(&catalog));
+        assert_eq!(local.name, "Traveller");
+        assert_eq!(local.palette_id, 3);
+        assert_eq!(local.lifetime_points, 25);
+        assert_eq!(local.rounds_played, 5);
+        assert_eq!(local.ratings.duel.rated_rounds, 1);
+        // The shared round cannot pay out again on either side of the merge.
+        assert!(!local.award_confirmed_outcome(&shared, true, false));
+        // Unlocks follow the merged points only.
+        assert!(local.is_unlocked(3));
+        assert!(!local.is_unlocked(129) && !local.is_unlocked(66) && !local.is_unlocked(130));
+        assert_eq!(local.equipped_cosmetics.bullet, 0);
+        // Match history travels with the profile.
+        assert!(local.match_history.find(&shared).is_some());
+        let reloaded = CasualProfile::decode(&local.encode());
+        assert_eq!(reloaded.match_history, local.match_history);
+        // Personal bests keep the better result of either profile.
+        assert_eq!(
+            local.practice_bests.get(Drill::Reflex, Difficulty::Hard),
+            80
+        );
+        assert_eq!(
+            local.practice_bests.get(Drill::Accuracy, Difficulty::Easy),
+            14
+        );
+        assert_eq!(reloaded.practice_bests, local.practice_bests);
+        // A tutorial finished on either device counts as done.
+        assert_eq!(reloaded.tutorial, TutorialStatus::Completed);
+
+        // Before the catalog loads, the import's unlock claims are ignored.
+        let mut early = CasualProfile::default();
+        let mut claimed = CasualProfile::default();
+        claimed.unlocked_cosmetics = UnlockSet::decode("15");
+        claimed.equipped_cosmetics.set(CosmeticSlot::Hat, 3);
+        early.merge_import(claimed, None);
+        assert!(!early.is_unlocked(3));
+        assert_eq!(early.equipped_cosmetics, Loadout::default());
+    }
+
+    #[test]
+    fn imports_claiming_achievements_unlock_nothing_extra() {
+        let catalog = shipped_catalog();
+        let mut local = CasualProfile::default();
+        local.refresh_unlocks(&catalog);
+        let before = local.unlocked_cosmetics.clone();
+
+        let mut claimed = CasualProfile::default();
+        claimed.achievements = ACHIEVEMENTS
+            .iter()
+            .map(|definition| definition.key.to_owned())
+            .collect();
+        let code = export_code(&claimed);
+        local.merge_import(decode_code(&code).unwrap(), Some(&catalog));
+
+        assert!(ACHIEVEMENTS
+            .iter()
+            .all(|definition| !local.has_achievement(definition.key)));
+        assert_eq!(local.unlocked_cosmetics, before);
+        assert!(!local.is_unlocked(66) && !local.is_unlocked(130));
+    }
+
+    /// A small deterministic generator, so the fuzz cases are reproducible
+    /// without a fuzzing dependency.
+    struct Fuzz(u64);
+
+    impl Fuzz {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next() % bound as u64) as usize
+        }
+
+        fn text(&mut self, max_chars: usize) -> String {
+            const ALPHABET: [char; 16] = [
+                'a', 'Z', '0', '9', '-', ':', ',', '.', '=', '\t', ' ', '\n', 'é', '👻', '\u{7f}',
+                '\u{0}',
+            ];
+            (0..self.below(max_chars + 1))
+                .map(|_| ALPHABET[self.below(ALPHABET.len())])
+                .collect()
+        }
+    }
+
+    #[test]
+    fn fuzzed_profiles_never_panic_or_lose_points() {
+        let mut fuzz = Fuzz(0x9e37_79b9_7f4a_7c15);
+        for case in 0..500 {
+            let mut profile = CasualProfile::default();
+            profile.name = fuzz.text(30);
+            profile.lifetime_points = fuzz.next() % (MAX_COUNTER + 1);
+            for round in 0..fuzz.below(4) {
+                let id =
+                    outcome_event_id(MatchId(case), SessionEpoch(0), RoundNumber(round as u32), 1);
+                profile.award_confirmed_outcome(&id, fuzz.below(2) == 0, false);
+            }
+            let points = profile.lifetime_points;
+            let encoded = profile.encode();
+            let decoded = CasualProfile::decode(&encoded);
+            assert_eq!(decoded.lifetime_points, points);
+            assert_eq!(CasualProfile::decode(&decoded.encode()), decoded);
+
+            // Damage everything but the points field; they must survive.
+            let mut fields: Vec<String> = encoded.split('\t').map(str::to_owned).collect();
+            for _ in 0..fuzz.below(4) {
+                let index = 2 + fuzz.below(fields.len() - 2);
+                if !fields[index].starts_with("points=") {
+                    fields[index] = fuzz.text(12);
+                }
+            }
+            for _ in 0..fuzz.below(3) {
+                let index = 2 + fuzz.below(fields.len() - 1);
+                fields.insert(index, fuzz.text(12).replace('\t', ""));
+            }
+            let damaged = CasualProfile::decode(&fields.join("\t"));
+            assert_eq!(damaged.lifetime_points, points, "case {case}");
+
+            // Arbitrary truncations and raw noise must never panic.
+            let cut = fuzz.below(encoded.chars().count() + 1);
+            CasualProfile::decode(&encoded.chars().take(cut).collect::<String>());
+            CasualProfile::decode(&format!("GHOSTIES_PROFILE\t{}", fuzz.text(40)));
+            CasualProfile::decode(&fuzz.text(60));
+        }
     }
 }
@@ -0,0 +1,364 @@
+//! Client-side skill rating per game mode.
+//!
+//! A rating moves only when `CasualProfile` first records a confirmed round's
+//! stable event ID, so it is exactly as rollback- and reload-safe as casual
+//! points. Opponent ratings are self-reported through the lobby and only ever
+//! move the local number; they are a display and future matchmaking hint, not
+//! an authority.
+
+use super::session::{GameMode, PlayerId};
+
+pub const INITIAL_RATING: u16 = 1000;
+pub const MAX_RATING: u16 = 4000;
+/// Ratings kept per mode for the profile's history.
+pub const MAX_RATING_HISTORY: usize = 50;
+const MAX_RATED_ROUNDS: u32 = 999_999_999;
+/// Rounds rated with the larger K factor so a new profile settles quickly.
+const PROVISIONAL_ROUNDS: u32 = 10;
+const PROVISIONAL_K: f64 = 48.0;
+const ESTABLISHED_K: f64 = 24.0;
+
+/// Probability that `rating` beats `opponent` under the Elo logistic curve.
+pub fn expected_score(rating: u16, opponent: u16) -> f64 {
+    1.0 / (1.0 + 10f64.powf((f64::from(opponent) - f64::from(rating)) / 400.0))
+}
+
+/// Rating change for one round from the local `placement` against every
+/// opponent's `(rating, placement)`. A lower placement finished better and an
+/// equal one is a draw. Pairwise results are averaged, so a Duel is classic
+/// Elo and an eight-ghost round moves a rating no faster than a Duel.
+pub fn multiplayer_delta(rating: u16, placement: u32, opponents: &[(u16, u32)], k: f64) -> i32 {
+    if opponents.is_empty() {
+        return 0;
+    }
+    let total: f64 = opponents
+        .iter()
+        .map(|&(opponent, their_placement)| {
+            let actual = match placement.cmp(&their_placement) {
+                std::cmp::Ordering::Less => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Greater => 0.0,
+            };
+            actual - expected_score(rating, opponent)
+        })
+        .sum();
+    (k * total / opponents.len() as f64).round() as i32
+}
+
+/// Finishing placement of every roster member, 0 being best. Point winners
+/// place first, then survivors, then eliminated ghosts from the latest
+/// elimination back; a ghost that disconnected places last. Ghosts tied on
+/// all of those share a placement.
+pub fn round_placements(
+    roster: &[PlayerId],
+    point_winners: &[PlayerId],
+    eliminated: &[(PlayerId, u32)],
+    disconnected: &[PlayerId],
+) -> Vec<(PlayerId, u32)> {
+    let standing = |player_id: &PlayerId| {
+        let frame = eliminated
+            .iter()
+            .find(|(eliminated, _)| eliminated == player_id)
+            .map(|(_, frame)| *frame);
+        let class = if disconnected.contains(player_id) {
+            0
+        } else if frame.is_some() {
+            1
+        } else {
+            2
+        };
+        (
+            point_winners.contains(player_id),
+            class,
+            frame.unwrap_or(u32::MAX),
+        )
+    };
+    roster
+        .iter()
+        .map(|player_id| {
+            let own = standing(player_id);
+            let ahead = roster.iter().filter(|other| standing(other) > own).count() as u32;
+            (*player_id, ahead)
+        })
+        .collect()
+}
+
+/// Drops the oldest entries beyond `limit`.
+pub fn prune_history(history: &mut Vec<u16>, limit: usize) {
+    if history.len() > limit {
+        history.drain(..history.len() - limit);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillRating {
+    pub rating: u16,
+    pub rated_rounds: u32,
+    history: Vec<u16>,
+}
+
+impl Default for SkillRating {
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            rated_rounds: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl SkillRating {
+    /// Ratings after each recent rated round, oldest first.
+    pub fn history(&self) -> &[u16] {
+        &self.history
+    }
+
+    pub fn is_provisional(&self) -> bool {
+        self.rated_rounds < PROVISIONAL_ROUNDS
+    }
+
+    /// Rates one round and records the result. Returns the applied change.
+    pub fn apply(&mut self, placement: u32, opponents: &[(u16, u32)]) -> i32 {
+        if opponents.is_empty() {
+            return 0;
+        }
+        let k = if self.is_provisional() {
+            PROVISIONAL_K
+        } else {
+            ESTABLISHED_K
+        };
+        let delta = multiplayer_delta(self.rating, placement, opponents, k);
+        let rating = (i32::from(self.rating) + delta).clamp(0, i32::from(MAX_RATING)) as u16;
+        let applied = i32::from(rating) - i32::from(self.rating);
+        self.rating = rating;
+        self.rated_rounds = self.rated_rounds.saturating_add(1).min(MAX_RATED_ROUNDS);
+        self.history.push(rating);
+        prune_history(&mut self.history, MAX_RATING_HISTORY);
+        applied
+    }
+
+    /// `rating:rounds:history`, history comma-separated oldest first.
+    pub fn encode(&self) -> String {
+        let history = self
+            .history
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{}:{}:{history}", self.rating, self.rated_rounds)
+    }
+
+    /// A malformed value reads as an unrated profile; out-of-range history
+    /// entries are dropped rather than failing the whole field.
+    pub fn decode(value: &str) -> Self {
+        let parts: Vec<_> = value.split(':').collect();
+        let [rating, rounds, history] = parts[..] else {
+            return Self::default();
+        };
+        let (Some(rating), Ok(rated_rounds)) = (parse_rating(rating), rounds.parse::<u32>()) else {
+            return Self::default();
+        };
+        let mut history: Vec<_> = history.split(',').filter_map(parse_rating).collect();
+        prune_history(&mut history, MAX_RATING_HISTORY);
+        Self {
+            rating,
+            rated_rounds: rated_rounds.min(MAX_RATED_ROUNDS),
+            history,
+        }
+    }
+}
+
+fn parse_rating(value: &str) -> Option<u16> {
+    value
+        .parse::<u16>()
+        .ok()
+        .filter(|rating| *rating <= MAX_RATING)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillRatings {
+    pub duel: SkillRating,
+    pub deathmatch: SkillRating,
+}
+
+impl SkillRatings {
+    pub fn get(&self, mode: GameMode) -> &SkillRating {
+        match mode {
+            GameMode::Duel => &self.duel,
+            GameMode::Deathmatch => &self.deathmatch,
+        }
+    }
+
+    pub fn get_mut(&mut self, mode: GameMode) -> &mut SkillRating {
+        match mode {
+            GameMode::Duel => &mut self.duel,
+            GameMode::Deathmatch => &mut self.deathmatch,
+        }
+    }
+
+    pub fn lobby(&self) -> LobbyRatings {
+        LobbyRatings {
+            duel: self.duel.rating,
+            deathmatch: self.deathmatch.rating,
+        }
+    }
+}
+
+/// The current rating per mode as shared with lobby members. A public queue
+/// may place the player in either mode, so both travel with the profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LobbyRatings {
+    pub duel: u16,
+    pub deathmatch: u16,
+}
+
+impl Default for LobbyRatings {
+    fn default() -> Self {
+        Self {
+            duel: INITIAL_RATING,
+            deathmatch: INITIAL_RATING,
+        }
+    }
+}
+
+impl LobbyRatings {
+    pub fn get(&self, mode: GameMode) -> u16 {
+        match mode {
+            GameMode::Duel => self.duel,
+            GameMode::Deathmatch => self.deathmatch,
+        }
+    }
+
+    /// `duel.deathmatch`, the form the lobby service validates and echoes.
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.duel, self.deathmatch)
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let (duel, deathmatch) = value.split_once('.')?;
+        Some(Self {
+            duel: parse_rating(duel)?,
+            deathmatch: parse_rating(deathmatch)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duel_delta_is_classic_elo() {
+        assert_eq!(expected_score(1000, 1000), 0.5);
+        assert!((expected_score(1400, 1000) - 10.0 / 11.0).abs() < 1e-9);
+        assert_eq!(multiplayer_delta(1000, 0, &[(1000, 1)], 32.0), 16);
+        assert_eq!(multiplayer_delta(1000, 1, &[(1000, 0)], 32.0), -16);
+        assert_eq!(multiplayer_delta(1000, 0, &[(1000, 0)], 32.0), 0);
+        // An upset moves more than an expected win.
+        assert_eq!(multiplayer_delta(1000, 0, &[(1400, 1)], 32.0), 29);
+        assert_eq!(multiplayer_delta(1400, 0, &[(1000, 1)], 32.0), 3);
+        assert_eq!(multiplayer_delta(1000, 0, &[], 32.0), 0);
+    }
+
+    #[test]
+    fn multiplayer_delta_averages_pairwise_results() {
+        let opponents = [(1000, 1), (1000, 2), (1000, 3)];
+        assert_eq!(multiplayer_delta(1000, 0, &opponents, 32.0), 16);
+        assert_eq!(
+            multiplayer_delta(1000, 3, &[(1000, 0), (1000, 1), (1000, 2)], 32.0),
+            -16
+        );
+        // Beating two and losing to one at equal ratings nets a third.
+        assert_eq!(
+            multiplayer_delta(1000, 1, &[(1000, 0), (1000, 2), (1000, 3)], 32.0),
+            5
+        );
+        // A whole lobby of equals sums to roughly zero.
+        let placements = [0, 1, 2, 3];
+        let total: i32 = placements
+            .iter()
+            .map(|own| {
+                let others: Vec<_> = placements
+                    .iter()
+                    .filter(|other| *other != own)
+                    .map(|other| (1000, *other))
+                    .collect();
+                multiplayer_delta(1000, *own, &others, 32.0)
+            })
+            .sum();
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn placements_follow_elimination_order() {
+        let (a, b, c, d) = (PlayerId(1), PlayerId(2), PlayerId(3), PlayerId(4));
+        let roster = [a, b, c, d];
+        assert_eq!(
+            round_placements(&roster, &[c], &[(a, 40), (b, 90), (d, 90)], &[]),
+            [(a, 3), (b, 1), (c, 0), (d, 1)]
+        );
+        // A point winner outranks a survivor at time up, and a disconnect
+        // places below every elimination.
+        assert_eq!(
+            round_placements(&roster, &[a], &[(b, 10)], &[d]),
+            [(a, 0), (b, 2), (c, 1), (d, 3)]
+        );
+        // A double knockout in a Duel is a draw.
+        assert_eq!(
+            round_placements(&[a, b], &[a, b], &[(a, 7), (b, 7)], &[]),
+            [(a, 0), (b, 0)]
+        );
+    }
+
+    #[test]
+    fn history_is_pruned_to_the_most_recent_ratings() {
+        let mut history: Vec<u16> = (0..10).collect();
+        prune_history(&mut history, 4);
+        assert_eq!(history, [6, 7, 8, 9]);
+        prune_history(&mut history, 8);
+        assert_eq!(history, [6, 7, 8, 9]);
+
+        let mut rating = SkillRating::default();
+        for _ in 0..MAX_RATING_HISTORY + 5 {
+            rating.apply(0, &[(INITIAL_RATING, 1)]);
+        }
+        assert_eq!(rating.history().len(), MAX_RATING_HISTORY);
+        assert_eq!(rating.history().last(), Some(&rating.rating));
+        assert_eq!(rating.rated_rounds, MAX_RATING_HISTORY as u32 + 5);
+        assert!(!rating.is_provisional());
+    }
+
+    #[test]
+    fn ratings_round_trip_and_reject_malformed_values() {
+        let mut rating = SkillRating::default();
+        assert_eq!(rating.apply(0, &[(1000, 1)]), 24);
+        assert_eq!(rating.apply(1, &[(1000, 0), (1000, 0)]), -26);
+        assert_eq!(SkillRating::decode(&rating.encode()), rating);
+        assert_eq!(SkillRating::decode("1000:0:"), SkillRating::default());
+        for malformed in ["", "1000", "4001:0:", "x:0:", "1000:-1:", "1:2:3:4"] {
+            assert_eq!(SkillRating::decode(malformed), SkillRating::default());
+        }
+        assert_eq!(
+            SkillRating::decode("1010:3:990,9000,x,1010").history(),
+            [990, 1010]
+        );
+        // Ratings stay within the storable range.
+        let mut floor = SkillRating {
+            rating: 3,
+            ..SkillRating::default()
+        };
+        assert_eq!(floor.apply(1, &[(3, 0)]), -3);
+        assert_eq!(floor.rating, 0);
+
+        let lobby = LobbyRatings {
+            duel: 1012,
+            deathmatch: 987,
+        };
+        assert_eq!(lobby.encode(), "1012.987");
+        assert_eq!(LobbyRatings::decode("1012.987"), Some(lobby));
+        assert_eq!(lobby.get(GameMode::Deathmatch), 987);
+        for malformed in ["", "1012", "1012.987.1", "5000.1000", "a.b"] {
+            assert_eq!(LobbyRatings::decode(malformed), None);
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use bevy::prelude::*;
+use bevy_ggrs::Session;
+
+use crate::cloudflare_net::CloudflareSocket;
+
+use super::{
+    components::{
+        BulletReady, EquippedWeapon, Health, HealthPickup, HitInvulnerability, MarkedForDeath,
+        MoveDir, PhasePickup, Phasing, PickupCharging, Player, Respawning, ShieldCharges,
+        ShieldPickup, SpeedBoost, SpeedPickup, Stocks, Weapon, WeaponPickup,
+    },
+    ggrs_framecount::GGFrameCount,
+    map::{damaged_walls, CellType, Map, WallDamage},
+    networking::GgrsConfig,
+    player::{starting_stocks, MAX_HEALTH, MAX_SHIELD_CHARGES},
+    round_clock::round_elapsed,
+    session::{GameMode, MatchRules, PlayerId, RoundBootstrap},
+    RoundProgress, MAP_SIZE,
+};
+
+const SNAPSHOT_VERSION: &str = "v2";
+/// Mirrors the lobby service's `resume_snapshot` bound.
+pub const MAX_SNAPSHOT_LEN: usize = 2048;
+/// Confirmed frames lag the predicted frame by at most the GGRS prediction
+/// window, so a short ring always covers the newest confirmed frame.
+const HISTORY_LEN: usize = 64;
+/// Wire order of weapon kinds; a snapshot stores the index.
+const WEAPONS: [Weapon; 4] = [
+    Weapon::Blaster,
+    Weapon::Spread,
+    Weapon::Ricochet,
+    Weapon::Heavy,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResumePlayer {
+    pub player_id: PlayerId,
+    pub position: Vec2,
+    pub move_dir: Vec2,
+    pub bullet_ready: u8,
+    pub shield_charges: u8,
+    pub speed_frames: u16,
+    /// Zero when the round runs without the multi-hit health rule.
+    pub health: u8,
+    pub invulnerable_frames: u8,
+    pub weapon: Weapon,
+    /// Zero while the ghost fires the default blaster.
+    pub weapon_frames: u16,
+    pub phase_frames: u16,
+    /// Zero when the round runs without the stock rule.
+    pub stocks: u8,
+    /// Frames left before a ghost that lost a stock is back in play.
+    pub respawn_frames: u16,
+}
+
+/// In-round state a survivor shares when a reloaded roster member rejoins.
+/// Bullets in flight and pending explosions are deliberately not carried: the
+/// resumed round restarts from live ghosts, pickups, wall damage and
+/// eliminations only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumeSnapshot {
+    pub frame: u32,
+    /// Frames the round had played, which restores its clock.
+    pub elapsed: u32,
+    /// Side of the arena still open after overtime closed its outer rings.
+    pub active_size: usize,
+    pub players: Vec<ResumePlayer>,
+    pub eliminated: Vec<PlayerId>,
+    /// Collected pickups with the frames left until they respawn.
+    pub charging: Vec<((u16, u16), u16)>,
+    /// Hits taken by cracked wall cells; crumbled walls carry their last hit.
+    pub walls: Vec<((u16, u16), u8)>,
+}
+
+impl ResumeSnapshot {
+    /// Compact lowercase text so the Worker can relay it as an opaque bounded
+    /// string. Floats travel as raw bits, keeping every peer bit-identical.
+    pub fn encode(&self) -> String {
+        let players = self
+            .players
+            .iter()
+            .map(|player| {
+                format!(
+                    "{:032x}:{:x},{:x},{:x},{:x},{},{},{},{},{},{},{},{},{},{}",
+                    player.player_id.0,
+                    player.position.x.to_bits(),
+                    player.position.y.to_bits(),
+                    player.move_dir.x.to_bits(),
+                    player.move_dir.y.to_bits(),
+                    player.bullet_ready,
+                    player.shield_charges,
+                    player.speed_frames,
+                    player.health,
+                    player.invulnerable_frames,
+                    WEAPONS
+                        .iter()
+                        .position(|weapon| *weapon == player.weapon)
+                        .unwrap_or(0),
+                    player.weapon_frames,
+                    player.phase_frames,
+                    player.stocks,
+                    player.respawn_frames,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        let eliminated = self
+            .eliminated
+            .iter()
+            .map(|player_id| format!("{:032x}", player_id.0))
+            .collect::<Vec<_>>()
+            .join(".");
+        let charging = self
+            .charging
+            .iter()
+            .map(|((x, y), frames_left)| format!("{x}:{y}:{frames_left}"))
+            .collect::<Vec<_>>()
+            .join(".");
+        let walls = self
+            .walls
+            .iter()
+            .map(|((x, y), hits)| format!("{x}:{y}:{hits}"))
+            .collect::<Vec<_>>()
+            .join(".");
+        format!(
+            "{SNAPSHOT_VERSION};{};{};{};{players};{eliminated};{charging};{walls}",
+            self.frame, self.elapsed, self.active_size
+        )
+    }
+
+    /// Strict inverse of `encode`. Every roster member must appear exactly once
+    /// as live or eliminated, at least two ghosts must still be alive, and each
+    /// ghost must stand inside the open arena with no more health, stocks or
+    /// shield charges than the match rules allow.
+    pub fn decode(
+        value: &str,
+        roster: &[PlayerId],
+        rules: MatchRules,
+        mode: GameMode,
+    ) -> Option<Self> {
+        if value.len() > MAX_SNAPSHOT_LEN {
+            return None;
+        }
+        let mut sections = value.split(';');
+        if sections.next()? != SNAPSHOT_VERSION {
+            return None;
+        }
+        let frame = sections.next()?.parse().ok()?;
+        let elapsed = sections.next()?.parse().ok()?;
+        let active_size = sections
+            .next()?
+            .parse::<usize>()
+            .ok()
+            .filter(|size| size % 2 == 1 && *size <= MAP_SIZE)?;
+        let players = list(sections.next()?)
+            .map(decode_player)
+            .collect::<Option<Vec<_>>>()?;
+        let eliminated = list(sections.next()?)
+            .map(decode_player_id)
+            .collect::<Option<Vec<_>>>()?;
+        let charging = list(sections.next()?)
+            .map(|pickup| {
+                let (cell, frames_left) = pickup.rsplit_once(':')?;
+                Some((decode_cell(cell)?, frames_left.parse().ok()?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let walls = list(sections.next()?)
+            .map(|wall| {
+                let (cell, hits) = wall.rsplit_once(':')?;
+                let hits = hits.parse::<u8>().ok().filter(|hits| *hits > 0)?;
+                Some((decode_cell(cell)?, hits))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if sections.next().is_some()
+            || players.len() < 2
+            || !players
+                .iter()
+                .all(|player| player.within_limits(rules, mode, active_size))
+        {
+            return None;
+        }
+        let mut listed: Vec<_> = players
+            .iter()
+            .map(|player| player.player_id)
+            .chain(eliminated.iter().copied())
+            .collect();
+        listed.sort();
+        let mut expected = roster.to_vec();
+        expected.sort();
+        (listed == expected).then_some(Self {
+            frame,
+            elapsed,
+            active_size,
+            players,
+            eliminated,
+            charging,
+            walls,
+        })
+    }
+
+    pub fn player(&self, player_id: PlayerId) -> Option<&ResumePlayer> {
+        self.players
+            .iter()
+            .find(|player| player.player_id == player_id)
+    }
+}
+
+impl ResumePlayer {
+    fn within_limits(&self, rules: MatchRules, mode: GameMode, active_size: usize) -> bool {
+        let max_health = if rules.multi_hit_health {
+            MAX_HEALTH
+        } else {
+            0
+        };
+        let stocks_in_range = match starting_stocks(rules, mode) {
+            Some(max) => (1..=max).contains(&self.stocks),
+            None => self.stocks == 0,
+        };
+        let half_arena = active_size as f32 / 2.;
+        self.health <= max_health
+            && stocks_in_range
+            && self.shield_charges <= MAX_SHIELD_CHARGES
+            && self.position.x.abs() <= half_arena
+            && self.position.y.abs() <= half_arena
+    }
+}
+
+fn list(section: &str) -> impl Iterator<Item = &str> {
+    section.split('.').filter(|item| !item.is_empty())
+}
+
+fn decode_cell(value: &str) -> Option<(u16, u16)> {
+    let (x, y) = value.split_once(':')?;
+    let cell = (x.parse::<u16>().ok()?, y.parse::<u16>().ok()?);
+    ((cell.0 as usize) < MAP_SIZE && (cell.1 as usize) < MAP_SIZE).then_some(cell)
+}
+
+fn decode_player_id(value: &str) -> Option<PlayerId> {
+    if value.len() != 32 || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    u128::from_str_radix(value, 16).ok().map(PlayerId)
+}
+
+fn decode_player(value: &str) -> Option<ResumePlayer> {
+    let (player_id, fields) = value.split_once(':')?;
+    let fields: Vec<_> = fields.split(',').collect();
+    let [x, y, dx, dy, ready, shield, speed, health, invulnerable, weapon, weapon_frames, phase, stocks, respawn] =
+        fields.as_slice()
+    else {
+        return None;
+    };
+    let float = |bits: &str| {
+        u32::from_str_radix(bits, 16)
+            .ok()
+            .map(f32::from_bits)
+            .filter(|value| value.is_finite())
+    };
+    Some(ResumePlayer {
+        player_id: decode_player_id(player_id)?,
+        position: Vec2::new(float(x)?, float(y)?),
+        move_dir: Vec2::new(float(dx)?, float(dy)?),
+        bullet_ready: ready.parse().ok()?,
+        shield_charges: shield.parse().ok()?,
+        speed_frames: speed.parse().ok()?,
+        health: health.parse().ok()?,
+        invulnerable_frames: invulnerable.parse().ok()?,
+        weapon: *WEAPONS.get(weapon.parse::<usize>().ok()?)?,
+        weapon_frames: weapon_frames.parse().ok()?,
+        phase_frames: phase.parse().ok()?,
+        stocks: stocks.parse().ok()?,
+        respawn_frames: respawn.parse().ok()?,
+    })
+}
+
+/// Non-rollback ring of recent in-round states keyed by `GGFrameCount`.
+/// Re-simulating a frame replaces it and everything after it, so entries
+/// always describe the latest simulation of their frame.
+#[derive(Resource, Default)]
+pub struct ResumeHistory(Vec<ResumeSnapshot>);
+
+impl ResumeHistory {
+    fn record(&mut self, snapshot: ResumeSnapshot) {
+        self.0.retain(|entry| entry.frame < snapshot.frame);
+        if self.0.len() == HISTORY_LEN {
+            self.0.remove(0);
+        }
+        self.0.push(snapshot);
+    }
+
+    fn at_or_before(&self, frame: i64) -> Option<&ResumeSnapshot> {
+        self.0
+            .iter()
+            .rev()
+            .find(|entry| i64::from(entry.frame) <= frame)
+    }
+}
+
+/// Snapshot from the lobby start that continues an interrupted round. Applied
+/// only on the first in-round frame of the session it was installed with.
+#[derive(Resource, Default)]
+pub struct RoundResume(pub Option<ResumeSnapshot>);
+
+impl RoundResume {
+    pub fn at_round_start(&self, frame: &GGFrameCount) -> Option<&ResumeSnapshot> {
+        self.0.as_ref().filter(|_| frame.frame == 0)
+    }
+}
+
+pub fn record_resume_history(
+    mut history: ResMut<ResumeHistory>,
+    frame: Res<GGFrameCount>,
+    progress: Res<RoundProgress>,
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    wall_damage: Res<WallDamage>,
+    players: Query<(
+        &Player,
+        &Transform,
+        &MoveDir,
+        &BulletReady,
+        Option<&ShieldCharges>,
+        Option<&SpeedBoost>,
+        Option<&Health>,
+        Option<&HitInvulnerability>,
+        Option<&EquippedWeapon>,
+        Option<&Phasing>,
+        Option<&Stocks>,
+        Option<&Respawning>,
+        Option<&MarkedForDeath>,
+    )>,
+    speed_pickups: Query<(&SpeedPickup, &PickupCharging)>,
+    shield_pickups: Query<(&ShieldPickup, &PickupCharging)>,
+    health_pickups: Query<(&HealthPickup, &PickupCharging)>,
+    weapon_pickups: Query<(&WeaponPickup, &PickupCharging)>,
+    phase_pickups: Query<(&PhasePickup, &PickupCharging)>,
+) {
+    let mut live = Vec::new();
+    for (
+        player,
+        transform,
+        dir,
+        ready,
+        shield,
+        boost,
+        health,
+        invulnerable,
+        weapon,
+        phasing,
+        stocks,
+        respawning,
+        marked,
+    ) in &players
+    {
+        // A ghost waiting out a stock respawn is still in the round and keeps
+        // its delay; a marked ghost without one is listed as eliminated.
+        if marked.is_some() && respawning.is_none() {
+            continue;
+        }
+        live.push(ResumePlayer {
+            player_id: player.player_id,
+            position: transform.translation.truncate(),
+            move_dir: dir.0,
+            bullet_ready: ready.0,
+            shield_charges: shield.map(|shield| shield.0).unwrap_or(0),
+            speed_frames: boost.map(|boost| boost.frames_left).unwrap_or(0),
+            health: health.map(|health| health.0).unwrap_or(0),
+            invulnerable_frames: invulnerable
+                .map(|invulnerable| invulnerable.frames_left)
+                .unwrap_or(0),
+            weapon: weapon.map(|weapon| weapon.weapon).unwrap_or_default(),
+            weapon_frames: weapon.map(|weapon| weapon.frames_left).unwrap_or(0),
+            phase_frames: phasing.map(|phasing| phasing.frames_left).unwrap_or(0),
+            stocks: stocks.map(|stocks| stocks.0).unwrap_or(0),
+            respawn_frames: respawning
+                .map(|respawning| respawning.frames_left)
+                .unwrap_or(0),
+        });
+    }
+    live.sort_by_key(|player| player.player_id);
+    // Charging pickups resume with their remaining respawn frames rather
+    // than coming back the moment the round continues.
+    let mut charging: Vec<_> = speed_pickups
+        .iter()
+        .map(|(pickup, charging)| (pickup.cell, charging.frames_left))
+        .chain(
+            shield_pickups
+                .iter()
+                .map(|(pickup, charging)| (pickup.cell, charging.frames_left)),
+        )
+        .chain(
+            health_pickups
+                .iter()
+                .map(|(pickup, charging)| (pickup.cell, charging.frames_left)),
+        )
+        .chain(
+            weapon_pickups
+                .iter()
+                .map(|(pickup, charging)| (pickup.cell, charging.frames_left)),
+        )
+        .chain(
+            phase_pickups
+                .iter()
+                .map(|(pickup, charging)| (pickup.cell, charging.frames_left)),
+        )
+        .collect();
+    charging.sort();
+    history.record(ResumeSnapshot {
+        frame: frame.frame,
+        elapsed: round_elapsed(&progress, &frame),
+        active_size: map_data.active_size,
+        players: live,
+        eliminated: progress
+            .eliminated
+            .iter()
+            .map(|entry| entry.player_id)
+            .collect(),
+        charging,
+        walls: damaged_walls(&wall_damage),
+    });
+}
+
+/// Answers the lobby's snapshot request with the newest confirmed in-round
+/// state. A resolved round, or one with fewer than two live ghosts, is left to
+/// the ordinary changed-epoch restart.
+pub fn share_resume_snapshot(
+    session: Option<Res<Session<GgrsConfig>>>,
+    socket: Res<CloudflareSocket>,
+    bootstrap: Option<Res<RoundBootstrap>>,
+    frame: Res<GGFrameCount>,
+    progress: Res<RoundProgress>,
+    history: Res<ResumeHistory>,
+) {
+    if !socket.take_resume_request() {
+        return;
+    }
+    let (Some(session), Some(bootstrap)) = (session, bootstrap) else {
+        return;
+    };
+    let Session::P2P(p2p) = session.as_ref() else {
+        return;
+    };
+    if progress.resolved.is_some() || p2p.confirmed_frame() < 0 {
+        return;
+    }
+    // GGFrameCount describes the newest simulated frame; step back by the
+    // unconfirmed span to find the matching in-round frame.
+    let unconfirmed = i64::from(p2p.current_frame() - p2p.confirmed_frame()).max(0);
+    let Some(snapshot) = history.at_or_before(i64::from(frame.frame) - unconfirmed) else {
+        return;
+    };
+    let encoded = snapshot.encode();
+    if snapshot.players.len() >= 2 && encoded.len() <= MAX_SNAPSHOT_LEN {
+        socket.send_resume_snapshot(bootstrap.epoch.0, bootstrap.round.0, &encoded);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::fingerprint::ROLLBACK_REGISTRATIONS;
+
+    const RULES: MatchRules = MatchRules {
+        multi_hit_health: true,
+        stock_lives: true,
+    };
+    const MODE: GameMode = GameMode::Deathmatch;
+
+    fn sample() -> (ResumeSnapshot, Vec<PlayerId>) {
+        let roster = vec![PlayerId(3), PlayerId(1), PlayerId(2)];
+        let snapshot = ResumeSnapshot {
+            frame: 412,
+            elapsed: 4_390,
+            active_size: 37,
+            players: vec![
+                ResumePlayer {
+                    player_id: PlayerId(1),
+                    position: Vec2::new(-3.25, 0.1),
+                    move_dir: Vec2::new(0.6, -0.8),
+                    bullet_ready: 7,
+                    shield_charges: 1,
+                    speed_frames: 90,
+                    health: 2,
+                    invulnerable_frames: 12,
+                    weapon: Weapon::Ricochet,
+                    weapon_frames: 310,
+                    phase_frames: 0,
+                    stocks: 2,
+                    respawn_frames: 0,
+                },
+                ResumePlayer {
+                    player_id: PlayerId(3),
+                    position: Vec2::new(12.5, -17.5),
+                    move_dir: Vec2::X,
+                    bullet_ready: 0,
+                    shield_charges: 0,
+                    speed_frames: 0,
+                    health: 3,
+                    invulnerable_frames: 0,
+                    weapon: Weapon::Blaster,
+                    weapon_frames: 0,
+                    phase_frames: 44,
+                    stocks: 1,
+                    respawn_frames: 0,
+                },
+            ],
+            eliminated: vec![PlayerId(2)],
+            charging: vec![((4, 9), 615), ((36, 31), 1)],
+            walls: vec![((10, 12), 1), ((30, 28), 3)],
+        };
+        (snapshot, roster)
+    }
+
+    #[test]
+    fn snapshot_round_trips_bit_exactly_within_the_lobby_bound() {
+        let (snapshot, roster) = sample();
+        let encoded = snapshot.encode();
+        assert!(encoded.len() <= MAX_SNAPSHOT_LEN);
+        assert!(encoded.bytes().all(|byte| byte.is_ascii_digit()
+            || byte.is_ascii_lowercase()
+            || b":;.,".contains(&byte)));
+        assert_eq!(
+            ResumeSnapshot::decode(&encoded, &roster, RULES, MODE),
+            Some(snapshot)
+        );
+    }
+
+    #[test]
+    fn ghosts_waiting_out_a_respawn_stay_in_the_snapshot() {
+        let (mut snapshot, roster) = sample();
+        snapshot.players[1].stocks = 2;
+        snapshot.players[1].respawn_frames = 97;
+        snapshot.players[1].health = 0;
+        let decoded = ResumeSnapshot::decode(&snapshot.encode(), &roster, RULES, MODE).unwrap();
+        let respawning = decoded.player(PlayerId(3)).unwrap();
+        assert_eq!(respawning.stocks, 2);
+        assert_eq!(respawning.respawn_frames, 97);
+        assert_eq!(decoded, snapshot);
+    }
+
+    #[test]
+    fn snapshot_must_cover_the_roster_with_two_live_ghosts() {
+        let (mut snapshot, roster) = sample();
+        assert_eq!(
+            ResumeSnapshot::decode(&snapshot.encode(), &roster[..2], RULES, MODE),
+            None
+        );
+        let even_arena = ResumeSnapshot {
+            active_size: 36,
+            ..snapshot.clone()
+        };
+        assert_eq!(
+            ResumeSnapshot::decode(&even_arena.encode(), &roster, RULES, MODE),
+            None
+        );
+        snapshot.eliminated.push(PlayerId(3));
+        assert_eq!(
+            ResumeSnapshot::decode(&snapshot.encode(), &roster, RULES, MODE),
+            None
+        );
+        snapshot.eliminated.pop();
+        snapshot.players.pop();
+        snapshot.eliminated.push(PlayerId(3));
+        assert_eq!(
+            ResumeSnapshot::decode(&snapshot.encode(), &roster, RULES, MODE),
+            None
+        );
+        assert_eq!(
+            ResumeSnapshot::decode("v1;0;0;41;;;;", &roster, RULES, MODE),
+            None
+        );
+        assert_eq!(ResumeSnapshot::decode("", &roster, RULES, MODE), None);
+    }
+
+    #[test]
+    fn ghosts_must_fit_the_match_rules_and_the_open_arena() {
+        let (snapshot, roster) = sample();
+        let decode = |edit: fn(&mut ResumePlayer), rules: MatchRules, mode: GameMode| {
+            let mut edited = snapshot.clone();
+            edit(&mut edited.players[0]);
+            ResumeSnapshot::decode(&edited.encode(), &roster, rules, mode)
+        };
+        assert!(decode(|_| {}, RULES, MODE).is_some());
+        assert!(decode(|player| player.health = MAX_HEALTH + 1, RULES, MODE).is_none());
+        assert!(decode(|player| player.stocks = 0, RULES, MODE).is_none());
+        assert!(decode(|player| player.stocks = 4, RULES, MODE).is_none());
+        assert!(decode(|player| player.shield_charges = 2, RULES, MODE).is_none());
+        // The sample arena is 37 cells across, so its edge sits 18.5 out.
+        assert!(decode(|player| player.position.x = 18.5, RULES, MODE).is_some());
+        assert!(decode(|player| player.position.x = 18.75, RULES, MODE).is_none());
+        assert!(decode(|player| player.position.y = -20.0, RULES, MODE).is_none());
+        // Without the optional rules, health and stocks must stay unset.
+        let without_health = MatchRules {
+            multi_hit_health: false,
+            ..RULES
+        };
+        assert!(decode(|_| {}, without_health, MODE).is_none());
+        assert!(decode(|_| {}, RULES, GameMode::Duel).is_none());
+        let mut plain = snapshot.clone();
+        for player in &mut plain.players {
+            player.health = 0;
+            player.stocks = 0;
+        }
+        let plain_rules = MatchRules::default();
+        assert!(ResumeSnapshot::decode(&plain.encode(), &roster, plain_rules, MODE).is_some());
+        assert!(ResumeSnapshot::decode(&snapshot.encode(), &roster, plain_rules, MODE).is_none());
+    }
+
+    /// Registrations a snapshot deliberately leaves out. Anything else GGRS
+    /// rolls back must be captured by `record_resume_history`.
+    const NOT_RESUMED: &[&str] = &[
+        // The round restarts InRound; scores and the match state come from the
+        // lobby start, and the seeds from the bootstrap.
+        "roll_state:RollbackState",
+        "resource:RoundEndTimer",
+        "resource:Scores",
+        "resource:GameSeed",
+        "resource:SoundIdSeed",
+        "resource:MatchFlow",
+        // Bullets in flight and short-lived movement timers restart fresh.
+        "component:Bullet",
+        "component:Dash",
+        "component:TeleportCooldown",
+        "component:AimDir",
+        // Presentation only.
+        "component:LookTowardsParentMove",
+        "component:RollbackSound",
+        "component:ExplosionCue",
+        "component:Name",
+        "component:Sprite",
+        "component:Handle<Image>",
+        "component:Handle<TextureAtlas>",
+        "component:TextureAtlasSprite",
+    ];
+
+    #[test]
+    fn every_rollback_registration_is_captured_or_deliberately_left_out() {
+        let source = include_str!("resume.rs");
+        let recorder = source
+            .split("pub fn record_resume_history")
+            .nth(1)
+            .and_then(|tail| tail.split("\n}\n").next())
+            .expect("recorder source");
+        for registration in ROLLBACK_REGISTRATIONS {
+            if NOT_RESUMED.contains(registration) {
+                continue;
+            }
+            let (_, name) = registration.split_once(':').unwrap();
+            let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+            let captured = recorder.match_indices(name).any(|(at, _)| {
+                !recorder[..at].ends_with(is_ident)
+                    && !recorder[at + name.len()..].starts_with(is_ident)
+            });
+            assert!(
+                captured,
+                "{registration} is rolled back but missing from resume snapshots"
+            );
+        }
+        for registration in NOT_RESUMED {
+            assert!(ROLLBACK_REGISTRATIONS.contains(registration));
+        }
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_simulation_of_each_frame() {
+        let (snapshot, _) = sample();
+        let mut history = ResumeHistory::default();
+        for frame in 0..80 {
+            history.record(ResumeSnapshot {
+                frame,
+                ..snapshot.clone()
+            });
+        }
+        assert_eq!(history.0.len(), HISTORY_LEN);
+        history.record(ResumeSnapshot {
+            frame: 70,
+            eliminated: Vec::new(),
+            ..snapshot.clone()
+        });
+        assert_eq!(history.0.last().map(|entry| entry.frame), Some(70));
+        assert!(history.at_or_before(75).unwrap().eliminated.is_empty());
+        assert_eq!(history.at_or_before(69).map(|entry| entry.frame), Some(69));
+        assert!(history.at_or_before(2).is_none());
+    }
+}
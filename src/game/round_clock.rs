@@ -0,0 +1,265 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_ggrs::AddRollbackCommandExtension;
+
+use super::{
+    assets::{procedural::void_color, sounds::SoundAssets},
+    components::{ClosingVoid, MapBlock, MarkedForDeath, Player, Stocks},
+    ggrs_framecount::GGFrameCount,
+    map::{arena_size, CellType, Map},
+    player::{spend_stock, world_to_grid},
+    rollback_audio::{RollbackSound, RollbackSoundBundle},
+    session::{expired_round_outcome, RoundBootstrap},
+    Elimination, RollbackState, RoundProgress, SoundIdSeed, MAP_SIZE,
+};
+
+/// Frame budget per round, 90 seconds at 60 Hz.
+pub(super) const ROUND_FRAMES: u32 = 5400;
+/// The last 20 seconds of the budget are overtime.
+pub(super) const OVERTIME_FRAMES: u32 = 1200;
+/// During overtime the void claims one outer ring of the arena this often.
+pub(super) const ARENA_SHRINK_FRAMES: u32 = 240;
+/// The closing void stops short of the central region.
+pub(super) const MIN_ARENA_SIZE: usize = 9;
+
+/// Frames since `generate_map` started the current round. A resumed round
+/// backdates its start by the frames the interrupted round had played, so the
+/// clock carries on from the snapshot on every peer alike.
+pub fn round_elapsed(progress: &RoundProgress, frame: &GGFrameCount) -> u32 {
+    frame.frame.wrapping_sub(progress.started_frame)
+}
+
+pub fn remaining_frames(elapsed: u32) -> u32 {
+    ROUND_FRAMES.saturating_sub(elapsed)
+}
+
+pub fn in_overtime(elapsed: u32) -> bool {
+    elapsed >= ROUND_FRAMES - OVERTIME_FRAMES
+}
+
+/// Overtime closes a ring as it begins and then every `ARENA_SHRINK_FRAMES`.
+fn closes_ring_on(elapsed: u32) -> bool {
+    in_overtime(elapsed)
+        && elapsed < ROUND_FRAMES
+        && (elapsed - (ROUND_FRAMES - OVERTIME_FRAMES)) % ARENA_SHRINK_FRAMES == 0
+}
+
+/// Turns the outermost ring of the active square into void. Returns false
+/// once the arena is already at its minimum size.
+pub(super) fn close_ring(map: &mut Map<CellType, MAP_SIZE, MAP_SIZE>) -> bool {
+    if map.active_size <= MIN_ARENA_SIZE {
+        return false;
+    }
+    let start = MAP_SIZE / 2 - map.active_size / 2;
+    let end = start + map.active_size;
+    for x in start..end {
+        for y in start..end {
+            if x == start || y == start || x + 1 == end || y + 1 == end {
+                map.cells[x][y] = CellType::Void;
+            }
+        }
+    }
+    map.active_size -= 2;
+    true
+}
+
+/// Overtime hazard. Ghosts whose cell the void claims are eliminated in
+/// `PlayerId` order, like traps; ghosts straddling the new edge are nudged in.
+pub fn close_arena(
+    mut commands: Commands,
+    frame: Res<GGFrameCount>,
+    sounds: Res<SoundAssets>,
+    mut sound_id: ResMut<SoundIdSeed>,
+    mut progress: ResMut<RoundProgress>,
+    mut map_data: ResMut<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    mut players: Query<(Entity, &Player, &mut Transform), Without<MarkedForDeath>>,
+    mut stocks: Query<&mut Stocks>,
+) {
+    if !closes_ring_on(round_elapsed(&progress, &frame)) || !close_ring(&mut map_data) {
+        return;
+    }
+    let limit = map_data.active_size as f32 / 2. - 0.5;
+    let mut caught = Vec::new();
+    for (entity, player, mut transform) in &mut players {
+        let inside = world_to_grid(transform.translation.xy())
+            .is_some_and(|(x, y)| map_data.cells[x as usize][y as usize] != CellType::Void);
+        if inside {
+            transform.translation.x = transform.translation.x.clamp(-limit, limit);
+            transform.translation.y = transform.translation.y.clamp(-limit, limit);
+        } else {
+            caught.push((
+                player.player_id,
+                player.handle,
+                entity,
+                transform.translation,
+            ));
+        }
+    }
+    caught.sort_by_key(|entry| entry.0);
+    for (player_id, handle, entity, position) in caught {
+        if spend_stock(&mut commands, entity, &mut stocks) {
+            progress.record_elimination(Elimination {
+                player_id,
+                frame: frame.frame,
+                eliminated_by: None,
+            });
+        }
+        commands
+            .entity(entity)
+            .insert(MarkedForDeath::at(frame.frame));
+        commands
+            .spawn((RollbackSoundBundle {
+                sound: RollbackSound {
+                    clip: sounds.swoosh_death.clone(),
+                    start_frame: frame.frame,
+                    sub_key: sound_id.next(handle),
+                    ..default()
+                },
+                transform: Transform::from_translation(position),
+                ..default()
+            },))
+            .add_rollback();
+    }
+}
+
+/// Resolves the round when its budget runs out. Runs after `process_deaths`,
+/// so a round decided on the final frame keeps its regular outcome.
+pub fn expire_round(
+    frame: Res<GGFrameCount>,
+    bootstrap: Res<RoundBootstrap>,
+    mut progress: ResMut<RoundProgress>,
+    mut next_state: ResMut<NextState<RollbackState>>,
+) {
+    if progress.resolved.is_some() || round_elapsed(&progress, &frame) < ROUND_FRAMES {
+        return;
+    }
+    let roster: Vec<_> = bootstrap
+        .roster
+        .iter()
+        .map(|entry| entry.player_id)
+        .collect();
+    let eliminated: Vec<_> = progress
+        .eliminated
+        .iter()
+        .map(|entry| entry.player_id)
+        .collect();
+    let credits: Vec<_> = progress
+        .eliminated
+        .iter()
+        .filter_map(|entry| entry.eliminated_by)
+        .collect();
+    let outcome = expired_round_outcome(&roster, &eliminated, &progress.disconnected, &credits);
+    progress.resolved = Some(outcome);
+    progress.resolved_frame = Some(frame.frame);
+    next_state.set(RollbackState::RoundEnd);
+}
+
+/// Centre and size of the four void bands outside an `active` square,
+/// ordered left, right, bottom, top.
+fn closing_void_layout(active: usize) -> [(Vec2, Vec2); 4] {
+    let full = MAP_SIZE as f32;
+    let inner = active as f32;
+    let depth = (full - inner) / 2.;
+    let offset = (inner + depth) / 2.;
+    [
+        (Vec2::new(-offset, 0.), Vec2::new(depth, full)),
+        (Vec2::new(offset, 0.), Vec2::new(depth, full)),
+        (Vec2::new(0., -offset), Vec2::new(inner, depth)),
+        (Vec2::new(0., offset), Vec2::new(inner, depth)),
+    ]
+}
+
+/// Draws the closed-off rings from the rolled back map size. A rollback to
+/// before overtime simply removes the bands again.
+pub fn reconcile_closing_void(
+    mut commands: Commands,
+    map_data: Res<Map<CellType, MAP_SIZE, MAP_SIZE>>,
+    bootstrap: Option<Res<RoundBootstrap>>,
+    mut bands: Query<(Entity, &ClosingVoid, &mut Transform, &mut Sprite)>,
+) {
+    let closed =
+        bootstrap.is_some_and(|bootstrap| map_data.active_size < arena_size(bootstrap.mode));
+    let layout = closing_void_layout(map_data.active_size);
+    let mut present = [false; 4];
+    for (entity, band, mut transform, mut sprite) in &mut bands {
+        if !closed || std::mem::replace(&mut present[band.side], true) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let (center, size) = layout[band.side];
+        if transform.translation.xy() != center {
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+        }
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+    }
+    if !closed {
+        return;
+    }
+    for (side, (center, size)) in layout.into_iter().enumerate() {
+        if present[side] {
+            continue;
+        }
+        commands.spawn((
+            MapBlock,
+            ClosingVoid { side },
+            SpriteBundle {
+                transform: Transform::from_translation(center.extend(5.)),
+                sprite: Sprite {
+                    color: void_color().with_a(0.9),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overtime_closes_rings_on_schedule_down_to_the_minimum() {
+        let overtime = ROUND_FRAMES - OVERTIME_FRAMES;
+        assert!(!closes_ring_on(overtime - 1));
+        assert!(closes_ring_on(overtime));
+        assert!(!closes_ring_on(overtime + 1));
+        assert!(closes_ring_on(overtime + ARENA_SHRINK_FRAMES));
+        assert!(!closes_ring_on(ROUND_FRAMES));
+        assert_eq!(remaining_frames(ROUND_FRAMES + 5), 0);
+
+        let mut map = Map::<CellType, MAP_SIZE, MAP_SIZE>::generated(42);
+        assert!(close_ring(&mut map));
+        assert_eq!(map.active_size, MAP_SIZE - 2);
+        assert_eq!(map.cells[0][MAP_SIZE / 2], CellType::Void);
+        assert_eq!(map.cells[MAP_SIZE - 1][MAP_SIZE - 1], CellType::Void);
+        assert_eq!(map.cells[MAP_SIZE / 2][MAP_SIZE / 2], CellType::Empty);
+        while close_ring(&mut map) {}
+        assert_eq!(map.active_size, MIN_ARENA_SIZE);
+        for x in 0..MAP_SIZE {
+            for y in 0..MAP_SIZE {
+                assert_eq!(
+                    map.cells[x][y] == CellType::Void,
+                    map.cells[MAP_SIZE - 1 - x][MAP_SIZE - 1 - y] == CellType::Void
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closing_void_bands_tile_everything_outside_the_arena() {
+        for active in [MIN_ARENA_SIZE, 21, MAP_SIZE - 2] {
+            let layout = closing_void_layout(active);
+            let area: f32 = layout.iter().map(|(_, size)| size.x * size.y).sum();
+            let full = MAP_SIZE as f32;
+            assert!((area - (full * full - (active * active) as f32)).abs() < 1e-3);
+            for (center, size) in layout {
+                let outer = center.abs() + size / 2.;
+                assert!((outer.max_element() - full / 2.).abs() < 1e-4);
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@ use std::collections::{BTreeSet, HashSet};
 
 use bevy::prelude::*;
 
+use super::{cosmetics::Loadout, rating::MAX_RATING};
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect)]
 pub struct PlayerId(pub u128);
 
@@ -16,6 +18,10 @@ pub struct RoundNumber(pub u32);
 
 pub const LOBBY_PROTOCOL_VERSION: u16 = 3;
 pub const MAX_LOBBY_PLAYERS: usize = 8;
+/// Ed25519 key the lobby service signs starts with, as 64 hex digits. Only
+/// `local` builds, which talk to an unsigned loopback Worker, may go without
+/// one; every other build refuses starts until it is compiled in.
+const LOBBY_START_PUBLIC_KEY: Option<&str> = option_env!("GHOST_BATTLE_LOBBY_PUBLIC_KEY");
 
 /// Pure continuity rule shared by bootstrap installation and source tests.
 /// An unchanged canonical roster stays in the epoch and advances the round;
@@ -62,6 +68,28 @@ impl MatchPreference {
 
 pub const MATCH_POINTS_TO_WIN: u32 = 3;
 
+/// Optional gameplay rules picked with a private room. They are folded into
+/// the private lobby name, so every peer that shares a lobby runs the same
+/// rules. Public queues always play the defaults.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MatchRules {
+    /// Ghosts take several hits instead of being eliminated by the first one.
+    pub multi_hit_health: bool,
+    /// Last Ghost Standing ghosts get several lives and respawn between them.
+    pub stock_lives: bool,
+}
+
+impl MatchRules {
+    pub fn room_suffix(self) -> &'static str {
+        match (self.multi_hit_health, self.stock_lives) {
+            (false, false) => "",
+            (true, false) => "-hp",
+            (false, true) => "-stock",
+            (true, true) => "-hp-stock",
+        }
+    }
+}
+
 /// Public-facing mode copy. The internal `Deathmatch` variant is a round-based
 /// last-survivor mode, not an unlimited respawn deathmatch.
 pub fn mode_label(mode: GameMode) -> &'static str {
@@ -94,18 +122,55 @@ pub fn match_winner(scores: &[PlayerScore]) -> Option<MatchWinner> {
 #[derive(Debug, Clone, PartialEq, Eq, Reflect)]
 pub enum RoundOutcome {
     InProgress,
-    Complete { point_winners: Vec<PlayerId> },
+    Complete {
+        point_winners: Vec<PlayerId>,
+    },
+    /// The round's frame budget ran out. Empty winners is a draw.
+    Expired {
+        point_winners: Vec<PlayerId>,
+    },
 }
 
 impl RoundOutcome {
     pub fn point_winners(&self) -> &[PlayerId] {
         match self {
             Self::InProgress => &[],
-            Self::Complete { point_winners } => point_winners,
+            Self::Complete { point_winners } | Self::Expired { point_winners } => point_winners,
         }
     }
 }
 
+/// Resolve a round whose frame budget ran out. The point goes to the one
+/// survivor with strictly the most eliminations; anything else is a draw.
+/// `credits` holds the eliminating player of every credited elimination.
+pub fn expired_round_outcome(
+    roster: &[PlayerId],
+    eliminated: &[PlayerId],
+    disconnected: &[PlayerId],
+    credits: &[PlayerId],
+) -> RoundOutcome {
+    let survivors: BTreeSet<_> = roster
+        .iter()
+        .copied()
+        .filter(|player_id| !eliminated.contains(player_id) && !disconnected.contains(player_id))
+        .collect();
+    let mut tally: Vec<_> = survivors
+        .iter()
+        .map(|survivor| {
+            let count = credits.iter().filter(|credit| *credit == survivor).count();
+            (count, *survivor)
+        })
+        .collect();
+    tally.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let point_winners = match tally.as_slice() {
+        [(best, winner), rest @ ..] if *best > 0 && rest.iter().all(|(count, _)| count < best) => {
+            vec![*winner]
+        }
+        _ => Vec::new(),
+    };
+    RoundOutcome::Expired { point_winners }
+}
+
 /// Resolve a round without depending on entity or network iteration order.
 ///
 /// Disconnected players are treated exactly like eliminated players. Unknown
@@ -166,7 +231,9 @@ pub struct PlayerProfile {
     pub player_id: PlayerId,
     pub name: String,
     pub palette_id: u8,
-    pub cosmetic_id: u8,
+    pub loadout: Loadout,
+    /// Self-reported skill rating for the round's mode; `None` when unrated.
+    pub rating: Option<u16>,
 }
 
 impl PlayerProfile {
@@ -198,7 +265,8 @@ impl PlayerProfile {
         !self.name.is_empty()
             && self.name == Self::sanitized_name(&self.name)
             && self.palette_id < MAX_LOBBY_PLAYERS as u8
-            && self.cosmetic_id < 4
+            && self.loadout.is_canonical()
+            && !self.rating.is_some_and(|rating| rating > MAX_RATING)
     }
 }
 
@@ -235,6 +303,8 @@ pub enum BootstrapError {
     InvalidHandles,
     InvalidProfiles,
     InvalidScores,
+    InvalidSignature,
+    SimulationMismatch,
 }
 
 impl RoundBootstrap {
@@ -343,7 +413,8 @@ impl RoundBootstrap {
                 player_id: entry.player_id,
                 name: format!("Player {}", entry.handle + 1),
                 palette_id: entry.handle as u8,
-                cosmetic_id: 0,
+                loadout: Loadout::default(),
+                rating: None,
             })
             .collect();
         let scores = roster
@@ -368,11 +439,149 @@ impl RoundBootstrap {
     }
 }
 
+/// Refuses a roster unless every member, this client included, reported the
+/// local simulation fingerprint. A member that reported none may run any
+/// build, so it is refused like a mismatch.
+pub fn check_simulation_fingerprints(
+    local: u64,
+    reported: &[(PlayerId, Option<u64>)],
+) -> Result<(), BootstrapError> {
+    if reported
+        .iter()
+        .all(|(_, simulation)| *simulation == Some(local))
+    {
+        Ok(())
+    } else {
+        Err(BootstrapError::SimulationMismatch)
+    }
+}
+
+/// Defense in depth against a tampered JS layer or relay: the canonical start
+/// text must carry the lobby service's signature before a session is built.
+pub fn verify_lobby_start(canonical: &str, signature: &str) -> Result<(), BootstrapError> {
+    verify_start_with(LOBBY_START_PUBLIC_KEY, canonical, signature)
+}
+
+fn verify_start_with(
+    public_key: Option<&str>,
+    canonical: &str,
+    signature: &str,
+) -> Result<(), BootstrapError> {
+    match public_key {
+        Some(public_key) => verify_start_signature(public_key, canonical, signature),
+        None if cfg!(feature = "local") => Ok(()),
+        None => Err(BootstrapError::InvalidSignature),
+    }
+}
+
+fn verify_start_signature(
+    public_key: &str,
+    canonical: &str,
+    signature: &str,
+) -> Result<(), BootstrapError> {
+    let key = decode_hex::<32>(public_key)
+        .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or(BootstrapError::InvalidSignature)?;
+    let signature = decode_hex::<64>(signature)
+        .map(|bytes| ed25519_dalek::Signature::from_bytes(&bytes))
+        .ok_or(BootstrapError::InvalidSignature)?;
+    key.verify_strict(canonical.as_bytes(), &signature)
+        .map_err(|_| BootstrapError::InvalidSignature)
+}
+
+fn decode_hex<const N: usize>(value: &str) -> Option<[u8; N]> {
+    if value.len() != N * 2 || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&value[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::game::SoundIdSeed;
 
+    /// RFC 8032 test 1 public key; the worker tests sign the same start.
+    const START_PUBLIC_KEY: &str =
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const START_SIGNATURE: &str = "fc7aeb385cec9f193bd0f7d3cd490e0c01911ce0e08005dda412bbceb31216abe6008dc5781dc36031ab2994f4a759a6c61482ceba80ae7d7ebf12047d765802";
+
+    #[test]
+    fn simulation_fingerprints_must_be_reported_and_agree() {
+        let (local, peer) = (PlayerId(1), PlayerId(2));
+        assert_eq!(
+            check_simulation_fingerprints(7, &[(local, Some(7)), (peer, Some(7))]),
+            Ok(())
+        );
+        for reported in [
+            [(local, Some(7)), (peer, Some(8))],
+            [(local, Some(7)), (peer, None)],
+            [(local, None), (peer, Some(7))],
+            [(local, None), (peer, None)],
+            [(local, Some(8)), (peer, Some(8))],
+        ] {
+            assert_eq!(
+                check_simulation_fingerprints(7, &reported),
+                Err(BootstrapError::SimulationMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn lobby_start_signatures_verify_only_the_signed_text() {
+        let canonical = format!(
+            "v3-start\ndeathmatch\n4\n3\n4\n1\n0123456789abcdef0123456789abcdef\n2\n{}:0:::,{}:2:0123456789abcdef:1.0.2:1510.1490,{}:1:fedcba9876543210::\n",
+            "a".repeat(32),
+            "b".repeat(32),
+            "c".repeat(32)
+        );
+        assert_eq!(
+            verify_start_signature(START_PUBLIC_KEY, &canonical, START_SIGNATURE),
+            Ok(())
+        );
+        for tampered in [
+            canonical.replace("\n2\n", "\n0\n"),
+            canonical.replace(":1.0.2:", ":0.0.0:"),
+            canonical.replace("fedcba9876543210", "0000000000000000"),
+        ] {
+            assert_eq!(
+                verify_start_signature(START_PUBLIC_KEY, &tampered, START_SIGNATURE),
+                Err(BootstrapError::InvalidSignature)
+            );
+        }
+        for signature in ["", "zz", &START_SIGNATURE[2..]] {
+            assert_eq!(
+                verify_start_signature(START_PUBLIC_KEY, &canonical, signature),
+                Err(BootstrapError::InvalidSignature)
+            );
+        }
+        assert_eq!(
+            verify_start_signature("00", &canonical, START_SIGNATURE),
+            Err(BootstrapError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_start_with(Some(START_PUBLIC_KEY), &canonical, START_SIGNATURE),
+            Ok(())
+        );
+    }
+
+    #[cfg(not(feature = "local"))]
+    #[test]
+    fn builds_without_a_lobby_key_refuse_every_start() {
+        assert_eq!(
+            verify_start_with(None, "v3-start\n", START_SIGNATURE),
+            Err(BootstrapError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_start_with(None, "v3-start\n", ""),
+            Err(BootstrapError::InvalidSignature)
+        );
+    }
+
     fn entry(id: u128, handle: usize) -> RosterEntry {
         RosterEntry {
             player_id: PlayerId(id),
@@ -391,7 +600,8 @@ mod tests {
                 player_id: entry.player_id,
                 name: format!("Player {}", entry.player_id.0),
                 palette_id: entry.handle as u8,
-                cosmetic_id: 0,
+                loadout: Loadout::default(),
+                rating: None,
             })
             .collect();
         RoundBootstrap::new(
@@ -434,14 +644,39 @@ mod tests {
             player_id: PlayerId(1),
             name: "Ghost Rider".into(),
             palette_id: 0,
-            cosmetic_id: 0,
+            loadout: Loadout {
+                hat: 1,
+                trail: 64,
+                bullet: 128,
+            },
+            rating: Some(MAX_RATING),
         }
         .is_canonical());
         assert!(!PlayerProfile {
             player_id: PlayerId(1),
             name: " Ghost ".into(),
             palette_id: 0,
-            cosmetic_id: 0,
+            loadout: Loadout::default(),
+            rating: None,
+        }
+        .is_canonical());
+        assert!(!PlayerProfile {
+            player_id: PlayerId(1),
+            name: "Ghost".into(),
+            palette_id: 0,
+            loadout: Loadout {
+                hat: 5000,
+                ..default()
+            },
+            rating: None,
+        }
+        .is_canonical());
+        assert!(!PlayerProfile {
+            player_id: PlayerId(1),
+            name: "Ghost".into(),
+            palette_id: 0,
+            loadout: Loadout::default(),
+            rating: Some(MAX_RATING + 1),
         }
         .is_canonical());
     }
@@ -486,10 +721,33 @@ mod tests {
     fn winners(outcome: RoundOutcome) -> Vec<PlayerId> {
         match outcome {
             RoundOutcome::Complete { point_winners } => point_winners,
-            RoundOutcome::InProgress => panic!("expected a completed round"),
+            RoundOutcome::InProgress | RoundOutcome::Expired { .. } => {
+                panic!("expected a completed round")
+            }
         }
     }
 
+    #[test]
+    fn expired_rounds_reward_the_top_eliminator_or_draw() {
+        let roster = ids(&[1, 2, 3, 4]);
+        // Ghost 4 is out; 1 scored twice, 2 once, and 4's own kill is moot.
+        let outcome = expired_round_outcome(&roster, &ids(&[4]), &[], &ids(&[1, 2, 1, 4]));
+        assert_eq!(
+            outcome,
+            RoundOutcome::Expired {
+                point_winners: ids(&[1])
+            }
+        );
+        assert_eq!(outcome.point_winners(), ids(&[1]).as_slice());
+
+        let tied = expired_round_outcome(&roster, &[], &[], &ids(&[1, 2]));
+        assert!(tied.point_winners().is_empty());
+        let quiet = expired_round_outcome(&roster, &[], &[], &[]);
+        assert!(quiet.point_winners().is_empty());
+        let leader_left = expired_round_outcome(&roster, &[], &ids(&[1]), &ids(&[1, 1, 2]));
+        assert_eq!(leader_left.point_winners(), ids(&[2]).as_slice());
+    }
+
     #[test]
     fn last_ghost_standing_scores_the_sole_survivor() {
         let roster = ids(&[1, 2, 3, 4]);
@@ -0,0 +1,502 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+
+use bevy::prelude::*;
+use ggrs::GGRSEvent;
+use instant::{Duration, Instant, SystemTime};
+
+use crate::cloudflare_net::{CloudflareSocket, NetworkTelemetry, QueueStatus};
+
+use super::{
+    networking::{
+        EpochRollover, GgrsConfig, InputDelaySetting, LocalPlayerHandle, MatchmakingRoom,
+    },
+    resume::RoundResume,
+    session::{GameMode, RoundBootstrap, LOBBY_PROTOCOL_VERSION},
+};
+
+/// Oldest events are dropped past this bound so a long match cannot grow the
+/// log without limit; the export header reports how many were dropped.
+pub const MAX_LOG_EVENTS: usize = 512;
+/// Interval between `NetworkTelemetry` snapshots while a round is running.
+const TELEMETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// One structured entry. Fields are limited to counters, phases and roster
+/// handles, following docs/network-telemetry.md: no player ids, addresses,
+/// TURN credentials, SDP, candidate text or raw transport error strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    MatchmakingStarted {
+        private: bool,
+    },
+    Queue(QueueStatus),
+    Assigned {
+        mode: GameMode,
+        players: usize,
+        local_handle: Option<usize>,
+        epoch: u32,
+        round: u32,
+        agreed_input_delay: Option<usize>,
+        input_delay: InputDelaySetting,
+        resumed: bool,
+    },
+    EpochRollover {
+        from: (u32, u32),
+        to: (u32, u32),
+    },
+    Ggrs {
+        kind: &'static str,
+        handle: Option<usize>,
+    },
+    Telemetry(NetworkTelemetry),
+    TransportFailed {
+        stalled: bool,
+    },
+}
+
+impl SessionEvent {
+    /// GGRS repeats its wait recommendation every few frames while a peer
+    /// lags; back-to-back copies fold into one entry so they cannot flush
+    /// the rest of the match out of the bounded log.
+    fn coalesces_with(&self, previous: &Self) -> bool {
+        matches!(
+            self,
+            Self::Ggrs {
+                kind: "wait_recommendation",
+                ..
+            }
+        ) && self == previous
+    }
+
+    fn write_fields(&self, line: &mut String) {
+        match self {
+            Self::MatchmakingStarted { private } => {
+                let _ = write!(line, r#""type":"matchmaking","private":{private}"#);
+            }
+            Self::Queue(status) => {
+                let _ = match status {
+                    QueueStatus::Searching => write!(line, r#""type":"queue","phase":"searching""#),
+                    QueueStatus::HoldingForThird => {
+                        write!(line, r#""type":"queue","phase":"holding_for_third""#)
+                    }
+                    QueueStatus::Staging {
+                        count,
+                        votes,
+                        votes_required,
+                        voted,
+                        ..
+                    } => write!(
+                        line,
+                        r#""type":"queue","phase":"staging","count":{count},"votes":{votes},"votes_required":{votes_required},"voted":{voted}"#
+                    ),
+                    QueueStatus::Assigned => write!(line, r#""type":"queue","phase":"assigned""#),
+                };
+            }
+            Self::Assigned {
+                mode,
+                players,
+                local_handle,
+                epoch,
+                round,
+                agreed_input_delay,
+                input_delay,
+                resumed,
+            } => {
+                let mode = match mode {
+                    GameMode::Duel => "duel",
+                    GameMode::Deathmatch => "last_ghost_standing",
+                };
+                let setting = input_delay.encode();
+                let _ = write!(
+                    line,
+                    r#""type":"assigned","mode":"{mode}","players":{players},"local_handle":{},"epoch":{epoch},"round":{round},"agreed_input_delay":{},"input_delay_setting":"{setting}","resumed":{resumed}"#,
+                    json_option(*local_handle),
+                    json_option(*agreed_input_delay),
+                );
+            }
+            Self::EpochRollover { from, to } => {
+                let _ = write!(
+                    line,
+                    r#""type":"epoch_rollover","from_epoch":{},"from_round":{},"to_epoch":{},"to_round":{}"#,
+                    from.0, from.1, to.0, to.1
+                );
+            }
+            Self::Ggrs { kind, handle } => {
+                let _ = write!(
+                    line,
+                    r#""type":"ggrs","event":"{kind}","handle":{}"#,
+                    json_option(*handle)
+                );
+            }
+            Self::Telemetry(telemetry) => {
+                let _ = write!(
+                    line,
+                    r#""type":"telemetry","packets_sent":{},"packets_received":{},"packets_dropped":{},"stale_epoch_packets":{},"reconnects":{},"reports_sent":{},"relay_connections":{},"stun_fallbacks":{},"candidate_pair_host":{},"candidate_pair_srflx":{},"candidate_pair_relay":{}"#,
+                    telemetry.packets_sent,
+                    telemetry.packets_received,
+                    telemetry.packets_dropped,
+                    telemetry.stale_epoch_packets,
+                    telemetry.reconnects,
+                    telemetry.reports_sent,
+                    telemetry.relay_connections,
+                    telemetry.stun_fallbacks,
+                    telemetry.candidate_pair_host,
+                    telemetry.candidate_pair_srflx,
+                    telemetry.candidate_pair_relay,
+                );
+            }
+            Self::TransportFailed { stalled } => {
+                let cause = if *stalled { "stalled" } else { "failed" };
+                let _ = write!(line, r#""type":"transport_failed","cause":"{cause}""#);
+            }
+        }
+    }
+}
+
+fn json_option(value: Option<usize>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+/// Maps a GGRS event to a stable name and the affected roster handle. The
+/// peer address is a `PlayerId` and never leaves this function.
+pub fn ggrs_event_entry(
+    event: &GGRSEvent<GgrsConfig>,
+    bootstrap: Option<&RoundBootstrap>,
+) -> SessionEvent {
+    let (kind, addr) = match event {
+        GGRSEvent::Synchronizing { addr, .. } => ("synchronizing", Some(addr)),
+        GGRSEvent::Synchronized { addr } => ("synchronized", Some(addr)),
+        GGRSEvent::Disconnected { addr } => ("disconnected", Some(addr)),
+        GGRSEvent::NetworkInterrupted { addr, .. } => ("network_interrupted", Some(addr)),
+        GGRSEvent::NetworkResumed { addr } => ("network_resumed", Some(addr)),
+        GGRSEvent::WaitRecommendation { .. } => ("wait_recommendation", None),
+        GGRSEvent::DesyncDetected { addr, .. } => ("desync_detected", Some(addr)),
+    };
+    let handle = addr.and_then(|addr| {
+        bootstrap?
+            .roster
+            .iter()
+            .find(|entry| entry.player_id == *addr)
+            .map(|entry| entry.handle)
+    });
+    SessionEvent::Ggrs { kind, handle }
+}
+
+/// Bounded per-match event log for connection triage. A match spans every
+/// round and epoch rollover from entering matchmaking until the next fresh
+/// matchmaking entry; it stays exportable after returning to the menu.
+#[derive(Resource, Debug, Default)]
+pub struct SessionLog {
+    started: Option<Instant>,
+    events: VecDeque<(u64, SessionEvent, u32)>,
+    dropped: u64,
+    last_queue: Option<QueueStatus>,
+    last_rollover: Option<((u32, u32), (u32, u32))>,
+    last_telemetry: Option<Instant>,
+}
+
+impl SessionLog {
+    pub fn begin(&mut self) {
+        *self = Self {
+            started: Some(Instant::now()),
+            ..Default::default()
+        };
+    }
+
+    pub fn record(&mut self, event: SessionEvent) {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let t_ms = started.elapsed().as_millis().min(u64::MAX as u128) as u64;
+        if let Some((_, previous, repeats)) = self.events.back_mut() {
+            if event.coalesces_with(previous) {
+                *repeats = repeats.saturating_add(1);
+                return;
+            }
+        }
+        if self.events.len() == MAX_LOG_EVENTS {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back((t_ms, event, 1));
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// JSON Lines: a header line describing the build and log bounds, then
+    /// one object per event with milliseconds since the match log began.
+    /// Coalesced entries carry the time of the first copy and a `repeats`
+    /// count.
+    pub fn to_jsonl(&self) -> String {
+        let mut out = format!(
+            r#"{{"type":"session_log","version":"{}","protocol":{LOBBY_PROTOCOL_VERSION},"events":{},"dropped":{}}}"#,
+            env!("CARGO_PKG_VERSION"),
+            self.events.len(),
+            self.dropped,
+        );
+        out.push('\n');
+        for (t_ms, event, repeats) in &self.events {
+            let _ = write!(out, r#"{{"t_ms":{t_ms},"#);
+            event.write_fields(&mut out);
+            if *repeats > 1 {
+                let _ = write!(out, r#","repeats":{repeats}"#);
+            }
+            out.push_str("}\n");
+        }
+        out
+    }
+}
+
+/// Starts a fresh log for each new match. Rollovers re-enter matchmaking
+/// between rounds and keep appending to the same match log.
+pub fn begin_session_log(
+    rollover: Res<EpochRollover>,
+    room: Res<MatchmakingRoom>,
+    mut log: ResMut<SessionLog>,
+) {
+    if rollover.active() {
+        return;
+    }
+    log.begin();
+    log.record(SessionEvent::MatchmakingStarted {
+        private: room.private_code.is_some(),
+    });
+}
+
+pub fn record_queue_status(socket: Res<CloudflareSocket>, mut log: ResMut<SessionLog>) {
+    let status = socket.queue_status();
+    // Staging deadlines tick every poll; only phase, count and vote changes
+    // are worth an entry.
+    let key = status.map(|status| match status {
+        QueueStatus::Staging {
+            count,
+            votes,
+            votes_required,
+            voted,
+            ..
+        } => QueueStatus::Staging {
+            count,
+            votes,
+            votes_required,
+            deadline_ms: 0,
+            voted,
+        },
+        status => status,
+    });
+    if key == log.last_queue {
+        return;
+    }
+    log.last_queue = key;
+    if let Some(status) = key {
+        log.record(SessionEvent::Queue(status));
+    }
+}
+
+pub fn record_round_assignment(
+    bootstrap: Option<Res<RoundBootstrap>>,
+    socket: Res<CloudflareSocket>,
+    local: Option<Res<LocalPlayerHandle>>,
+    delay: Res<InputDelaySetting>,
+    resume: Res<RoundResume>,
+    mut log: ResMut<SessionLog>,
+) {
+    let Some(bootstrap) = bootstrap.filter(|bootstrap| bootstrap.is_changed()) else {
+        return;
+    };
+    log.record(SessionEvent::Assigned {
+        mode: bootstrap.mode,
+        players: bootstrap.roster.len(),
+        local_handle: local.map(|local| local.0),
+        epoch: bootstrap.epoch.0,
+        round: bootstrap.round.0,
+        agreed_input_delay: socket.lobby_match_info().map(|info| info.input_delay),
+        input_delay: *delay,
+        resumed: resume.0.is_some(),
+    });
+}
+
+pub fn record_epoch_rollover(rollover: Res<EpochRollover>, mut log: ResMut<SessionLog>) {
+    let (Some(from), Some(to)) = (rollover.old, rollover.pending) else {
+        return;
+    };
+    if log.last_rollover == Some((from, to)) {
+        return;
+    }
+    log.last_rollover = Some((from, to));
+    log.record(SessionEvent::EpochRollover { from, to });
+}
+
+pub fn record_telemetry_snapshot(telemetry: Res<NetworkTelemetry>, mut log: ResMut<SessionLog>) {
+    if log
+        .last_telemetry
+        .is_some_and(|last| last.elapsed() < TELEMETRY_INTERVAL)
+    {
+        return;
+    }
+    log.last_telemetry = Some(Instant::now());
+    log.record(SessionEvent::Telemetry(*telemetry));
+}
+
+/// Saves the log for a support request: a browser download on the web build,
+/// a file in the working directory natively.
+pub fn export_session_log(log: &SessionLog) -> Result<(), String> {
+    if log.is_empty() {
+        return Err("No session events recorded yet.".into());
+    }
+    let stamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    save_log_file(
+        &format!("ghost-battle-session-{stamp}.jsonl"),
+        &log.to_jsonl(),
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_log_file(filename: &str, contents: &str) -> Result<(), String> {
+    if session_log_download(filename, contents) {
+        Ok(())
+    } else {
+        Err("Could not start the session log download.".into())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_log_file(filename: &str, contents: &str) -> Result<(), String> {
+    std::fs::write(filename, contents)
+        .map_err(|error| format!("Could not write {filename}: {error}"))
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
+export function session_log_download(filename, contents) {
+  try {
+    const url = URL.createObjectURL(new Blob([contents], { type: "application/x-ndjson" }));
+    const link = document.createElement("a");
+    link.href = url;
+    link.download = filename;
+    document.body.appendChild(link);
+    link.click();
+    link.remove();
+    setTimeout(() => URL.revokeObjectURL(url), 0);
+    return true;
+  } catch (_) {
+    return false;
+  }
+}
+"#)]
+extern "C" {
+    fn session_log_download(filename: &str, contents: &str) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_is_bounded_and_counts_dropped_events() {
+        let mut log = SessionLog::default();
+        log.begin();
+        for _ in 0..MAX_LOG_EVENTS + 3 {
+            log.record(SessionEvent::TransportFailed { stalled: true });
+        }
+        assert_eq!(log.len(), MAX_LOG_EVENTS);
+        let export = log.to_jsonl();
+        let header = export.lines().next().unwrap();
+        assert!(header.contains(&format!(r#""events":{MAX_LOG_EVENTS}"#)));
+        assert!(header.contains(r#""dropped":3"#));
+        assert_eq!(export.lines().count(), MAX_LOG_EVENTS + 1);
+    }
+
+    #[test]
+    fn export_is_one_object_per_line() {
+        let mut log = SessionLog::default();
+        log.begin();
+        log.record(SessionEvent::MatchmakingStarted { private: false });
+        log.record(SessionEvent::Queue(QueueStatus::Staging {
+            count: 3,
+            votes: 1,
+            votes_required: 2,
+            deadline_ms: 9_000,
+            voted: true,
+        }));
+        log.record(SessionEvent::Assigned {
+            mode: GameMode::Deathmatch,
+            players: 3,
+            local_handle: Some(1),
+            epoch: 4,
+            round: 0,
+            agreed_input_delay: Some(2),
+            input_delay: InputDelaySetting::Auto,
+            resumed: false,
+        });
+        log.record(SessionEvent::EpochRollover {
+            from: (4, 0),
+            to: (4, 1),
+        });
+        log.record(SessionEvent::Telemetry(NetworkTelemetry {
+            packets_sent: 10,
+            ..Default::default()
+        }));
+        let export = log.to_jsonl();
+        let lines: Vec<_> = export.lines().collect();
+        assert_eq!(lines.len(), 6);
+        for line in &lines {
+            assert!(line.starts_with('{') && line.ends_with('}'), "{line}");
+            assert_eq!(line.matches('{').count(), 1, "{line}");
+        }
+        assert!(lines[2].contains(r#""phase":"staging","count":3,"votes":1"#));
+        assert!(!lines[2].contains("deadline"));
+        assert!(lines[3].contains(r#""local_handle":1"#));
+        assert!(lines[3].contains(r#""input_delay_setting":"auto""#));
+        assert!(lines[4].contains(r#""to_round":1"#));
+        assert!(lines[5].contains(r#""packets_sent":10"#));
+    }
+
+    #[test]
+    fn repeated_wait_recommendations_share_one_entry() {
+        let wait = ggrs_event_entry(&GGRSEvent::WaitRecommendation { skip_frames: 2 }, None);
+        let mut log = SessionLog::default();
+        log.begin();
+        for _ in 0..MAX_LOG_EVENTS * 2 {
+            log.record(wait);
+        }
+        log.record(SessionEvent::TransportFailed { stalled: false });
+        log.record(wait);
+        assert_eq!(log.len(), 3);
+        let export = log.to_jsonl();
+        let lines: Vec<_> = export.lines().collect();
+        assert!(lines[0].contains(r#""dropped":0"#));
+        assert!(lines[1].ends_with(&format!(r#""repeats":{}}}"#, MAX_LOG_EVENTS * 2)));
+        assert!(!lines[3].contains("repeats"));
+    }
+
+    #[test]
+    fn ggrs_entries_carry_roster_handles_not_player_ids() {
+        let bootstrap = RoundBootstrap::duel(0xdead_beef);
+        let player_id = bootstrap.roster[1].player_id;
+        let entry = ggrs_event_entry(
+            &GGRSEvent::NetworkInterrupted {
+                addr: player_id,
+                disconnect_timeout: 10_000,
+            },
+            Some(&bootstrap),
+        );
+        assert_eq!(
+            entry,
+            SessionEvent::Ggrs {
+                kind: "network_interrupted",
+                handle: Some(1)
+            }
+        );
+        let mut log = SessionLog::default();
+        log.record(entry);
+        let export = log.to_jsonl();
+        assert!(!export.contains(&player_id.0.to_string()));
+    }
+}
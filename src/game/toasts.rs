@@ -15,6 +15,16 @@ impl Toasts {
                 .show_progress(true),
         });
     }
+
+    pub fn success(&mut self, text: WidgetText) {
+        self.0.add(Toast {
+            text,
+            kind: egui_toast::ToastKind::Success,
+            options: egui_toast::ToastOptions::default()
+                .duration_in_seconds(5.0)
+                .show_progress(true),
+        });
+    }
 }
 
 impl Default for Toasts {
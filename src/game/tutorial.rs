@@ -0,0 +1,482 @@
+//! Scripted first-run tutorial.
+//!
+//! The tutorial borrows the local practice arena, so it needs no network
+//! session and nothing in it is rollback-authoritative. A small authored map
+//! places a waypoint, the speed and shield pickups and a trap guarding a
+//! second marker, each of which appears only once its step begins. Steps
+//! finish when the player actually does what the prompt asks, and the
+//! profile remembers whether the tutorial was finished or skipped so the
+//! first-run prompt is only offered once.
+
+use bevy::prelude::*;
+
+use super::{
+    assets::{
+        procedural::{shield_pickup_color, speed_pickup_color, trap_color, PICKUP_SIZE, TRAP_SIZE},
+        textures::ImageAssets,
+    },
+    player::{SPEED_BOOST_FRAMES, STOCK_LIVES},
+    practice::{
+        queue_respawn, spawn_practice_arena, PracticeOwned, PracticePlayer, PracticeScore,
+        PracticeSpawn, Shot, ARENA_MAX, ARENA_MIN, FIRE_COOLDOWN, PLAYER_COLOR, PLAYER_RADIUS,
+    },
+    session::MATCH_POINTS_TO_WIN,
+};
+
+/// The tutorial arena, top row first: `P` is the start, `M` the waypoint,
+/// `S` the speed pickup, `H` the shield pickup, `T` the trap and `G` the
+/// marker behind it.
+const TUTORIAL_MAP: [&str; 7] = [
+    ".........",
+    ".P.....M.",
+    ".........",
+    "....S....",
+    ".........",
+    ".H..T..G.",
+    ".........",
+];
+/// Shots to fire while the blaster cools down before the next step.
+pub const COOLDOWN_SHOTS: u32 = 6;
+const WAYPOINT_SIZE: f32 = 0.6;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    #[default]
+    Move,
+    Fire,
+    Cooldown,
+    SpeedPickup,
+    ShieldPickup,
+    Trap,
+    WinMatch,
+    Complete,
+}
+
+impl TutorialStep {
+    /// Steps with something to do; `Complete` is not counted.
+    pub const COUNT: usize = 7;
+
+    pub fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Move => "Move",
+            Self::Fire => "Fire",
+            Self::Cooldown => "Blaster cooldown",
+            Self::SpeedPickup => "Speed pickup",
+            Self::ShieldPickup => "Shield pickup",
+            Self::Trap => "Traps",
+            Self::WinMatch => "Winning",
+            Self::Complete => "Tutorial complete",
+        }
+    }
+
+    pub fn prompt(self) -> String {
+        match self {
+            Self::Move => "Move with WASD or the arrow keys (touch: drag on the LEFT side) \
+                           and reach the green marker."
+                .into(),
+            Self::Fire => "Shots fly the way you last moved. Face a target and fire with \
+                           Space or Enter (touch: hold on the RIGHT side) to hit it."
+                .into(),
+            Self::Cooldown => format!(
+                "Your blaster needs {FIRE_COOLDOWN:.2}s between shots, so holding fire \
+                 will not shoot any faster. Fire {COOLDOWN_SHOTS} more shots."
+            ),
+            Self::SpeedPickup => format!(
+                "Blue pickups make you faster for {} seconds. Grab the speed pickup.",
+                SPEED_BOOST_FRAMES / 60
+            ),
+            Self::ShieldPickup => {
+                "Gold pickups give you a shield that blocks one hit. Grab the shield pickup.".into()
+            }
+            Self::Trap => "Red traps eliminate you the moment you touch one, shield or not. \
+                           Walk around the trap to reach the green marker."
+                .into(),
+            Self::WinMatch => format!(
+                "In a match, the last ghost standing wins the round and scores a point; \
+                 the first to {MATCH_POINTS_TO_WIN} points wins the match. In rooms with \
+                 stock lives each ghost has {STOCK_LIVES} and is only out once the last \
+                 one is lost. Hit {MATCH_POINTS_TO_WIN} targets to win this practice match."
+            ),
+            Self::Complete => "You know the basics. Find a match from the main menu!".into(),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Move => Self::Fire,
+            Self::Fire => Self::Cooldown,
+            Self::Cooldown => Self::SpeedPickup,
+            Self::SpeedPickup => Self::ShieldPickup,
+            Self::ShieldPickup => Self::Trap,
+            Self::Trap => Self::WinMatch,
+            Self::WinMatch | Self::Complete => Self::Complete,
+        }
+    }
+}
+
+/// Something the player did that a step may be waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialEvent {
+    ReachedWaypoint,
+    Fired,
+    HitTarget,
+    CollectedSpeed,
+    CollectedShield,
+    ReachedTrapGoal,
+}
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct TutorialProgress {
+    pub step: TutorialStep,
+    /// Count toward the current step's goal, for steps that need several.
+    pub count: u32,
+    pub shielded: bool,
+    seen_hits: u32,
+}
+
+impl TutorialProgress {
+    pub fn is_complete(&self) -> bool {
+        self.step == TutorialStep::Complete
+    }
+
+    /// How many repetitions the current step needs, when it needs several.
+    pub fn goal(&self) -> Option<u32> {
+        match self.step {
+            TutorialStep::Cooldown => Some(COOLDOWN_SHOTS),
+            TutorialStep::WinMatch => Some(MATCH_POINTS_TO_WIN),
+            _ => None,
+        }
+    }
+
+    /// Applies `event` and returns whether it finished the current step.
+    /// Events the current step is not waiting for are ignored.
+    pub fn handle(&mut self, event: TutorialEvent) -> bool {
+        let finished = match (self.step, event) {
+            (TutorialStep::Move, TutorialEvent::ReachedWaypoint)
+            | (TutorialStep::Fire, TutorialEvent::HitTarget)
+            | (TutorialStep::SpeedPickup, TutorialEvent::CollectedSpeed)
+            | (TutorialStep::ShieldPickup, TutorialEvent::CollectedShield)
+            | (TutorialStep::Trap, TutorialEvent::ReachedTrapGoal) => true,
+            (TutorialStep::Cooldown, TutorialEvent::Fired)
+            | (TutorialStep::WinMatch, TutorialEvent::HitTarget) => {
+                self.count += 1;
+                self.goal().is_some_and(|goal| self.count >= goal)
+            }
+            _ => false,
+        };
+        if finished {
+            self.step = self.step.next();
+            self.count = 0;
+        }
+        finished
+    }
+}
+
+/// Whether the player has been through the tutorial, stored in the profile.
+/// Ordered so that merging keeps the furthest a profile has got.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TutorialStatus {
+    #[default]
+    New,
+    Skipped,
+    Completed,
+}
+
+impl TutorialStatus {
+    /// Profiles that already played online are not new players, even if they
+    /// predate the tutorial.
+    pub fn should_offer(self, rounds_played: u64) -> bool {
+        self == Self::New && rounds_played == 0
+    }
+
+    pub fn encode(self) -> &'static str {
+        match self {
+            Self::New => "",
+            Self::Skipped => "skipped",
+            Self::Completed => "done",
+        }
+    }
+
+    pub fn decode(value: &str) -> Self {
+        match value {
+            "skipped" => Self::Skipped,
+            "done" => Self::Completed,
+            _ => Self::New,
+        }
+    }
+}
+
+/// A status the first-run prompt or a finished tutorial asks to save.
+#[derive(Resource, Debug, Default)]
+pub struct TutorialStatusRequest(pub Option<TutorialStatus>);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialItem {
+    Waypoint,
+    SpeedPickup,
+    ShieldPickup,
+    Trap,
+    TrapGoal,
+}
+
+impl TutorialItem {
+    fn tile(self) -> char {
+        match self {
+            Self::Waypoint => 'M',
+            Self::SpeedPickup => 'S',
+            Self::ShieldPickup => 'H',
+            Self::Trap => 'T',
+            Self::TrapGoal => 'G',
+        }
+    }
+
+    fn look(self) -> (Color, f32) {
+        match self {
+            Self::Waypoint | Self::TrapGoal => (Color::rgba(0.35, 0.95, 0.45, 0.7), WAYPOINT_SIZE),
+            Self::SpeedPickup => (speed_pickup_color(), PICKUP_SIZE),
+            Self::ShieldPickup => (shield_pickup_color(), PICKUP_SIZE),
+            Self::Trap => (trap_color(), TRAP_SIZE),
+        }
+    }
+
+    /// The items a step places in the arena.
+    fn for_step(step: TutorialStep) -> &'static [Self] {
+        match step {
+            TutorialStep::Move => &[Self::Waypoint],
+            TutorialStep::SpeedPickup => &[Self::SpeedPickup],
+            TutorialStep::ShieldPickup => &[Self::ShieldPickup],
+            TutorialStep::Trap => &[Self::Trap, Self::TrapGoal],
+            _ => &[],
+        }
+    }
+}
+
+/// Center of the first `tile` in the authored map, in arena coordinates.
+fn tile_position(tile: char) -> Option<Vec2> {
+    let rows = TUTORIAL_MAP.len();
+    let columns = TUTORIAL_MAP[0].len();
+    let cell = (ARENA_MAX - ARENA_MIN) / Vec2::new(columns as f32, rows as f32);
+    TUTORIAL_MAP.iter().enumerate().find_map(|(row, line)| {
+        let column = line.chars().position(|symbol| symbol == tile)?;
+        let from_bottom = (rows - 1 - row) as f32;
+        Some(ARENA_MIN + (Vec2::new(column as f32, from_bottom) + 0.5) * cell)
+    })
+}
+
+fn touches_item(player: Vec2, item: Vec2, size: f32) -> bool {
+    player.distance_squared(item) <= (PLAYER_RADIUS + size * 0.5).powi(2)
+}
+
+fn spawn_item(commands: &mut Commands, item: TutorialItem) {
+    let Some(position) = tile_position(item.tile()) else {
+        return;
+    };
+    let (color, size) = item.look();
+    commands.spawn((
+        PracticeOwned,
+        item,
+        SpriteBundle {
+            transform: Transform::from_translation(position.extend(25.0)),
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            ..default()
+        },
+        Name::new("tutorial: item"),
+    ));
+}
+
+pub fn setup_tutorial(mut commands: Commands, images: Res<ImageAssets>) {
+    let start = tile_position('P').unwrap_or_default();
+    spawn_practice_arena(&mut commands, &images, start);
+    // Targets only appear once the firing step begins.
+    commands.insert_resource(PracticeSpawn::default());
+    commands.insert_resource(TutorialProgress::default());
+    spawn_item(&mut commands, TutorialItem::Waypoint);
+}
+
+/// Watches the practice arena for what the current step asks for and sets up
+/// the next step once it is done.
+pub fn advance_tutorial(
+    mut commands: Commands,
+    mut progress: ResMut<TutorialProgress>,
+    mut spawn: ResMut<PracticeSpawn>,
+    score: Res<PracticeScore>,
+    fired: Query<(), Added<Shot>>,
+    mut players: Query<(&mut Transform, &mut PracticePlayer, &mut Sprite)>,
+    items: Query<(Entity, &Transform, &TutorialItem), Without<PracticePlayer>>,
+) {
+    let mut events = Vec::new();
+    events.extend(fired.iter().map(|()| TutorialEvent::Fired));
+    let hits = score.hits.saturating_sub(progress.seen_hits);
+    progress.seen_hits = score.hits;
+    events.extend((0..hits).map(|_| TutorialEvent::HitTarget));
+
+    let Ok((mut transform, mut player, mut sprite)) = players.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    for (entity, item_transform, item) in &items {
+        let (_, size) = item.look();
+        if !touches_item(position, item_transform.translation.truncate(), size) {
+            continue;
+        }
+        let event = match item {
+            TutorialItem::Waypoint => TutorialEvent::ReachedWaypoint,
+            TutorialItem::SpeedPickup => {
+                player.boost(SPEED_BOOST_FRAMES as f32 / 60.0);
+                TutorialEvent::CollectedSpeed
+            }
+            TutorialItem::ShieldPickup => {
+                progress.shielded = true;
+                TutorialEvent::CollectedShield
+            }
+            TutorialItem::TrapGoal => TutorialEvent::ReachedTrapGoal,
+            TutorialItem::Trap => {
+                // In a match the trap would have eliminated the ghost: start
+                // over from the spawn point and try again.
+                let start = tile_position('P').unwrap_or_default();
+                transform.translation.x = start.x;
+                transform.translation.y = start.y;
+                continue;
+            }
+        };
+        commands.entity(entity).despawn_recursive();
+        events.push(event);
+    }
+    sprite.color = if progress.shielded {
+        shield_pickup_color()
+    } else {
+        PLAYER_COLOR
+    };
+
+    for event in events {
+        if !progress.handle(event) {
+            continue;
+        }
+        if progress.step == TutorialStep::Fire {
+            queue_respawn(&mut spawn);
+            queue_respawn(&mut spawn);
+        }
+        for &item in TutorialItem::for_step(progress.step) {
+            spawn_item(&mut commands, item);
+        }
+    }
+    if progress.step != TutorialStep::Trap {
+        for (entity, _, item) in &items {
+            if *item == TutorialItem::Trap {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Leaving early counts as skipping, so the first-run prompt is not offered
+/// again; finishing marks the tutorial done.
+pub fn finish_tutorial(
+    progress: Option<Res<TutorialProgress>>,
+    mut request: ResMut<TutorialStatusRequest>,
+) {
+    let Some(progress) = progress else {
+        return;
+    };
+    request.0 = Some(if progress.is_complete() {
+        TutorialStatus::Completed
+    } else {
+        TutorialStatus::Skipped
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_finish_only_on_the_event_they_ask_for() {
+        let mut progress = TutorialProgress::default();
+        assert!(!progress.handle(TutorialEvent::HitTarget));
+        assert!(!progress.handle(TutorialEvent::CollectedShield));
+        assert_eq!(progress.step, TutorialStep::Move);
+
+        assert!(progress.handle(TutorialEvent::ReachedWaypoint));
+        assert!(progress.handle(TutorialEvent::HitTarget));
+        assert_eq!(progress.step, TutorialStep::Cooldown);
+        for _ in 1..COOLDOWN_SHOTS {
+            assert!(!progress.handle(TutorialEvent::Fired));
+        }
+        assert_eq!(progress.count, COOLDOWN_SHOTS - 1);
+        assert!(progress.handle(TutorialEvent::Fired));
+        assert_eq!(progress.count, 0);
+
+        assert!(!progress.handle(TutorialEvent::ReachedTrapGoal));
+        assert!(progress.handle(TutorialEvent::CollectedSpeed));
+        assert!(progress.handle(TutorialEvent::CollectedShield));
+        assert!(!progress.handle(TutorialEvent::ReachedWaypoint));
+        assert!(progress.handle(TutorialEvent::ReachedTrapGoal));
+        assert_eq!(progress.goal(), Some(MATCH_POINTS_TO_WIN));
+        for _ in 0..MATCH_POINTS_TO_WIN {
+            progress.handle(TutorialEvent::HitTarget);
+        }
+        assert!(progress.is_complete());
+        assert_eq!(progress.step.number(), TutorialStep::COUNT + 1);
+        assert!(!progress.handle(TutorialEvent::HitTarget));
+        assert!(progress.is_complete());
+    }
+
+    #[test]
+    fn the_authored_map_places_every_tile_inside_the_arena() {
+        let lower = ARENA_MIN + Vec2::splat(PLAYER_RADIUS);
+        let upper = ARENA_MAX - Vec2::splat(PLAYER_RADIUS);
+        let start = tile_position('P').unwrap();
+        for item in [
+            TutorialItem::Waypoint,
+            TutorialItem::SpeedPickup,
+            TutorialItem::ShieldPickup,
+            TutorialItem::Trap,
+            TutorialItem::TrapGoal,
+        ] {
+            let position = tile_position(item.tile()).unwrap();
+            assert!(position.cmpge(lower).all() && position.cmple(upper).all());
+            // Nothing is collected just by spawning.
+            assert!(!touches_item(start, position, item.look().1));
+        }
+        assert!(TUTORIAL_MAP
+            .iter()
+            .all(|row| row.len() == TUTORIAL_MAP[0].len()));
+        assert_eq!(tile_position('Z'), None);
+    }
+
+    #[test]
+    fn the_trap_blocks_the_straight_path_but_can_be_walked_around() {
+        let shield = tile_position('H').unwrap();
+        let trap = tile_position('T').unwrap();
+        let goal = tile_position('G').unwrap();
+        assert_eq!((shield.y, goal.y), (trap.y, trap.y));
+        assert!(shield.x < trap.x && trap.x < goal.x);
+        let row = (ARENA_MAX.y - ARENA_MIN.y) / TUTORIAL_MAP.len() as f32;
+        assert!(!touches_item(trap + Vec2::new(0.0, row), trap, TRAP_SIZE));
+        assert!(!touches_item(trap - Vec2::new(0.0, row), trap, TRAP_SIZE));
+    }
+
+    #[test]
+    fn status_round_trips_and_only_new_players_are_offered_the_tutorial() {
+        for status in [
+            TutorialStatus::New,
+            TutorialStatus::Skipped,
+            TutorialStatus::Completed,
+        ] {
+            assert_eq!(TutorialStatus::decode(status.encode()), status);
+        }
+        assert_eq!(TutorialStatus::decode("later"), TutorialStatus::New);
+        assert!(TutorialStatus::New.should_offer(0));
+        assert!(!TutorialStatus::New.should_offer(12));
+        assert!(!TutorialStatus::Skipped.should_offer(0));
+        assert!(TutorialStatus::Completed > TutorialStatus::Skipped);
+    }
+}